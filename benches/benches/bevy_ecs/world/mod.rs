@@ -1,10 +1,12 @@
 use criterion::criterion_group;
 
 mod commands;
+mod snapshot;
 mod spawn;
 mod world_get;
 
 use commands::*;
+use snapshot::*;
 use spawn::*;
 use world_get::*;
 
@@ -30,4 +32,7 @@ criterion_group!(
     query_get_many::<2>,
     query_get_many::<5>,
     query_get_many::<10>,
+    world_snapshot_capture,
+    world_snapshot_restore,
+    world_snapshot_diff,
 );
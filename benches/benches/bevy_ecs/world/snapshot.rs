@@ -0,0 +1,107 @@
+use bevy_ecs::{
+    prelude::*,
+    snapshot::{SnapshotFilter, WorldSnapshot},
+};
+use bevy_reflect::{Reflect, TypeRegistry};
+use criterion::{black_box, Criterion};
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Position {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Velocity {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[derive(Component, Default)]
+struct NotCaptured(f32);
+
+fn setup(entity_count: u32) -> (World, TypeRegistry) {
+    let mut registry = TypeRegistry::default();
+    registry.register::<Position>();
+    registry.register::<Velocity>();
+    let mut world = World::default();
+    world.spawn_batch((0..entity_count).map(|_| {
+        (
+            Position::default(),
+            Velocity::default(),
+            NotCaptured::default(),
+        )
+    }));
+    (world, registry)
+}
+
+fn move_half(world: &mut World) {
+    let mut query = world.query::<(&mut Position, &Velocity)>();
+    for (mut position, _) in query.iter_mut(world).step_by(2) {
+        position.x += 1.0;
+    }
+}
+
+pub fn world_snapshot_capture(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("world_snapshot_capture");
+    group.warm_up_time(std::time::Duration::from_millis(500));
+    group.measurement_time(std::time::Duration::from_secs(4));
+
+    for entity_count in [100, 1_000, 10_000] {
+        group.bench_function(format!("{}_entities", entity_count), |bencher| {
+            let (world, registry) = setup(entity_count);
+            bencher.iter(|| {
+                black_box(WorldSnapshot::capture(
+                    &world,
+                    &registry,
+                    SnapshotFilter::default(),
+                ));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+pub fn world_snapshot_restore(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("world_snapshot_restore");
+    group.warm_up_time(std::time::Duration::from_millis(500));
+    group.measurement_time(std::time::Duration::from_secs(4));
+
+    for entity_count in [100, 1_000, 10_000] {
+        group.bench_function(format!("{}_entities", entity_count), |bencher| {
+            let (mut world, registry) = setup(entity_count);
+            let snapshot = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+            bencher.iter(|| {
+                move_half(&mut world);
+                snapshot.restore(&mut world, &registry).unwrap();
+            });
+        });
+    }
+
+    group.finish();
+}
+
+pub fn world_snapshot_diff(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("world_snapshot_diff");
+    group.warm_up_time(std::time::Duration::from_millis(500));
+    group.measurement_time(std::time::Duration::from_secs(4));
+
+    for entity_count in [100, 1_000, 10_000] {
+        group.bench_function(format!("{}_entities", entity_count), |bencher| {
+            let (mut world, registry) = setup(entity_count);
+            let old = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+            move_half(&mut world);
+            let new = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+            bencher.iter(|| {
+                black_box(old.diff(&new));
+            });
+        });
+    }
+
+    group.finish();
+}
@@ -1,17 +1,17 @@
 use crate::{CoreStage, Plugin, PluginGroup, StartupSchedule, StartupStage};
 pub use bevy_derive::AppLabel;
 use bevy_ecs::{
-    event::{Event, Events},
+    event::{Event, EventRetention, Events},
     prelude::FromWorld,
     schedule::{
-        IntoSystemDescriptor, Schedule, ShouldRun, Stage, StageLabel, State, StateData, SystemSet,
-        SystemStage,
+        IntoSystemDescriptor, Schedule, ScheduleGraph, ShouldRun, Stage, StageLabel, State,
+        StateData, SystemSet, SystemStage,
     },
-    system::Resource,
+    system::{CommandErrorHandler, DefaultCommandErrorHandler, Resource},
     world::World,
 };
 use bevy_utils::{tracing::debug, HashMap, HashSet};
-use std::fmt::Debug;
+use std::{any::TypeId, fmt::Debug, io, path::Path};
 
 #[cfg(feature = "trace")]
 use bevy_utils::tracing::info_span;
@@ -27,8 +27,42 @@ bevy_utils::define_label!(
 #[derive(Resource, Clone, bevy_derive::Deref, bevy_derive::DerefMut, Default)]
 pub struct AppTypeRegistry(pub bevy_reflect::TypeRegistryArc);
 
+/// A [`Resource`] giving systems access to the [`schedule_graphs`](App::schedule_graphs) of the
+/// [`App`] running them.
+///
+/// Systems can't access the schedule they run in, so they [`request`](Self::request) the graphs,
+/// which the [`App`] then writes at the end of its current [`update`](App::update).
+#[derive(Resource, Debug, Default)]
+pub struct ScheduleGraphs {
+    requested: bool,
+    graphs: Option<Vec<(String, ScheduleGraph)>>,
+}
+
+impl ScheduleGraphs {
+    /// Asks the [`App`] to write its schedule graphs at the end of the current update.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Whether graphs were requested and haven't been written yet.
+    pub fn is_requested(&self) -> bool {
+        self.requested
+    }
+
+    /// Takes the graphs written by the [`App`] since the last request, if any.
+    pub fn take(&mut self) -> Option<Vec<(String, ScheduleGraph)>> {
+        self.graphs.take()
+    }
+}
+
 pub(crate) enum AppError {
-    DuplicatePlugin { plugin_name: String },
+    DuplicatePlugin {
+        plugin_name: String,
+    },
+    MissingDependencies {
+        plugin_name: String,
+        dependencies: Vec<&'static str>,
+    },
 }
 
 #[allow(clippy::needless_doctest_main)]
@@ -153,6 +187,12 @@ impl App {
         for sub_app in self.sub_apps.values_mut() {
             (sub_app.runner)(&mut self.world, &mut sub_app.app);
         }
+        if matches!(self.world.get_resource::<ScheduleGraphs>(), Some(graphs) if graphs.requested) {
+            let graphs = self.schedule_graphs();
+            let mut schedule_graphs = self.world.resource_mut::<ScheduleGraphs>();
+            schedule_graphs.requested = false;
+            schedule_graphs.graphs = Some(graphs);
+        }
     }
 
     /// Starts the application by calling the app's [runner function](Self::set_runner).
@@ -681,6 +721,39 @@ impl App {
         self
     }
 
+    /// Setup the application to manage events of type `T`, retaining them until all their
+    /// registered readers have read them.
+    ///
+    /// Like [`add_event`](Self::add_event), but the [`Events::<T>`] are created with
+    /// [`Events::with_retention`], so the [`EventReader`](bevy_ecs::event::EventReader)s of
+    /// systems that don't run every frame don't miss events. If the events were already added,
+    /// the retention is applied to them with [`Events::set_retention`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::{event::{EventOverflow, EventRetention}, prelude::*};
+    /// #
+    /// # struct MyEvent;
+    /// # let mut app = App::new();
+    /// #
+    /// app.add_event_with_retention::<MyEvent>(EventRetention::new(1024, EventOverflow::DropOldest));
+    /// ```
+    pub fn add_event_with_retention<T>(&mut self, retention: EventRetention) -> &mut Self
+    where
+        T: Event,
+    {
+        match self.world.get_resource_mut::<Events<T>>() {
+            Some(mut events) => events.set_retention(retention),
+            None => {
+                self.insert_resource(Events::<T>::with_retention(retention))
+                    .add_system_to_stage(CoreStage::First, Events::<T>::update_system);
+            }
+        }
+        self
+    }
+
     /// Inserts a [`Resource`] to the current [`App`] and overwrites any [`Resource`] previously added of the same type.
     ///
     /// A [`Resource`] in Bevy represents globally unique data. [`Resource`]s must be added to Bevy apps
@@ -775,6 +848,24 @@ impl App {
         self
     }
 
+    /// Sets the [`CommandErrorHandler`] of the fallible commands of the app, like
+    /// [`EntityCommands::try_insert`](bevy_ecs::system::EntityCommands::try_insert), that don't
+    /// set one on their [`Commands`](bevy_ecs::system::Commands).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::system::CommandErrorHandler;
+    /// #
+    /// App::new().set_command_error_handler(CommandErrorHandler::Ignore);
+    /// ```
+    pub fn set_command_error_handler(&mut self, error_handler: CommandErrorHandler) -> &mut Self {
+        self.world
+            .insert_resource(DefaultCommandErrorHandler(error_handler));
+        self
+    }
+
     /// Sets the function that will be called when the app is run.
     ///
     /// The runner function `run_fn` is called only once by [`App::run`]. If the
@@ -831,7 +922,8 @@ impl App {
     ///
     /// # Panics
     ///
-    /// Panics if the plugin was already added to the application.
+    /// Panics if the plugin was already added to the application, or if one of its required
+    /// [dependencies](Plugin::dependencies) wasn't.
     pub fn add_plugin<T>(&mut self, plugin: T) -> &mut Self
     where
         T: Plugin,
@@ -842,6 +934,14 @@ impl App {
                 "Error adding plugin {}: : plugin was already added in application",
                 plugin_name
             ),
+            Err(AppError::MissingDependencies {
+                plugin_name,
+                dependencies,
+            }) => panic!(
+                "Error adding plugin {}: it requires {} to be added before it",
+                plugin_name,
+                dependencies.join(", ")
+            ),
         }
     }
 
@@ -851,6 +951,20 @@ impl App {
         plugin: Box<dyn Plugin>,
     ) -> Result<&mut Self, AppError> {
         debug!("added plugin: {}", plugin.name());
+        let missing: Vec<_> = plugin
+            .dependencies()
+            .iter()
+            .filter(|dependency| {
+                !dependency.is_optional() && !self.is_plugin_type_added(dependency.type_id())
+            })
+            .map(|dependency| dependency.name())
+            .collect();
+        if !missing.is_empty() {
+            Err(AppError::MissingDependencies {
+                plugin_name: plugin.name().to_string(),
+                dependencies: missing,
+            })?;
+        }
         if plugin.is_unique() && !self.plugin_name_added.insert(plugin.name().to_string()) {
             Err(AppError::DuplicatePlugin {
                 plugin_name: plugin.name().to_string(),
@@ -874,6 +988,12 @@ impl App {
             .any(|p| p.downcast_ref::<T>().is_some())
     }
 
+    pub(crate) fn is_plugin_type_added(&self, type_id: TypeId) -> bool {
+        self.plugin_registry
+            .iter()
+            .any(|p| p.as_any().type_id() == type_id)
+    }
+
     /// Returns a vector of references to any plugins of type `T` that have been added.
     ///
     /// This can be used to read the settings of any already added plugins.
@@ -1044,6 +1164,43 @@ impl App {
             .map(|sub_app| &sub_app.app)
             .ok_or(label)
     }
+
+    /// Returns the structure of the [`Schedule`] of this app, named `"main"`, and of the schedules
+    /// of its sub-apps, such as the render app, named by their label.
+    ///
+    /// Stages are only sorted and checked for ambiguities once they have run, so this is best
+    /// called after [`App::update`]. See [`Schedule::graph`].
+    pub fn schedule_graphs(&self) -> Vec<(String, ScheduleGraph)> {
+        let mut sub_apps: Vec<_> = self
+            .sub_apps
+            .iter()
+            .map(|(label, sub_app)| {
+                (
+                    label.as_str().to_owned(),
+                    sub_app.app.schedule.graph(&sub_app.app.world),
+                )
+            })
+            .collect();
+        sub_apps.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut graphs = vec![("main".to_owned(), self.schedule.graph(&self.world))];
+        graphs.extend(sub_apps);
+        graphs
+    }
+
+    /// Writes the [`schedule_graphs`](Self::schedule_graphs) of this app in `directory`, as
+    /// `<name>.dot` and `<name>.json` files. The directory is created if needed.
+    ///
+    /// The DOT files can be rendered with Graphviz, for example with `dot -Tsvg main.dot`.
+    pub fn dump_schedule_graphs(&self, directory: impl AsRef<Path>) -> io::Result<()> {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory)?;
+        for (name, graph) in self.schedule_graphs() {
+            std::fs::write(directory.join(format!("{name}.dot")), graph.to_dot())?;
+            std::fs::write(directory.join(format!("{name}.json")), graph.to_json())?;
+        }
+        Ok(())
+    }
 }
 
 fn run_once(mut app: App) {
@@ -1061,7 +1218,12 @@ pub struct AppExit;
 
 #[cfg(test)]
 mod tests {
-    use crate::{App, Plugin};
+    use crate::{App, Plugin, PluginDependency, ScheduleGraphs};
+    use bevy_ecs::{
+        event::{EventOverflow, EventRetention, Events},
+        schedule::SystemStage,
+        system::ResMut,
+    };
 
     struct PluginA;
     impl Plugin for PluginA {
@@ -1103,4 +1265,77 @@ mod tests {
     fn can_add_twice_the_same_plugin_not_unique() {
         App::new().add_plugin(PluginD).add_plugin(PluginD);
     }
+
+    struct PluginE;
+    impl Plugin for PluginE {
+        fn build(&self, _app: &mut crate::App) {}
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![
+                PluginDependency::required::<PluginA>(),
+                PluginDependency::optional::<PluginB>(),
+            ]
+        }
+    }
+
+    #[test]
+    fn can_add_plugin_after_its_dependencies() {
+        App::new().add_plugin(PluginA).add_plugin(PluginE);
+    }
+
+    #[test]
+    #[should_panic(expected = "it requires bevy_app::app::tests::PluginA to be added before it")]
+    fn cant_add_plugin_before_its_dependencies() {
+        App::new().add_plugin(PluginE).add_plugin(PluginA);
+    }
+
+    #[test]
+    fn schedule_graphs_of_sub_apps() {
+        fn main_system() {}
+        fn sub_system() {}
+
+        let mut sub_app = App::empty();
+        sub_app.add_stage("sub_stage", SystemStage::parallel().with_system(sub_system));
+        let mut app = App::new();
+        app.add_system(main_system)
+            .add_sub_app("sub", sub_app, |_, sub_app| sub_app.update());
+        app.update();
+
+        let graphs = app.schedule_graphs();
+        let names: Vec<_> = graphs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["main", "sub"]);
+        assert!(graphs[0].1.to_json().contains("main_system"));
+        assert!(graphs[1].1.to_dot().contains("sub_system"));
+    }
+
+    #[test]
+    fn requested_schedule_graphs() {
+        fn request_graphs(mut graphs: ResMut<ScheduleGraphs>) {
+            if graphs.take().is_none() {
+                graphs.request();
+            }
+        }
+
+        let mut app = App::new();
+        app.init_resource::<ScheduleGraphs>()
+            .add_system(request_graphs);
+        app.update();
+        assert!(!app.world.resource::<ScheduleGraphs>().is_requested());
+        let graphs = app.world.resource_mut::<ScheduleGraphs>().take().unwrap();
+        assert_eq!(graphs[0].0, "main");
+        assert!(graphs[0].1.to_json().contains("request_graphs"));
+    }
+
+    #[test]
+    fn retention_applies_to_added_events() {
+        struct MyEvent;
+
+        let retention = EventRetention::new(8, EventOverflow::DropOldest);
+        let mut app = App::new();
+        app.add_event::<MyEvent>()
+            .add_event_with_retention::<MyEvent>(retention);
+        assert_eq!(
+            app.world.resource::<Events<MyEvent>>().retention(),
+            Some(retention)
+        );
+    }
 }
@@ -1,7 +1,38 @@
-use crate::{app::AppExit, App};
-use serde::Deserialize;
+//! Scripted CI testing, enabled by the `bevy_ci_testing` feature.
+//!
+//! The [`CiTestingConfig`] is read from the file in the `CI_TESTING_CONFIG` environment variable,
+//! or from `ci_testing_config.ron`, and can both exit the app after a number of frames and run a
+//! timeline of [`CiTestingAction`]s:
+//!
+//! ```ron
+//! (
+//!     exit_after: Some(60),
+//!     events: [
+//!         (10, SendEvent(
+//!             event: "KeyboardInput",
+//!             value: "(scan_code: 57, key_code: Some(Space), state: Pressed)",
+//!         )),
+//!         (20, SetTime(1.5)),
+//!         (30, Screenshot("screenshot-30.png")),
+//!         (40, AssertResource(resource: "Scoreboard", path: "score", value: "0")),
+//!     ],
+//! )
+//! ```
 
-use bevy_ecs::prelude::Resource;
+use crate::{app::AppExit, App, AppTypeRegistry, CoreStage};
+use serde::{de::DeserializeSeed, Deserialize};
+
+use bevy_ecs::{
+    event::Event,
+    prelude::{Local, Resource},
+    reflect::ReflectResource,
+    schedule::{IntoSystemDescriptor, SystemLabel},
+    world::World,
+};
+use bevy_reflect::{
+    serde::TypedReflectDeserializer, FromReflect, FromType, GetPath, GetTypeRegistration, Reflect,
+    TypeRegistration, TypeRegistry,
+};
 use bevy_utils::tracing::info;
 
 /// A configuration struct for automated CI testing.
@@ -9,10 +40,91 @@ use bevy_utils::tracing::info;
 /// It gets used when the `bevy_ci_testing` feature is enabled to automatically
 /// exit a Bevy app when run through the CI. This is needed because otherwise
 /// Bevy apps would be stuck in the game loop and wouldn't allow the CI to progress.
+///
+/// It can also script the app with a timeline of [`CiTestingEvent`]s, so examples double as
+/// integration tests.
 #[derive(Deserialize, Resource)]
 pub struct CiTestingConfig {
     /// The number of frames after which Bevy should exit.
     pub exit_after: Option<u32>,
+    /// The actions to run, and the frames to run them at.
+    #[serde(default)]
+    pub events: Vec<CiTestingEvent>,
+}
+
+/// A [`CiTestingAction`] to run at the start of the given frame, counted from 0.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CiTestingEvent(pub u32, pub CiTestingAction);
+
+/// An action of the [`CiTestingConfig`] timeline.
+///
+/// Every action is also sent as an event at the start of its frame, in [`CoreStage::First`], so
+/// that plugins can handle their own: `bevy_time` handles [`SetTime`](Self::SetTime) and
+/// `bevy_render` handles [`Screenshot`](Self::Screenshot).
+#[derive(Deserialize, Debug, Clone)]
+pub enum CiTestingAction {
+    /// Sends an event of a type registered with [`App::register_ci_testing_event`].
+    SendEvent {
+        /// The name of the event type, either the full type name or the short one.
+        event: String,
+        /// The event, in RON.
+        value: String,
+    },
+    /// Freezes `Time` at the given number of seconds since startup, until the next `SetTime`.
+    SetTime(f64),
+    /// Renders the frame of the cameras targeting the primary window to an offscreen image,
+    /// and saves it at the given path.
+    Screenshot(String),
+    /// Panics unless a value of a resource registered with
+    /// [`ReflectResource`] equals the given one.
+    AssertResource {
+        /// The name of the resource type, either the full type name or the short one.
+        resource: String,
+        /// The path of the value in the resource, see [`GetPath`]. An empty path is the
+        /// resource itself.
+        path: String,
+        /// The expected value, in RON.
+        value: String,
+    },
+}
+
+/// The label of the system running the actions of the [`CiTestingConfig`] timeline.
+///
+/// It runs at the start of [`CoreStage::First`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub struct CiTestingSystem;
+
+/// Type data sending events of the registered type from the [`CiTestingConfig`] timeline.
+#[derive(Clone)]
+struct ReflectCiTestingEvent {
+    /// Sends the event, or returns `false` if the value can't be converted to it.
+    send: fn(&mut World, &dyn Reflect) -> bool,
+}
+
+impl<E: Event + FromReflect> FromType<E> for ReflectCiTestingEvent {
+    fn from_type() -> Self {
+        ReflectCiTestingEvent {
+            send: |world, value| match E::from_reflect(value) {
+                Some(event) => {
+                    world.send_event(event);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+}
+
+impl App {
+    /// Allows [`CiTestingAction::SendEvent`] to send events of type `E`.
+    ///
+    /// The event type must have been added with [`App::add_event`].
+    pub fn register_ci_testing_event<E: Event + FromReflect + GetTypeRegistration>(
+        &mut self,
+    ) -> &mut Self {
+        self.register_type::<E>()
+            .register_type_data::<E, ReflectCiTestingEvent>()
+    }
 }
 
 fn ci_testing_exit_after(
@@ -29,6 +141,143 @@ fn ci_testing_exit_after(
     *current_frame += 1;
 }
 
+fn ci_testing_run_timeline(world: &mut World, mut current_frame: Local<u32>) {
+    let frame = *current_frame;
+    *current_frame += 1;
+
+    let actions: Vec<CiTestingAction> = world
+        .resource::<CiTestingConfig>()
+        .events
+        .iter()
+        .filter(|CiTestingEvent(event_frame, _)| *event_frame == frame)
+        .map(|CiTestingEvent(_, action)| action.clone())
+        .collect();
+    if actions.is_empty() {
+        return;
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    for action in actions {
+        info!("Running CI testing action at frame {}: {:?}", frame, action);
+        match &action {
+            CiTestingAction::SendEvent { event, value } => {
+                send_event(world, &registry, event, value);
+            }
+            CiTestingAction::AssertResource {
+                resource,
+                path,
+                value,
+            } => {
+                assert_resource(world, &registry, resource, path, value);
+            }
+            CiTestingAction::SetTime(_) | CiTestingAction::Screenshot(_) => {}
+        }
+        world.send_event(action);
+    }
+}
+
+fn get_registration<'a>(registry: &'a TypeRegistry, name: &str) -> &'a TypeRegistration {
+    registry
+        .get_with_name(name)
+        .or_else(|| registry.get_with_short_name(name))
+        .unwrap_or_else(|| panic!("CI testing: type `{}` is not registered", name))
+}
+
+fn deserialize(
+    registry: &TypeRegistry,
+    registration: &TypeRegistration,
+    value: &str,
+) -> Box<dyn Reflect> {
+    let mut deserializer = ron::Deserializer::from_str(value).unwrap_or_else(|error| {
+        panic!("CI testing: error parsing `{}`: {}", value, error);
+    });
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(&mut deserializer)
+        .unwrap_or_else(|error| {
+            panic!(
+                "CI testing: error deserializing `{}` as `{}`: {}",
+                value,
+                registration.type_name(),
+                error
+            );
+        })
+}
+
+fn send_event(world: &mut World, registry: &TypeRegistry, event: &str, value: &str) {
+    let registration = get_registration(registry, event);
+    let sender = registration
+        .data::<ReflectCiTestingEvent>()
+        .unwrap_or_else(|| {
+            panic!(
+                "CI testing: event `{}` is not registered with `App::register_ci_testing_event`",
+                registration.type_name()
+            )
+        });
+    let value = deserialize(registry, registration, value);
+    if !(sender.send)(world, &*value) {
+        panic!(
+            "CI testing: `{:?}` is not a valid `{}`",
+            value,
+            registration.type_name()
+        );
+    }
+}
+
+fn assert_resource(
+    world: &World,
+    registry: &TypeRegistry,
+    resource: &str,
+    path: &str,
+    value: &str,
+) {
+    let registration = get_registration(registry, resource);
+    let reflect_resource = registration.data::<ReflectResource>().unwrap_or_else(|| {
+        panic!(
+            "CI testing: `{}` is not registered with `#[reflect(Resource)]`",
+            registration.type_name()
+        )
+    });
+    let resource = reflect_resource.reflect(world).unwrap_or_else(|| {
+        panic!(
+            "CI testing: resource `{}` does not exist",
+            registration.type_name()
+        )
+    });
+    let actual = if path.is_empty() {
+        resource
+    } else {
+        resource.path(path).unwrap_or_else(|error| {
+            panic!(
+                "CI testing: invalid path `{}` in `{}`: {}",
+                path,
+                registration.type_name(),
+                error
+            )
+        })
+    };
+    let expected = deserialize(
+        registry,
+        get_registration(registry, actual.type_name()),
+        value,
+    );
+    match actual.reflect_partial_eq(&*expected) {
+        Some(true) => info!(
+            "CI testing: `{}` `{}` is {:?} as expected",
+            registration.type_name(),
+            path,
+            actual
+        ),
+        _ => panic!(
+            "CI testing: `{}` `{}` is {:?}, expected {:?}",
+            registration.type_name(),
+            path,
+            actual,
+            expected
+        ),
+    }
+}
+
 pub(crate) fn setup_app(app: &mut App) -> &mut App {
     #[cfg(not(target_arch = "wasm32"))]
     let config: CiTestingConfig = {
@@ -46,8 +295,116 @@ pub(crate) fn setup_app(app: &mut App) -> &mut App {
         ron::from_str(config).expect("error deserializing CI testing configuration file")
     };
 
+    add_ci_testing(app, config)
+}
+
+fn add_ci_testing(app: &mut App, config: CiTestingConfig) -> &mut App {
     app.insert_resource(config)
+        .add_event::<CiTestingAction>()
+        .add_system_to_stage(
+            CoreStage::First,
+            ci_testing_run_timeline.at_start().label(CiTestingSystem),
+        )
         .add_system(ci_testing_exit_after);
 
     app
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{add_ci_testing, CiTestingAction, CiTestingConfig, CiTestingEvent};
+    use crate::{App, AppExit, AppTypeRegistry};
+    use bevy_ecs::prelude::*;
+    use bevy_reflect::{FromReflect, Reflect};
+
+    #[derive(Reflect, FromReflect)]
+    struct Ping(u32);
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Pings {
+        total: u32,
+    }
+
+    fn count_pings(mut pings: EventReader<Ping>, mut total: ResMut<Pings>) {
+        for Ping(count) in pings.iter() {
+            total.total += count;
+        }
+    }
+
+    fn test_app(events: Vec<CiTestingEvent>) -> App {
+        let mut app = App::empty();
+        app.init_resource::<AppTypeRegistry>()
+            .add_default_stages()
+            .add_event::<AppExit>()
+            .add_event::<Ping>()
+            .register_ci_testing_event::<Ping>()
+            .register_type::<Pings>()
+            .init_resource::<Pings>()
+            .add_system(count_pings);
+        add_ci_testing(
+            &mut app,
+            CiTestingConfig {
+                exit_after: None,
+                events,
+            },
+        );
+        app
+    }
+
+    fn assert_total(frame: u32, total: &str) -> CiTestingEvent {
+        CiTestingEvent(
+            frame,
+            CiTestingAction::AssertResource {
+                resource: "Pings".to_string(),
+                path: "total".to_string(),
+                value: total.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn timeline() {
+        let config: CiTestingConfig = ron::from_str(
+            r#"(
+                exit_after: None,
+                events: [
+                    (1, SendEvent(event: "Ping", value: "(2)")),
+                    (1, SendEvent(event: "bevy_app::ci_testing::tests::Ping", value: "(3)")),
+                    (2, Screenshot("screenshot.png")),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let mut events = config.events;
+        events.push(assert_total(1, "0"));
+        events.push(assert_total(2, "5"));
+        let mut app = test_app(events);
+
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<Pings>().total, 5);
+        app.update();
+
+        // Every action is forwarded as an event for the plugins handling them.
+        let actions = app.world.resource::<Events<CiTestingAction>>();
+        let mut reader = actions.get_reader();
+        let mut actions = reader.iter(actions);
+        assert!(matches!(
+            actions.next(),
+            Some(CiTestingAction::Screenshot(path)) if path == "screenshot.png"
+        ));
+        assert!(matches!(
+            actions.next(),
+            Some(CiTestingAction::AssertResource { .. })
+        ));
+        assert!(actions.next().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "is 0, expected 1")]
+    fn failed_assertion() {
+        let mut app = test_app(vec![assert_total(0, "1")]);
+        app.update();
+    }
+}
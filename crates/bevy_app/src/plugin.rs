@@ -1,7 +1,7 @@
 use downcast_rs::{impl_downcast, Downcast};
 
 use crate::App;
-use std::any::Any;
+use std::any::{Any, TypeId};
 
 /// A collection of Bevy app logic and configuration.
 ///
@@ -13,6 +13,9 @@ use std::any::Any;
 /// should be overriden to return `false`. Plugins are considered duplicate if they have the same
 /// [`name()`](Self::name). The default `name()` implementation returns the type name, which means
 /// generic plugins with different type parameters will not be considered duplicates.
+///
+/// A plugin relying on others being built first can declare them in
+/// [`dependencies()`](Self::dependencies).
 pub trait Plugin: Downcast + Any + Send + Sync {
     /// Configures the [`App`] to which this plugin is added.
     fn build(&self, app: &mut App);
@@ -25,10 +28,80 @@ pub trait Plugin: Downcast + Any + Send + Sync {
     fn is_unique(&self) -> bool {
         true
     }
+    /// The [`Plugin`]s that must be built before this one.
+    ///
+    /// Adding a plugin to an [`App`] panics if one of its required dependencies wasn't added
+    /// before. A [`PluginGroup`](crate::PluginGroup) builds its plugins after their dependencies
+    /// in the group, and panics if their dependencies are cyclic.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_app::PluginDependency;
+    /// # struct RenderPlugin;
+    /// # impl Plugin for RenderPlugin {
+    /// #     fn build(&self, app: &mut App) {}
+    /// # }
+    /// struct OutlinePlugin;
+    ///
+    /// impl Plugin for OutlinePlugin {
+    ///     fn build(&self, app: &mut App) {}
+    ///
+    ///     fn dependencies(&self) -> Vec<PluginDependency> {
+    ///         vec![PluginDependency::required::<RenderPlugin>()]
+    ///     }
+    /// }
+    /// ```
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        Vec::new()
+    }
 }
 
 impl_downcast!(Plugin);
 
+/// A [`Plugin`] that another plugin must be built after, see [`Plugin::dependencies`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PluginDependency {
+    type_id: TypeId,
+    name: &'static str,
+    optional: bool,
+}
+
+impl PluginDependency {
+    /// A dependency on the plugin `T`, which must be added before the dependent plugin.
+    pub fn required<T: Plugin>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            optional: false,
+        }
+    }
+
+    /// A dependency on the plugin `T`, which is built before the dependent plugin if it is
+    /// added with it.
+    pub fn optional<T: Plugin>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            optional: true,
+        }
+    }
+
+    /// The [`TypeId`] of the plugin.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// The type name of the plugin.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Whether the dependent plugin can be added without this plugin.
+    pub fn is_optional(&self) -> bool {
+        self.optional
+    }
+}
+
 /// A type representing an unsafe function that returns a mutable pointer to a [`Plugin`].
 /// It is used for dynamically loading plugins.
 ///
@@ -166,26 +166,78 @@ impl PluginGroupBuilder {
         self
     }
 
+    /// The enabled [`Plugin`]s in the order they are built: the order of the group, except that
+    /// plugins are moved after their [dependencies](Plugin::dependencies) in the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dependencies of the plugins are cyclic.
+    fn build_order(&self) -> Vec<TypeId> {
+        let mut sorted = Vec::with_capacity(self.order.len());
+        let mut visiting = Vec::new();
+        for ty in &self.order {
+            self.visit(*ty, &mut visiting, &mut sorted);
+        }
+        sorted
+    }
+
+    fn visit(&self, ty: TypeId, visiting: &mut Vec<TypeId>, sorted: &mut Vec<TypeId>) {
+        let entry = match self.plugins.get(&ty) {
+            Some(entry) if entry.enabled => entry,
+            _ => return,
+        };
+        if sorted.contains(&ty) {
+            return;
+        }
+        if let Some(start) = visiting.iter().position(|visited| *visited == ty) {
+            let cycle: Vec<_> = visiting[start..]
+                .iter()
+                .chain(std::iter::once(&ty))
+                .map(|ty| self.plugins[ty].plugin.name())
+                .collect();
+            panic!(
+                "Error adding plugin group {}: plugins have cyclic dependencies: {}",
+                self.group_name,
+                cycle.join(" -> ")
+            );
+        }
+        visiting.push(ty);
+        for dependency in entry.plugin.dependencies() {
+            self.visit(dependency.type_id(), visiting, sorted);
+        }
+        visiting.pop();
+        sorted.push(ty);
+    }
+
     /// Consumes the [`PluginGroupBuilder`] and [builds](Plugin::build) the contained [`Plugin`]s
-    /// in the order specified.
+    /// in the order specified, after moving plugins after their
+    /// [dependencies](Plugin::dependencies).
     ///
     /// # Panics
     ///
-    /// Panics if one of the plugin in the group was already added to the application.
+    /// Panics if one of the plugin in the group was already added to the application, if one of
+    /// the required dependencies of a plugin is neither in the group nor in the application, or
+    /// if the dependencies are cyclic.
     pub fn finish(mut self, app: &mut App) {
-        for ty in &self.order {
-            if let Some(entry) = self.plugins.remove(ty) {
-                if entry.enabled {
-                    debug!("added plugin: {}", entry.plugin.name());
-                    if let Err(AppError::DuplicatePlugin { plugin_name }) =
-                        app.add_boxed_plugin(entry.plugin)
-                    {
-                        panic!(
-                            "Error adding plugin {} in group {}: plugin was already added in application",
-                            plugin_name,
-                            self.group_name
-                        );
-                    }
+        for ty in self.build_order() {
+            if let Some(entry) = self.plugins.remove(&ty) {
+                debug!("added plugin: {}", entry.plugin.name());
+                match app.add_boxed_plugin(entry.plugin) {
+                    Ok(_) => {}
+                    Err(AppError::DuplicatePlugin { plugin_name }) => panic!(
+                        "Error adding plugin {} in group {}: plugin was already added in application",
+                        plugin_name,
+                        self.group_name
+                    ),
+                    Err(AppError::MissingDependencies {
+                        plugin_name,
+                        dependencies,
+                    }) => panic!(
+                        "Error adding plugin {} in group {}: it requires {}, which is neither enabled in the group nor added in application",
+                        plugin_name,
+                        self.group_name,
+                        dependencies.join(", ")
+                    ),
                 }
             }
         }
@@ -213,7 +265,8 @@ impl PluginGroup for NoopPluginGroup {
 #[cfg(test)]
 mod tests {
     use super::PluginGroupBuilder;
-    use crate::{App, NoopPluginGroup, Plugin};
+    use crate::{App, NoopPluginGroup, Plugin, PluginDependency};
+    use bevy_ecs::system::Resource;
 
     struct PluginA;
     impl Plugin for PluginA {
@@ -334,4 +387,93 @@ mod tests {
             ]
         );
     }
+
+    #[derive(Resource, Default)]
+    struct BuildOrder(Vec<&'static str>);
+
+    struct Renderer;
+    impl Plugin for Renderer {
+        fn build(&self, app: &mut App) {
+            app.world.resource_mut::<BuildOrder>().0.push("Renderer");
+        }
+    }
+
+    struct Xr;
+    impl Plugin for Xr {
+        fn build(&self, app: &mut App) {
+            app.world.resource_mut::<BuildOrder>().0.push("Xr");
+        }
+    }
+
+    struct XrCamera;
+    impl Plugin for XrCamera {
+        fn build(&self, app: &mut App) {
+            app.world.resource_mut::<BuildOrder>().0.push("XrCamera");
+        }
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![
+                PluginDependency::required::<Renderer>(),
+                PluginDependency::required::<Xr>(),
+                PluginDependency::optional::<PluginA>(),
+            ]
+        }
+    }
+
+    struct CyclicA;
+    impl Plugin for CyclicA {
+        fn build(&self, _: &mut App) {}
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::required::<CyclicB>()]
+        }
+    }
+
+    struct CyclicB;
+    impl Plugin for CyclicB {
+        fn build(&self, _: &mut App) {}
+
+        fn dependencies(&self) -> Vec<PluginDependency> {
+            vec![PluginDependency::optional::<CyclicA>()]
+        }
+    }
+
+    #[test]
+    fn dependencies_are_built_first() {
+        let mut app = App::new();
+        app.init_resource::<BuildOrder>();
+        PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(Renderer)
+            .add(XrCamera)
+            .add(Xr)
+            .finish(&mut app);
+
+        assert_eq!(
+            app.world.resource::<BuildOrder>().0,
+            ["Renderer", "Xr", "XrCamera"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "it requires bevy_app::plugin_group::tests::Xr")]
+    fn disabled_dependency() {
+        let mut app = App::new();
+        app.init_resource::<BuildOrder>();
+        PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(Renderer)
+            .add(XrCamera)
+            .add(Xr)
+            .disable::<Xr>()
+            .finish(&mut app);
+    }
+
+    #[test]
+    #[should_panic(expected = "plugins have cyclic dependencies")]
+    fn cyclic_dependencies() {
+        PluginGroupBuilder::start::<NoopPluginGroup>()
+            .add(PluginA)
+            .add(CyclicA)
+            .add(CyclicB)
+            .finish(&mut App::new());
+    }
 }
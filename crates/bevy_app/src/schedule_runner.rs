@@ -24,6 +24,18 @@ pub enum RunMode {
     },
     /// Indicates that the [`App`]'s schedule should run only once.
     Once,
+    /// Indicates that the [`App`]'s schedule should run repeatedly, with a virtual time advancing
+    /// by `delta` per update regardless of the wall clock.
+    ///
+    /// The `Time` resource of `bevy_time` follows this virtual time, which makes simulations and
+    /// headless tests deterministic.
+    FixedStep {
+        /// The [`Duration`] the virtual time advances by per update.
+        delta: Duration,
+        /// The number of updates to run as fast as possible before the runner returns. A value of
+        /// [`None`] runs repeatedly, waiting for `delta` of wall-clock time between updates.
+        frames: Option<u32>,
+    },
 }
 
 impl Default for RunMode {
@@ -57,6 +69,22 @@ impl ScheduleRunnerSettings {
             },
         }
     }
+
+    /// See [`RunMode::FixedStep`].
+    pub fn run_fixed_step(delta: Duration, frames: Option<u32>) -> Self {
+        ScheduleRunnerSettings {
+            run_mode: RunMode::FixedStep { delta, frames },
+        }
+    }
+
+    /// Returns the [`Duration`] the virtual time advances by per update, if the run mode is
+    /// [`RunMode::FixedStep`].
+    pub fn fixed_step(&self) -> Option<Duration> {
+        match self.run_mode {
+            RunMode::FixedStep { delta, .. } => Some(delta),
+            RunMode::Loop { .. } | RunMode::Once => None,
+        }
+    }
 }
 
 /// Configures an [`App`] to run its [`Schedule`](bevy_ecs::schedule::Schedule) according to a given
@@ -76,10 +104,25 @@ impl Plugin for ScheduleRunnerPlugin {
                 RunMode::Once => {
                     app.update();
                 }
-                RunMode::Loop { wait } => {
+                RunMode::Loop { .. } | RunMode::FixedStep { .. } => {
+                    let (wait, mut remaining_frames) = match settings.run_mode {
+                        RunMode::FixedStep {
+                            frames: Some(frames),
+                            ..
+                        } => (None, Some(frames)),
+                        RunMode::FixedStep {
+                            delta,
+                            frames: None,
+                        } => (Some(delta), None),
+                        RunMode::Loop { wait } => (wait, None),
+                        RunMode::Once => unreachable!(),
+                    };
                     let mut tick = move |app: &mut App,
                                          wait: Option<Duration>|
                           -> Result<Option<Duration>, AppExit> {
+                        if remaining_frames == Some(0) {
+                            return Err(AppExit);
+                        }
                         let start_time = Instant::now();
 
                         if let Some(app_exit_events) =
@@ -92,6 +135,9 @@ impl Plugin for ScheduleRunnerPlugin {
                         }
 
                         app.update();
+                        if let Some(remaining_frames) = &mut remaining_frames {
+                            *remaining_frames -= 1;
+                        }
 
                         if let Some(app_exit_events) =
                             app.world.get_resource_mut::<Events<AppExit>>()
@@ -1,4 +1,8 @@
-use bevy_ecs::{component::Component, reflect::ReflectComponent};
+use bevy_ecs::{
+    component::Component,
+    inspector::{EntityName, ReflectEntityName},
+    reflect::ReflectComponent,
+};
 use bevy_reflect::Reflect;
 use bevy_reflect::{std_traits::ReflectDefault, FromReflect};
 use bevy_utils::AHasher;
@@ -15,7 +19,7 @@ use std::{
 /// as multiple entities can have the same name.  [`bevy_ecs::entity::Entity`] should be
 /// used instead as the default unique identifier.
 #[derive(Reflect, FromReflect, Component, Debug, Clone)]
-#[reflect(Component, Default)]
+#[reflect(Component, Default, EntityName)]
 pub struct Name {
     hash: u64, // TODO: Shouldn't be serialized
     name: Cow<'static, str>,
@@ -93,6 +97,12 @@ impl From<String> for Name {
 
 /* Conversions to strings */
 
+impl EntityName for Name {
+    fn entity_name(&self) -> &str {
+        self.as_str()
+    }
+}
+
 impl AsRef<str> for Name {
     #[inline(always)]
     fn as_ref(&self) -> &str {
@@ -88,9 +88,19 @@ impl Diagnostic {
                 name, MAX_DIAGNOSTIC_NAME_WIDTH
             );
         }
+        Self::new_unchecked_name(id, name, max_history_length)
+    }
+
+    /// Like [`Diagnostic::new`], without the warning about long names. Used for diagnostics that
+    /// the `LogDiagnosticsPlugin` does not align, like system names.
+    pub(crate) fn new_unchecked_name(
+        id: DiagnosticId,
+        name: impl Into<Cow<'static, str>>,
+        max_history_length: usize,
+    ) -> Diagnostic {
         Diagnostic {
             id,
-            name,
+            name: name.into(),
             suffix: Cow::Borrowed(""),
             history: VecDeque::with_capacity(max_history_length),
             max_history_length,
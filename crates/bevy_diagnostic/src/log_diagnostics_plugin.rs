@@ -1,4 +1,4 @@
-use super::{Diagnostic, DiagnosticId, Diagnostics};
+use super::{Diagnostic, DiagnosticId, Diagnostics, SystemTimingDiagnostics};
 use bevy_app::prelude::*;
 use bevy_ecs::system::{Res, ResMut, Resource};
 use bevy_log::{debug, info};
@@ -10,6 +10,10 @@ pub struct LogDiagnosticsPlugin {
     pub debug: bool,
     pub wait_duration: Duration,
     pub filter: Option<Vec<DiagnosticId>>,
+    /// The number of systems reported, from the slowest, when the
+    /// [`SystemTimingDiagnosticsPlugin`](crate::SystemTimingDiagnosticsPlugin) is added and no
+    /// `filter` is set.
+    pub slowest_systems: usize,
 }
 
 /// State used by the [`LogDiagnosticsPlugin`]
@@ -17,6 +21,7 @@ pub struct LogDiagnosticsPlugin {
 struct LogDiagnosticsState {
     timer: Timer,
     filter: Option<Vec<DiagnosticId>>,
+    slowest_systems: usize,
 }
 
 impl Default for LogDiagnosticsPlugin {
@@ -25,6 +30,7 @@ impl Default for LogDiagnosticsPlugin {
             debug: false,
             wait_duration: Duration::from_secs(1),
             filter: None,
+            slowest_systems: 10,
         }
     }
 }
@@ -34,6 +40,7 @@ impl Plugin for LogDiagnosticsPlugin {
         app.insert_resource(LogDiagnosticsState {
             timer: Timer::new(self.wait_duration, TimerMode::Repeating),
             filter: self.filter.clone(),
+            slowest_systems: self.slowest_systems,
         });
 
         if self.debug {
@@ -80,10 +87,51 @@ impl LogDiagnosticsPlugin {
         }
     }
 
+    /// Logs the average time of every stage, in execution order, and of the slowest systems.
+    fn log_system_timings(
+        diagnostics: &Diagnostics,
+        system_timings: &SystemTimingDiagnostics,
+        slowest_systems: usize,
+    ) {
+        let average = |id: &DiagnosticId| {
+            diagnostics
+                .get(*id)
+                .filter(|diagnostic| diagnostic.is_enabled)
+                .and_then(|diagnostic| Some((diagnostic, diagnostic.average()?)))
+        };
+
+        info!(target: "bevy diagnostic", "stage times:");
+        for (diagnostic, average) in system_timings.stages().iter().filter_map(average) {
+            info!(
+                target: "bevy diagnostic",
+                "{average:>11.6}{suffix:2} {name}",
+                name = diagnostic.name,
+                suffix = diagnostic.suffix,
+            );
+        }
+
+        let mut systems: Vec<_> = system_timings
+            .systems()
+            .iter()
+            .filter_map(average)
+            .collect();
+        systems.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        info!(target: "bevy diagnostic", "slowest systems:");
+        for (diagnostic, average) in systems.into_iter().take(slowest_systems) {
+            info!(
+                target: "bevy diagnostic",
+                "{average:>11.6}{suffix:2} {name}",
+                name = diagnostic.name,
+                suffix = diagnostic.suffix,
+            );
+        }
+    }
+
     fn log_diagnostics_system(
         mut state: ResMut<LogDiagnosticsState>,
         time: Res<Time>,
         diagnostics: Res<Diagnostics>,
+        system_timings: Option<Res<SystemTimingDiagnostics>>,
     ) {
         if state.timer.tick(time.raw_delta()).finished() {
             if let Some(ref filter) = state.filter {
@@ -95,12 +143,25 @@ impl LogDiagnosticsPlugin {
                     Self::log_diagnostic(diagnostic);
                 }
             } else {
-                for diagnostic in diagnostics
-                    .iter()
-                    .filter(|diagnostic| diagnostic.is_enabled)
-                {
+                // System timings are too many to be logged one by one.
+                for diagnostic in diagnostics.iter().filter(|diagnostic| {
+                    diagnostic.is_enabled
+                        && !system_timings
+                            .as_ref()
+                            .map(|system_timings| system_timings.contains(diagnostic.id))
+                            .unwrap_or(false)
+                }) {
                     Self::log_diagnostic(diagnostic);
                 }
+                if let Some(system_timings) = &system_timings {
+                    if state.slowest_systems > 0 {
+                        Self::log_system_timings(
+                            &diagnostics,
+                            system_timings,
+                            state.slowest_systems,
+                        );
+                    }
+                }
             }
         }
     }
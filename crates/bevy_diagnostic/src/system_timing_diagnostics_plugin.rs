@@ -0,0 +1,165 @@
+use std::hash::BuildHasher;
+
+use crate::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_app::prelude::*;
+use bevy_ecs::{
+    schedule::{StageLabel, SystemTimings},
+    system::{ResMut, Resource},
+};
+use bevy_utils::{FixedState, HashSet};
+
+/// Adds "system time" and "stage time" diagnostics to an App: the CPU time spent running each
+/// system and each stage of the app's schedule every frame, in milliseconds.
+///
+/// Times are recorded by the executors of the [`SystemStage`](bevy_ecs::schedule::SystemStage)s
+/// into [`SystemTimings`], which adds a small overhead to every system run. A diagnostic is added
+/// the first time a system or stage runs, and only gets measurements on frames it ran in, so its
+/// average is the average time of a run. Systems of sub-apps, like the render app, are not
+/// recorded.
+///
+/// The [`LogDiagnosticsPlugin`](crate::LogDiagnosticsPlugin) reports these diagnostics separately
+/// from the others: the time of every stage and the slowest systems.
+pub struct SystemTimingDiagnosticsPlugin {
+    /// The number of frames the average times are computed over.
+    pub max_history_length: usize,
+}
+
+impl Default for SystemTimingDiagnosticsPlugin {
+    fn default() -> Self {
+        SystemTimingDiagnosticsPlugin {
+            max_history_length: 20,
+        }
+    }
+}
+
+impl Plugin for SystemTimingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SystemTimings>()
+            .insert_resource(SystemTimingDiagnostics {
+                max_history_length: self.max_history_length,
+                systems: Vec::new(),
+                stages: Vec::new(),
+                ids: HashSet::default(),
+            })
+            .add_system_to_stage(CoreStage::Last, Self::diagnostic_system);
+    }
+}
+
+impl SystemTimingDiagnosticsPlugin {
+    const SYSTEM_NAMESPACE: u128 = 0x5a0f_37c2_9d61_4e8b_0000_0000_0000_0000;
+    const STAGE_NAMESPACE: u128 = 0xc41e_8b07_2f95_4a3d_0000_0000_0000_0000;
+
+    /// Returns the id of the diagnostic of the system with the given name.
+    pub fn system_diagnostic_id(name: &str) -> DiagnosticId {
+        Self::diagnostic_id(Self::SYSTEM_NAMESPACE, name)
+    }
+
+    /// Returns the id of the diagnostic of the stage with the given label.
+    pub fn stage_diagnostic_id(label: &str) -> DiagnosticId {
+        Self::diagnostic_id(Self::STAGE_NAMESPACE, label)
+    }
+
+    fn diagnostic_id(namespace: u128, name: &str) -> DiagnosticId {
+        DiagnosticId::from_u128(namespace | FixedState.hash_one(name) as u128)
+    }
+
+    pub fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        mut timings: ResMut<SystemTimings>,
+        mut state: ResMut<SystemTimingDiagnostics>,
+    ) {
+        for (label, time) in timings.stages() {
+            let id = Self::stage_diagnostic_id(label.as_str());
+            if state.ids.insert(id) {
+                state.stages.push(id);
+                diagnostics.add(
+                    Diagnostic::new_unchecked_name(id, label.as_str(), state.max_history_length)
+                        .with_suffix("ms"),
+                );
+            }
+            diagnostics.add_measurement(id, || time.as_secs_f64() * 1000.0);
+        }
+        for (name, time) in timings.systems() {
+            let id = Self::system_diagnostic_id(name);
+            if state.ids.insert(id) {
+                state.systems.push(id);
+                diagnostics.add(
+                    Diagnostic::new_unchecked_name(id, name.to_owned(), state.max_history_length)
+                        .with_suffix("ms"),
+                );
+            }
+            diagnostics.add_measurement(id, || time.as_secs_f64() * 1000.0);
+        }
+        timings.clear();
+    }
+}
+
+/// The diagnostics added by the [`SystemTimingDiagnosticsPlugin`].
+#[derive(Resource, Debug)]
+pub struct SystemTimingDiagnostics {
+    max_history_length: usize,
+    systems: Vec<DiagnosticId>,
+    stages: Vec<DiagnosticId>,
+    ids: HashSet<DiagnosticId>,
+}
+
+impl SystemTimingDiagnostics {
+    /// The diagnostics of the systems that ran so far.
+    pub fn systems(&self) -> &[DiagnosticId] {
+        &self.systems
+    }
+
+    /// The diagnostics of the stages that ran so far.
+    pub fn stages(&self) -> &[DiagnosticId] {
+        &self.stages
+    }
+
+    /// Returns `true` if `id` is the diagnostic of a system or a stage.
+    pub fn contains(&self, id: DiagnosticId) -> bool {
+        self.ids.contains(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiagnosticsPlugin;
+    use bevy_ecs::schedule::SystemStage;
+
+    #[derive(bevy_ecs::schedule::StageLabel)]
+    struct Stage;
+
+    fn some_system() {}
+
+    #[test]
+    fn records_system_and_stage_times() {
+        let mut app = App::new();
+        app.add_plugin(DiagnosticsPlugin)
+            .add_plugin(SystemTimingDiagnosticsPlugin::default())
+            .add_stage_after(
+                CoreStage::Update,
+                Stage,
+                SystemStage::parallel().with_system(some_system),
+            );
+        app.update();
+        app.update();
+
+        let state = app.world.resource::<SystemTimingDiagnostics>();
+        let diagnostics = app.world.resource::<Diagnostics>();
+        let system = SystemTimingDiagnosticsPlugin::system_diagnostic_id(
+            "bevy_diagnostic::system_timing_diagnostics_plugin::tests::some_system",
+        );
+        let stage = SystemTimingDiagnosticsPlugin::stage_diagnostic_id("Stage");
+        assert!(state.systems().contains(&system));
+        assert!(state.stages().contains(&stage));
+        assert!(!state.contains(SystemTimingDiagnosticsPlugin::system_diagnostic_id("Stage")));
+
+        assert_eq!(diagnostics.get(system).unwrap().history_len(), 2);
+        assert!(diagnostics.get(stage).unwrap().average().unwrap() >= 0.0);
+        // The timings are drained every frame.
+        let timings = app.world.resource::<SystemTimings>();
+        assert!(timings
+            .system("bevy_diagnostic::system_timing_diagnostics_plugin::tests::some_system")
+            .is_none());
+    }
+}
@@ -0,0 +1,341 @@
+use std::{
+    any::TypeId,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use libloading::Library;
+
+use bevy_app::{App, AppTypeRegistry, CoreStage, Plugin, StartupSchedule};
+use bevy_ecs::{
+    reflect::{ReflectComponent, ReflectResource},
+    schedule::{Schedule, Stage, StageLabel},
+    snapshot::{SnapshotFilter, WorldSnapshot},
+    world::World,
+};
+use bevy_utils::{
+    tracing::{error, info, warn},
+    Duration, HashSet, Instant,
+};
+
+use crate::{dynamically_load_plugin, DynamicPluginLoadError};
+
+/// The [`Stage`] running a dynamic plugin loaded with
+/// [`DynamicPluginExt::load_hot_reloaded_plugin`](crate::DynamicPluginExt::load_hot_reloaded_plugin),
+/// which reloads the plugin when its library is rebuilt.
+///
+/// The plugin is built into its own [`Schedule`], run by this stage after [`CoreStage::Update`],
+/// so that its systems can be torn down. Reloading the plugin:
+/// - captures the components and resources of the types the plugin registered in the
+///   [`AppTypeRegistry`] with [`ReflectComponent`] or [`ReflectResource`], and removes them,
+/// - removes the registrations of these types,
+/// - loads a copy of the rebuilt library and builds the new plugin, without running its startup
+///   systems again,
+/// - restores the captured components and resources with the new registrations.
+///
+/// Previous versions of the library are never unloaded, because the [`World`] may still use their
+/// code, for example to drop the resources that are not reflected.
+pub struct HotReloadStage {
+    path: PathBuf,
+    check_interval: Duration,
+    last_check: Instant,
+    /// The modification time of the loaded library.
+    modified: Option<SystemTime>,
+    /// The modification time of the library seen at the last check, if it changed. The library is
+    /// reloaded once this is the same for two checks in a row, so it isn't read while written.
+    pending: Option<SystemTime>,
+    plugin: Box<dyn Plugin>,
+    schedule: Schedule,
+    types: Vec<TypeId>,
+    libraries: Vec<Library>,
+}
+
+impl HotReloadStage {
+    /// Loads the plugin in the library at `path` and builds it.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`dynamically_load_plugin`]. In addition, the component and resource types of
+    /// the plugin must keep the same memory layout across reloads, because the [`World`] keeps
+    /// the component information of their [`TypeId`].
+    pub unsafe fn new(world: &mut World, path: &str) -> Result<Self, DynamicPluginLoadError> {
+        let path = PathBuf::from(path);
+        let modified = modified(&path);
+        let (library, plugin) = dynamically_load_plugin(&library_copy(&path, 0)?)?;
+        let mut stage = Self::from_plugin(world, plugin, true);
+        stage.path = path;
+        stage.modified = modified;
+        stage.libraries.push(library);
+        Ok(stage)
+    }
+
+    fn from_plugin(world: &mut World, plugin: Box<dyn Plugin>, run_startup: bool) -> Self {
+        let (schedule, types) = build_plugin(world, &*plugin, run_startup);
+        Self {
+            path: PathBuf::new(),
+            check_interval: Duration::from_secs(1),
+            last_check: Instant::now(),
+            modified: None,
+            pending: None,
+            plugin,
+            schedule,
+            types,
+            libraries: Vec::new(),
+        }
+    }
+
+    /// Sets how often the library is checked for changes. Defaults to one second.
+    pub fn set_check_interval(&mut self, check_interval: Duration) -> &mut Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// The path of the library of the plugin.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of times the plugin was reloaded.
+    pub fn reload_count(&self) -> usize {
+        self.libraries.len().saturating_sub(1)
+    }
+
+    /// Returns `true` if the library was rebuilt since it was loaded.
+    fn should_reload(&mut self) -> bool {
+        if self.last_check.elapsed() < self.check_interval {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let modified = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            self.pending = None;
+            return false;
+        }
+        if modified != self.pending {
+            self.pending = modified;
+            return false;
+        }
+        self.modified = modified;
+        self.pending = None;
+        true
+    }
+
+    /// Reloads the plugin from its library.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`HotReloadStage::new`].
+    pub unsafe fn reload(&mut self, world: &mut World) -> Result<(), DynamicPluginLoadError> {
+        let copy = library_copy(&self.path, self.libraries.len())?;
+        let (library, plugin) = dynamically_load_plugin(&copy)?;
+        self.libraries.push(library);
+        self.replace_plugin(world, plugin);
+        Ok(())
+    }
+
+    /// Tears down the current plugin, and builds `plugin` with the state of the current one.
+    fn replace_plugin(&mut self, world: &mut World, plugin: Box<dyn Plugin>) {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let snapshot = if self.types.is_empty() {
+            None
+        } else {
+            let filter = self
+                .types
+                .iter()
+                .fold(SnapshotFilter::default(), |filter, type_id| {
+                    filter.allow_by_id(*type_id)
+                });
+            let snapshot = WorldSnapshot::capture(world, &registry.read(), filter);
+            remove_reflected(world, &registry.read(), &self.types);
+            let mut registry = registry.write();
+            for type_id in &self.types {
+                registry.remove(*type_id);
+            }
+            Some(snapshot)
+        };
+
+        let (schedule, types) = build_plugin(world, &*plugin, false);
+        self.plugin = plugin;
+        self.schedule = schedule;
+        self.types = types;
+
+        if let Some(snapshot) = snapshot {
+            if let Err(err) = snapshot.restore(world, &registry.read()) {
+                warn!(
+                    "Could not restore the state of hot reloaded plugin {}: {}",
+                    self.plugin.name(),
+                    err
+                );
+            }
+        }
+    }
+}
+
+impl Stage for HotReloadStage {
+    fn run(&mut self, world: &mut World) {
+        if self.should_reload() {
+            // SAFETY: the safety requirements were promised when loading the plugin
+            match unsafe { self.reload(world) } {
+                Ok(()) => info!("Reloaded plugin {}", self.plugin.name()),
+                Err(err) => error!("Could not reload plugin {:?}: {}", self.path, err),
+            }
+        }
+        self.schedule.run(world);
+    }
+}
+
+/// Builds `plugin` into its own [`Schedule`], and returns it with the types the plugin registered.
+fn build_plugin(
+    world: &mut World,
+    plugin: &dyn Plugin,
+    run_startup: bool,
+) -> (Schedule, Vec<TypeId>) {
+    let registered = |world: &World| -> HashSet<TypeId> {
+        world
+            .resource::<AppTypeRegistry>()
+            .read()
+            .iter()
+            .map(|registration| registration.type_id())
+            .collect()
+    };
+    let registered_before = registered(world);
+
+    let mut app = App::empty();
+    std::mem::swap(&mut app.world, world);
+    app.add_default_stages();
+    plugin.build(&mut app);
+    std::mem::swap(&mut app.world, world);
+
+    if !run_startup {
+        app.schedule
+            .stage(StartupSchedule, |startup: &mut Schedule| {
+                *startup = Schedule::default();
+                startup
+            });
+    }
+    let types = registered(world)
+        .difference(&registered_before)
+        .copied()
+        .collect();
+    (std::mem::take(&mut app.schedule), types)
+}
+
+/// Removes the components and resources of the given types.
+fn remove_reflected(world: &mut World, registry: &bevy_reflect::TypeRegistry, types: &[TypeId]) {
+    for type_id in types {
+        if let Some(reflect_component) = registry.get_type_data::<ReflectComponent>(*type_id) {
+            let component_id = match world.components().get_id(*type_id) {
+                Some(component_id) => component_id,
+                None => continue,
+            };
+            let entities: Vec<_> = world
+                .archetypes()
+                .iter()
+                .filter(|archetype| archetype.contains(component_id))
+                .flat_map(|archetype| archetype.entities())
+                .map(|archetype_entity| archetype_entity.entity())
+                .collect();
+            for entity in entities {
+                reflect_component.remove(world, entity);
+            }
+        }
+        if let Some(reflect_resource) = registry.get_type_data::<ReflectResource>(*type_id) {
+            reflect_resource.remove(world);
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Copies the library to a unique path, since a library can't be loaded twice from the same path.
+fn library_copy(path: &Path, version: usize) -> Result<String, DynamicPluginLoadError> {
+    let file_name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    let copy = std::env::temp_dir().join(format!(
+        "bevy_hot_reload_{}_{}_{}",
+        std::process::id(),
+        version,
+        file_name
+    ));
+    std::fs::copy(path, &copy).map_err(DynamicPluginLoadError::Copy)?;
+    Ok(copy.to_string_lossy().into_owned())
+}
+
+/// The label of the [`HotReloadStage`] of the plugin in the library at `path`.
+pub(crate) fn stage_label(path: &str) -> impl StageLabel {
+    let label: &'static str = Box::leak(format!("hot_reload {}", path).into_boxed_str());
+    label
+}
+
+pub(crate) fn add_hot_reload_stage(app: &mut App, path: &str, stage: HotReloadStage) {
+    app.add_stage_after(CoreStage::Update, stage_label(path), stage);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HotReloadStage;
+    use bevy_app::{App, Plugin};
+    use bevy_ecs::{prelude::*, schedule::Stage};
+    use bevy_reflect::Reflect;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Counter(u32);
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Total(u32);
+
+    struct CounterPlugin {
+        step: u32,
+    }
+
+    impl Plugin for CounterPlugin {
+        fn build(&self, app: &mut App) {
+            let step = self.step;
+            app.register_type::<Counter>()
+                .register_type::<Total>()
+                .init_resource::<Total>()
+                .add_startup_system(|mut commands: Commands| {
+                    commands.spawn(Counter(0));
+                })
+                .add_system(
+                    move |mut counters: Query<&mut Counter>, mut total: ResMut<Total>| {
+                        for mut counter in &mut counters {
+                            counter.0 += step;
+                            total.0 += step;
+                        }
+                    },
+                );
+        }
+    }
+
+    #[test]
+    fn replace_plugin_preserves_state() {
+        let mut app = App::new();
+        let mut stage =
+            HotReloadStage::from_plugin(&mut app.world, Box::new(CounterPlugin { step: 1 }), true);
+        stage.run(&mut app.world);
+        stage.run(&mut app.world);
+
+        stage.replace_plugin(&mut app.world, Box::new(CounterPlugin { step: 10 }));
+        stage.run(&mut app.world);
+
+        // The startup system didn't run again, and the old system was torn down.
+        let counters: Vec<_> = app
+            .world
+            .query::<&Counter>()
+            .iter(&app.world)
+            .map(|counter| counter.0)
+            .collect();
+        assert_eq!(counters, [12]);
+        assert_eq!(app.world.resource::<Total>().0, 12);
+    }
+}
@@ -3,6 +3,8 @@ use thiserror::Error;
 
 use bevy_app::{App, CreatePlugin, Plugin};
 
+use crate::{hot_reload::add_hot_reload_stage, HotReloadStage};
+
 /// Errors that can occur when loading a dynamic plugin
 #[derive(Debug, Error)]
 pub enum DynamicPluginLoadError {
@@ -10,6 +12,8 @@ pub enum DynamicPluginLoadError {
     Library(libloading::Error),
     #[error("dynamic library does not contain a valid Bevy dynamic plugin")]
     Plugin(libloading::Error),
+    #[error("cannot copy library for hot reloading: {0}")]
+    Copy(std::io::Error),
 }
 
 /// Dynamically links a plugin at the given path. The plugin must export a function with the
@@ -36,6 +40,14 @@ pub trait DynamicPluginExt {
     ///
     /// Same as [`dynamically_load_plugin`].
     unsafe fn load_plugin(&mut self, path: &str) -> &mut Self;
+
+    /// Loads a plugin like [`load_plugin`](Self::load_plugin), and reloads it when its library
+    /// is rebuilt, keeping its reflected state. See [`HotReloadStage`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`HotReloadStage::new`].
+    unsafe fn load_hot_reloaded_plugin(&mut self, path: &str) -> &mut Self;
 }
 
 impl DynamicPluginExt for App {
@@ -45,4 +57,10 @@ impl DynamicPluginExt for App {
         plugin.build(self);
         self
     }
+
+    unsafe fn load_hot_reloaded_plugin(&mut self, path: &str) -> &mut Self {
+        let stage = HotReloadStage::new(&mut self.world, path).unwrap();
+        add_hot_reload_stage(self, path, stage);
+        self
+    }
 }
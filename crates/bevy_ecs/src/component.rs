@@ -2,8 +2,10 @@
 
 use crate::{
     change_detection::MAX_CHANGE_AGE,
+    entity::Entity,
     storage::{SparseSetIndex, Storages},
     system::Resource,
+    world::DeferredWorld,
 };
 pub use bevy_ecs_macros::Component;
 use bevy_ptr::OwningPtr;
@@ -159,6 +161,7 @@ pub enum StorageType {
 pub struct ComponentInfo {
     id: ComponentId,
     descriptor: ComponentDescriptor,
+    hooks: ComponentHooks,
 }
 
 impl ComponentInfo {
@@ -203,8 +206,103 @@ impl ComponentInfo {
         self.descriptor.is_send_and_sync
     }
 
+    /// Returns the lifecycle hooks registered for this component.
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
     fn new(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
-        ComponentInfo { id, descriptor }
+        ComponentInfo {
+            id,
+            descriptor,
+            hooks: ComponentHooks::default(),
+        }
+    }
+}
+
+/// A function called when a component is added to, inserted on or removed from an entity.
+///
+/// Hooks run synchronously, in the middle of the structural change that triggered them, so they
+/// only get a [`DeferredWorld`]: they can mutate components and resources, but entities can only
+/// be spawned, despawned or changed through [`DeferredWorld::commands`].
+pub type ComponentHook = for<'w> fn(DeferredWorld<'w>, Entity, ComponentId);
+
+/// The lifecycle hooks of a component type, see [`World::register_component_hooks`].
+///
+/// - `on_add` runs when the component is added to an entity that did not have it.
+/// - `on_insert` runs every time a value of the component is inserted, after `on_add`. When the
+///   value replaces a previous one, `on_remove` does not run for the previous value.
+/// - `on_remove` runs before the component is removed from an entity, including when the entity
+///   is despawned, so the hook can still read the value.
+///
+/// A component has at most one hook of each kind, which is meant to be set by the code owning the
+/// component type. Use [observers](crate::world::World::observe) to react to other components.
+///
+/// [`World::register_component_hooks`]: crate::world::World::register_component_hooks
+#[derive(Debug, Clone, Default)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_insert: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    /// Sets the `on_add` hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component already has an `on_add` hook.
+    pub fn on_add(&mut self, hook: ComponentHook) -> &mut Self {
+        self.try_on_add(hook)
+            .expect("Component already has an on_add hook")
+    }
+
+    /// Sets the `on_insert` hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component already has an `on_insert` hook.
+    pub fn on_insert(&mut self, hook: ComponentHook) -> &mut Self {
+        self.try_on_insert(hook)
+            .expect("Component already has an on_insert hook")
+    }
+
+    /// Sets the `on_remove` hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component already has an `on_remove` hook.
+    pub fn on_remove(&mut self, hook: ComponentHook) -> &mut Self {
+        self.try_on_remove(hook)
+            .expect("Component already has an on_remove hook")
+    }
+
+    /// Sets the `on_add` hook, or returns `None` if the component already has one.
+    pub fn try_on_add(&mut self, hook: ComponentHook) -> Option<&mut Self> {
+        if self.on_add.is_some() {
+            return None;
+        }
+        self.on_add = Some(hook);
+        Some(self)
+    }
+
+    /// Sets the `on_insert` hook, or returns `None` if the component already has one.
+    pub fn try_on_insert(&mut self, hook: ComponentHook) -> Option<&mut Self> {
+        if self.on_insert.is_some() {
+            return None;
+        }
+        self.on_insert = Some(hook);
+        Some(self)
+    }
+
+    /// Sets the `on_remove` hook, or returns `None` if the component already has one.
+    pub fn try_on_remove(&mut self, hook: ComponentHook) -> Option<&mut Self> {
+        if self.on_remove.is_some() {
+            return None;
+        }
+        self.on_remove = Some(hook);
+        Some(self)
     }
 }
 
@@ -367,6 +465,7 @@ pub struct Components {
     components: Vec<ComponentInfo>,
     indices: std::collections::HashMap<TypeId, usize, fxhash::FxBuildHasher>,
     resource_indices: std::collections::HashMap<TypeId, usize, fxhash::FxBuildHasher>,
+    hooks_registered: bool,
 }
 
 impl Components {
@@ -424,6 +523,20 @@ impl Components {
         self.components.get(id.0)
     }
 
+    /// Returns the lifecycle hooks of the component, to register new ones.
+    pub fn get_hooks_mut(&mut self, id: ComponentId) -> Option<&mut ComponentHooks> {
+        let info = self.components.get_mut(id.0)?;
+        self.hooks_registered = true;
+        Some(&mut info.hooks)
+    }
+
+    /// Returns `false` if no component has lifecycle hooks, which lets structural changes skip
+    /// looking them up.
+    #[inline]
+    pub(crate) fn hooks_registered(&self) -> bool {
+        self.hooks_registered
+    }
+
     /// # Safety
     ///
     /// `id` must be a valid [`ComponentId`]
@@ -692,6 +692,15 @@ pub struct EntityLocation {
     pub index: usize,
 }
 
+impl EntityLocation {
+    /// Location of an entity that was despawned while an [`EntityMut`](crate::world::EntityMut)
+    /// to it was alive.
+    pub(crate) const INVALID: EntityLocation = EntityLocation {
+        archetype_id: ArchetypeId::INVALID,
+        index: usize::MAX,
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,9 +2,14 @@
 
 use crate as bevy_ecs;
 use crate::system::{Local, Res, ResMut, Resource, SystemParam};
-use bevy_utils::tracing::trace;
+use crate::{change_detection::DetectChanges, world::FromWorld, world::World};
+use bevy_utils::tracing::{trace, warn};
 use std::ops::{Deref, DerefMut};
-use std::{fmt, hash::Hash, marker::PhantomData};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Weak,
+};
+use std::{collections::VecDeque, fmt, hash::Hash, marker::PhantomData};
 
 /// A type that can be stored in an [`Events<E>`] resource
 /// You can conveniently access events using the [`EventReader`] and [`EventWriter`] system parameter.
@@ -121,6 +126,16 @@ struct EventInstance<E: Event> {
 /// but can be done by adding your event as a resource instead of using
 /// [`add_event`](https://docs.rs/bevy/*/bevy/app/struct.App.html#method.add_event).
 ///
+/// # Retention
+///
+/// Readers that don't run every frame, for example in systems with run criteria or in fixed
+/// timesteps, can miss events. [`Events`] created with [`Events::with_retention`] keep the events
+/// until all their registered readers have read them, up to a bounded number of events, see
+/// [`EventRetention`]. The [`EventReader`]s of systems initialized after the [`Events`] are
+/// registered automatically, and [`ManualEventReader`]s are registered with
+/// [`Events::register_reader`]. Readers that aren't registered can read the retained events, but
+/// don't retain them.
+///
 /// [Example usage.](https://github.com/bevyengine/bevy/blob/latest/examples/ecs/event.rs)
 /// [Example usage standalone.](https://github.com/bevyengine/bevy/blob/latest/crates/bevy_ecs/examples/events.rs)
 ///
@@ -132,6 +147,9 @@ pub struct Events<E: Event> {
     /// Holds the newer events.
     events_b: EventSequence<E>,
     event_count: usize,
+    retention: Option<EventRetention>,
+    /// The number of events read by each registered reader.
+    readers: Vec<Weak<AtomicUsize>>,
 }
 
 // Derived Default impl would incorrectly require E: Default
@@ -141,10 +159,46 @@ impl<E: Event> Default for Events<E> {
             events_a: Default::default(),
             events_b: Default::default(),
             event_count: Default::default(),
+            retention: None,
+            readers: Vec::new(),
         }
     }
 }
 
+/// Configures [`Events`] to retain events until all their registered readers have read them.
+///
+/// See [`Events::with_retention`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventRetention {
+    /// The maximum number of events stored.
+    pub capacity: usize,
+    /// What happens when an event is sent while `capacity` events are stored.
+    pub overflow: EventOverflow,
+}
+
+impl EventRetention {
+    /// Retains each event until every reader registered with [`Events::register_reader`] has
+    /// read it, instead of dropping it after two updates, storing at most `capacity` events.
+    /// `overflow` decides what happens to an event sent when `capacity` events are stored.
+    /// Events without registered readers are still dropped after two updates.
+    pub fn new(capacity: usize, overflow: EventOverflow) -> Self {
+        Self { capacity, overflow }
+    }
+}
+
+/// What happens when an event is sent to [`Events`] that store as many events as their
+/// [`EventRetention::capacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventOverflow {
+    /// The oldest event is dropped. Readers that haven't read it yet miss it, see
+    /// [`ManualEventReader::missed_events`].
+    DropOldest,
+    /// The sent event is dropped.
+    DropNewest,
+    /// Panics.
+    Panic,
+}
+
 impl<E: Event> Events<E> {
     pub fn oldest_event_count(&self) -> usize {
         self.events_a
@@ -155,7 +209,8 @@ impl<E: Event> Events<E> {
 
 #[derive(Debug)]
 struct EventSequence<E: Event> {
-    events: Vec<EventInstance<E>>,
+    /// A [`VecDeque`], as retained events can be dropped from the front on overflow.
+    events: VecDeque<EventInstance<E>>,
     start_event_count: usize,
 }
 
@@ -170,7 +225,7 @@ impl<E: Event> Default for EventSequence<E> {
 }
 
 impl<E: Event> Deref for EventSequence<E> {
-    type Target = Vec<EventInstance<E>>;
+    type Target = VecDeque<EventInstance<E>>;
 
     fn deref(&self) -> &Self::Target {
         &self.events
@@ -186,10 +241,40 @@ impl<E: Event> DerefMut for EventSequence<E> {
 /// Reads events of type `T` in order and tracks which events have already been read.
 #[derive(SystemParam)]
 pub struct EventReader<'w, 's, E: Event> {
-    reader: Local<'s, ManualEventReader<E>>,
+    reader: Local<'s, SystemEventReader<E>>,
     events: Res<'w, Events<E>>,
 }
 
+/// The [`ManualEventReader`] of an [`EventReader`], registered to the [`Events`] if they retain
+/// events.
+#[derive(Debug)]
+pub struct SystemEventReader<E: Event>(ManualEventReader<E>);
+
+impl<E: Event> FromWorld for SystemEventReader<E> {
+    fn from_world(world: &mut World) -> Self {
+        match world.get_resource_mut::<Events<E>>() {
+            Some(mut events) if events.retention().is_some() => {
+                SystemEventReader(events.bypass_change_detection().register_reader())
+            }
+            _ => SystemEventReader(ManualEventReader::default()),
+        }
+    }
+}
+
+impl<E: Event> Deref for SystemEventReader<E> {
+    type Target = ManualEventReader<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<E: Event> DerefMut for SystemEventReader<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 impl<'w, 's, E: Event> EventReader<'w, 's, E> {
     /// Iterates over the events this [`EventReader`] has not seen yet. This updates the
     /// [`EventReader`]'s event counter, which means subsequent event reads will not include events
@@ -324,6 +409,8 @@ impl<'w, 's, E: Event> EventWriter<'w, 's, E> {
 #[derive(Debug)]
 pub struct ManualEventReader<E: Event> {
     last_event_count: usize,
+    /// Shares `last_event_count` with the [`Events`] this reader is registered to.
+    cursor: Option<Arc<AtomicUsize>>,
     _marker: PhantomData<E>,
 }
 
@@ -331,6 +418,7 @@ impl<E: Event> Default for ManualEventReader<E> {
     fn default() -> Self {
         ManualEventReader {
             last_event_count: 0,
+            cursor: None,
             _marker: Default::default(),
         }
     }
@@ -354,19 +442,29 @@ impl<E: Event> ManualEventReader<E> {
            + ExactSizeIterator<Item = (&'a E, EventId<E>)> {
         let a_index = (self.last_event_count).saturating_sub(events.events_a.start_event_count);
         let b_index = (self.last_event_count).saturating_sub(events.events_b.start_event_count);
-        let a = events.events_a.get(a_index..).unwrap_or_default();
-        let b = events.events_b.get(b_index..).unwrap_or_default();
+        let a = events.events_a.range(a_index.min(events.events_a.len())..);
+        let b = events.events_b.range(b_index.min(events.events_b.len())..);
 
         let unread_count = a.len() + b.len();
         // Ensure `len` is implemented correctly
         debug_assert_eq!(unread_count, self.len(events));
         self.last_event_count = events.event_count - unread_count;
+        let last_event_count = &mut self.last_event_count;
+        let cursor = self.cursor.as_deref();
+        if let Some(cursor) = cursor {
+            cursor.fetch_max(*last_event_count, Ordering::Relaxed);
+        }
         // Iterate the oldest first, then the newer events
-        let iterator = a.iter().chain(b.iter());
+        let iterator = a.chain(b);
         iterator
             .map(|e| (&e.event, e.event_id))
             .with_exact_size(unread_count)
-            .inspect(move |(_, id)| self.last_event_count = (id.id + 1).max(self.last_event_count))
+            .inspect(move |(_, id)| {
+                *last_event_count = (id.id + 1).max(*last_event_count);
+                if let Some(cursor) = cursor {
+                    cursor.fetch_max(*last_event_count, Ordering::Relaxed);
+                }
+            })
     }
 
     /// See [`EventReader::len`]
@@ -449,9 +547,67 @@ impl<I: Iterator> ExactSizeIterator for ExactSize<I> {
 }
 
 impl<E: Event> Events<E> {
+    /// Creates [`Events`] that retain events until all their registered readers have read them.
+    pub fn with_retention(retention: EventRetention) -> Self {
+        Self {
+            retention: Some(retention),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the [`EventRetention`] of these [`Events`], if they were created with
+    /// [`Events::with_retention`] or given one with [`Events::set_retention`].
+    pub fn retention(&self) -> Option<EventRetention> {
+        self.retention
+    }
+
+    /// Makes these [`Events`] retain events until all their registered readers have read them.
+    ///
+    /// Only the readers registered from now on retain events, and the events already stored
+    /// beyond the new capacity are kept until they are read.
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        self.retention = Some(retention);
+    }
+
+    /// Gets a new [`ManualEventReader`] registered to these [`Events`]. This will include all events
+    /// already in the event buffers.
+    ///
+    /// If these [`Events`] retain events, the events are kept until this reader has read them, or
+    /// until it is dropped.
+    pub fn register_reader(&mut self) -> ManualEventReader<E> {
+        let last_event_count = self.oldest_event_count();
+        let cursor = Arc::new(AtomicUsize::new(last_event_count));
+        self.readers.push(Arc::downgrade(&cursor));
+        ManualEventReader {
+            last_event_count,
+            cursor: Some(cursor),
+            _marker: PhantomData,
+        }
+    }
+
     /// "Sends" an `event` by writing it to the current event buffer. [`EventReader`]s can then read
     /// the event.
     pub fn send(&mut self, event: E) {
+        if let Some(retention) = self.retention {
+            if self.len() >= retention.capacity {
+                match retention.overflow {
+                    EventOverflow::DropOldest => self.drop_oldest(),
+                    EventOverflow::DropNewest => {
+                        warn!(
+                            "Events<{}> are full, dropping the sent event",
+                            std::any::type_name::<E>()
+                        );
+                        return;
+                    }
+                    EventOverflow::Panic => panic!(
+                        "Events<{}> exceeded their capacity of {} events",
+                        std::any::type_name::<E>(),
+                        retention.capacity
+                    ),
+                }
+            }
+        }
+
         let event_id = EventId {
             id: self.event_count,
             _marker: PhantomData,
@@ -460,7 +616,7 @@ impl<E: Event> Events<E> {
 
         let event_instance = EventInstance { event_id, event };
 
-        self.events_b.push(event_instance);
+        self.events_b.push_back(event_instance);
         self.event_count += 1;
     }
 
@@ -488,7 +644,14 @@ impl<E: Event> Events<E> {
 
     /// Swaps the event buffers and clears the oldest event buffer. In general, this should be
     /// called once per frame/update.
+    ///
+    /// If these [`Events`] retain events, the oldest events are only cleared once all registered
+    /// readers have read them.
     pub fn update(&mut self) {
+        if self.retention.is_some() {
+            self.update_retained();
+            return;
+        }
         std::mem::swap(&mut self.events_a, &mut self.events_b);
         self.events_b.clear();
         self.events_b.start_event_count = self.event_count;
@@ -498,6 +661,45 @@ impl<E: Event> Events<E> {
         );
     }
 
+    fn update_retained(&mut self) {
+        self.readers.retain(|cursor| cursor.strong_count() > 0);
+        let read_event_count = self
+            .readers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|cursor| cursor.load(Ordering::Relaxed))
+            .min()
+            .unwrap_or(usize::MAX);
+        // Like the double buffer, keep the events sent since the last update.
+        let keep_from = read_event_count.min(self.events_b.start_event_count);
+        let dropped = keep_from
+            .saturating_sub(self.events_a.start_event_count)
+            .min(self.events_a.len());
+        self.events_a.drain(..dropped);
+        self.events_a.start_event_count += dropped;
+
+        let events_b = std::mem::take(&mut self.events_b.events);
+        self.events_a.extend(events_b);
+        self.events_b.start_event_count = self.event_count;
+        debug_assert_eq!(
+            self.events_a.start_event_count + self.events_a.len(),
+            self.events_b.start_event_count
+        );
+    }
+
+    fn drop_oldest(&mut self) {
+        if self.events_a.is_empty() {
+            if !self.events_b.is_empty() {
+                self.events_b.pop_front();
+                self.events_b.start_event_count += 1;
+            }
+            self.events_a.start_event_count = self.events_b.start_event_count;
+        } else {
+            self.events_a.pop_front();
+            self.events_a.start_event_count += 1;
+        }
+    }
+
     /// A system that calls [`Events::update`] once per frame.
     pub fn update_system(mut events: ResMut<Self>) {
         events.update();
@@ -557,6 +759,13 @@ impl<E: Event> std::iter::Extend<E> for Events<E> {
     where
         I: IntoIterator<Item = E>,
     {
+        if self.retention.is_some() {
+            for event in iter {
+                self.send(event);
+            }
+            return;
+        }
+
         let mut event_count = self.event_count;
         let events = iter.into_iter().map(|event| {
             let event_id = EventId {
@@ -850,6 +1059,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_events_retention() {
+        let mut events =
+            Events::<TestEvent>::with_retention(EventRetention::new(8, EventOverflow::Panic));
+        let mut reader = events.register_reader();
+
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.update();
+        events.update();
+        events.send(TestEvent { i: 1 });
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }],
+            "registered readers don't miss events"
+        );
+
+        events.update();
+        events.update();
+        assert!(events.is_empty(), "read events are dropped");
+
+        events.send(TestEvent { i: 2 });
+        drop(reader);
+        events.update();
+        events.update();
+        assert!(events.is_empty(), "dropped readers don't retain events");
+    }
+
+    #[test]
+    fn test_events_retention_overflow() {
+        let mut events =
+            Events::<TestEvent>::with_retention(EventRetention::new(2, EventOverflow::DropOldest));
+        let mut reader = events.register_reader();
+        events.extend((0..3).map(|i| TestEvent { i }));
+        assert_eq!(reader.missed_events(&events), 1);
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 1 }, TestEvent { i: 2 }]
+        );
+
+        let mut events =
+            Events::<TestEvent>::with_retention(EventRetention::new(2, EventOverflow::DropNewest));
+        let mut reader = events.register_reader();
+        events.extend((0..3).map(|i| TestEvent { i }));
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeded their capacity of 1 events")]
+    fn test_events_retention_overflow_panic() {
+        let mut events =
+            Events::<TestEvent>::with_retention(EventRetention::new(1, EventOverflow::Panic));
+        let _reader = events.register_reader();
+        events.send(TestEvent { i: 0 });
+        events.send(TestEvent { i: 1 });
+    }
+
+    #[test]
+    fn test_event_reader_retention() {
+        use bevy_ecs::prelude::*;
+
+        let mut world = World::new();
+        world.insert_resource(Events::<TestEvent>::with_retention(EventRetention::new(
+            8,
+            EventOverflow::Panic,
+        )));
+        let mut reader =
+            IntoSystem::into_system(|mut events: EventReader<TestEvent>| events.iter().count());
+        reader.initialize(&mut world);
+
+        world.send_event(TestEvent { i: 0 });
+        for _ in 0..3 {
+            world.resource_mut::<Events<TestEvent>>().update();
+        }
+        assert_eq!(reader.run((), &mut world), 1);
+        assert_eq!(reader.run((), &mut world), 0);
+    }
+
     #[test]
     fn ensure_reader_readonly() {
         fn read_for<E: Event>() {
@@ -0,0 +1,652 @@
+//! An editor-agnostic view of the reflected state of a [`World`], and edits of it.
+//!
+//! [`WorldInspector`] lists the entities of a world with their name, hierarchy and components, and
+//! the resources of the world, using the types registered in a [`TypeRegistry`]. Components and
+//! resources are read and edited by path: the name of their type, followed by a
+//! [`GetPath`] path, like `"Transform.translation.x"`.
+//!
+//! The crates defining names and hierarchies expose them to the inspector by registering the
+//! [`EntityName`], [`EntityParent`] and [`EntityChildren`] traits with `#[reflect(EntityName)]`,
+//! `#[reflect(EntityParent)]` and `#[reflect(EntityChildren)]`.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ecs::inspector::{self, WorldInspector};
+//! # use bevy_reflect::{Reflect, TypeRegistry};
+//! #[derive(Component, Reflect, Default)]
+//! #[reflect(Component)]
+//! struct Position {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! let mut registry = TypeRegistry::default();
+//! registry.register::<Position>();
+//!
+//! let mut world = World::new();
+//! let entity = world.spawn(Position { x: 0.0, y: 0.0 }).id();
+//!
+//! inspector::edit_component(&mut world, &registry, entity, "Position.x", &3.0f32).unwrap();
+//! let inspector = WorldInspector::new(&world, &registry);
+//! let x = inspector.component(entity, "Position.x").unwrap();
+//! assert_eq!(x.downcast_ref::<f32>(), Some(&3.0));
+//! ```
+
+use std::{any::TypeId, borrow::Cow, fmt};
+
+use bevy_reflect::{reflect_trait, GetPath, Reflect, TypeRegistration, TypeRegistry};
+
+use crate::{
+    component::ComponentId,
+    entity::Entity,
+    reflect::{ReflectComponent, ReflectResource},
+    world::World,
+};
+
+/// A component naming its entity in a [`WorldInspector`].
+#[reflect_trait]
+pub trait EntityName {
+    /// The name of the entity.
+    fn entity_name(&self) -> &str;
+}
+
+/// A component giving the parent of its entity in a [`WorldInspector`].
+#[reflect_trait]
+pub trait EntityParent {
+    /// The parent of the entity.
+    fn parent_entity(&self) -> Entity;
+}
+
+/// A component listing the children of its entity in a [`WorldInspector`].
+#[reflect_trait]
+pub trait EntityChildren {
+    /// The children of the entity, in order.
+    fn child_entities(&self) -> &[Entity];
+}
+
+/// A read-only view of the entities, components and resources of a [`World`], through the types
+/// registered in a [`TypeRegistry`].
+pub struct WorldInspector<'w> {
+    world: &'w World,
+    registry: &'w TypeRegistry,
+}
+
+/// An entity listed by a [`WorldInspector`].
+#[derive(Debug, Clone)]
+pub struct InspectedEntity<'w> {
+    pub entity: Entity,
+    /// The name given by a component registered with [`ReflectEntityName`].
+    pub name: Option<&'w str>,
+    pub parent: Option<Entity>,
+    pub children: Vec<Entity>,
+    pub components: Vec<InspectedValue<'w>>,
+}
+
+/// A component or resource listed by a [`WorldInspector`].
+#[derive(Clone)]
+pub struct InspectedValue<'w> {
+    /// The full name of the type.
+    pub type_name: Cow<'w, str>,
+    /// The [`TypeId`] of the type, if it is a Rust type.
+    pub type_id: Option<TypeId>,
+    /// The reflected value, if the type is registered with [`ReflectComponent`] or
+    /// [`ReflectResource`].
+    pub value: Option<&'w dyn Reflect>,
+}
+
+impl<'w> fmt::Debug for InspectedValue<'w> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InspectedValue")
+            .field("type_name", &self.type_name)
+            .field("type_id", &self.type_id)
+            .field("reflected", &self.value.is_some())
+            .finish()
+    }
+}
+
+impl<'w> WorldInspector<'w> {
+    pub fn new(world: &'w World, registry: &'w TypeRegistry) -> Self {
+        Self { world, registry }
+    }
+
+    /// All the entities of the world, ordered by [`Entity`].
+    pub fn entity_ids(&self) -> Vec<Entity> {
+        let mut entities: Vec<_> = self
+            .world
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.entities())
+            .map(|archetype_entity| archetype_entity.entity())
+            .collect();
+        entities.sort_unstable();
+        entities
+    }
+
+    /// The entities without a parent, ordered by [`Entity`].
+    pub fn roots(&self) -> Vec<Entity> {
+        self.entity_ids()
+            .into_iter()
+            .filter(|entity| self.parent(*entity).is_none())
+            .collect()
+    }
+
+    /// All the entities of the world, ordered by [`Entity`].
+    pub fn entities(&self) -> Vec<InspectedEntity<'w>> {
+        self.entity_ids()
+            .into_iter()
+            .filter_map(|entity| self.entity(entity))
+            .collect()
+    }
+
+    /// Returns the name, hierarchy and components of `entity`, if it exists.
+    pub fn entity(&self, entity: Entity) -> Option<InspectedEntity<'w>> {
+        let components = self.component_ids(entity)?;
+        let components = components
+            .into_iter()
+            .map(|component_id| self.inspect_component(entity, component_id))
+            .collect();
+        Some(InspectedEntity {
+            entity,
+            name: self.name(entity),
+            parent: self.parent(entity),
+            children: self.children(entity),
+            components,
+        })
+    }
+
+    /// Returns the name of `entity`, given by a component registered with [`ReflectEntityName`].
+    pub fn name(&self, entity: Entity) -> Option<&'w str> {
+        self.reflected_components(entity)
+            .find_map(|(registration, value)| {
+                registration
+                    .data::<ReflectEntityName>()
+                    .and_then(|reflect_name| reflect_name.get(value))
+            })
+            .map(|name| name.entity_name())
+    }
+
+    /// Returns the parent of `entity`, given by a component registered with
+    /// [`ReflectEntityParent`].
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.reflected_components(entity)
+            .find_map(|(registration, value)| {
+                registration
+                    .data::<ReflectEntityParent>()
+                    .and_then(|reflect_parent| reflect_parent.get(value))
+            })
+            .map(|parent| parent.parent_entity())
+    }
+
+    /// Returns the children of `entity`, given by a component registered with
+    /// [`ReflectEntityChildren`].
+    pub fn children(&self, entity: Entity) -> Vec<Entity> {
+        self.reflected_components(entity)
+            .find_map(|(registration, value)| {
+                registration
+                    .data::<ReflectEntityChildren>()
+                    .and_then(|reflect_children| reflect_children.get(value))
+            })
+            .map(|children| children.child_entities().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// The resources of the world registered with [`ReflectResource`].
+    pub fn resources(&self) -> Vec<InspectedValue<'w>> {
+        self.registry
+            .iter()
+            .filter_map(|registration| {
+                let value = registration
+                    .data::<ReflectResource>()?
+                    .reflect(self.world)?;
+                Some(InspectedValue {
+                    type_name: registration.type_name().into(),
+                    type_id: Some(registration.type_id()),
+                    value: Some(value),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the value at `path` in a component of `entity`, where `path` starts with the full
+    /// or short name of the component type, like `"Transform.translation"`.
+    pub fn component(&self, entity: Entity, path: &str) -> Result<&'w dyn Reflect, InspectorError> {
+        let (registration, field_path) = split_path(self.registry, path)?;
+        let reflect_component = reflect_component(registration)?;
+        if !self.world.entities().contains(entity) {
+            return Err(InspectorError::NoSuchEntity(entity));
+        }
+        let component = reflect_component
+            .reflect(self.world, entity)
+            .ok_or_else(|| InspectorError::MissingComponent {
+                entity,
+                type_name: registration.type_name(),
+            })?;
+        field(component, path, field_path)
+    }
+
+    /// Returns the value at `path` in a resource, where `path` starts with the full or short name
+    /// of the resource type, like `"Time.paused"`.
+    pub fn resource(&self, path: &str) -> Result<&'w dyn Reflect, InspectorError> {
+        let (registration, field_path) = split_path(self.registry, path)?;
+        let resource = reflect_resource(registration)?
+            .reflect(self.world)
+            .ok_or(InspectorError::MissingResource(registration.type_name()))?;
+        field(resource, path, field_path)
+    }
+
+    fn component_ids(&self, entity: Entity) -> Option<Vec<ComponentId>> {
+        let location = self.world.entities().get(entity)?;
+        let archetype = self.world.archetypes().get(location.archetype_id)?;
+        Some(archetype.components().collect())
+    }
+
+    fn inspect_component(&self, entity: Entity, component_id: ComponentId) -> InspectedValue<'w> {
+        let info = self.world.components().get_info(component_id).unwrap();
+        let registration = info
+            .type_id()
+            .and_then(|type_id| self.registry.get(type_id));
+        let value = registration
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .and_then(|reflect_component| reflect_component.reflect(self.world, entity));
+        InspectedValue {
+            type_name: info.name().into(),
+            type_id: info.type_id(),
+            value,
+        }
+    }
+
+    fn reflected_components(
+        &self,
+        entity: Entity,
+    ) -> impl Iterator<Item = (&'w TypeRegistration, &'w dyn Reflect)> + '_ {
+        let registry = self.registry;
+        let world = self.world;
+        self.component_ids(entity)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(move |component_id| {
+                let type_id = world.components().get_info(component_id)?.type_id()?;
+                let registration = registry.get(type_id)?;
+                let value = registration
+                    .data::<ReflectComponent>()?
+                    .reflect(world, entity)?;
+                Some((registration, value))
+            })
+    }
+}
+
+/// Sets the value at `path` in a component of `entity` to `value`, where `path` starts with the
+/// full or short name of the component type, like `"Transform.translation.x"`.
+///
+/// The component is marked as changed.
+pub fn edit_component(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    path: &str,
+    value: &dyn Reflect,
+) -> Result<(), InspectorError> {
+    let (registration, field_path) = split_path(registry, path)?;
+    let reflect_component = reflect_component(registration)?;
+    if !world.entities().contains(entity) {
+        return Err(InspectorError::NoSuchEntity(entity));
+    }
+    // Only the field is borrowed mutably, so the component is only marked as changed when set.
+    let mut component = reflect_component
+        .reflect_mut(world, entity)
+        .ok_or_else(|| InspectorError::MissingComponent {
+            entity,
+            type_name: registration.type_name(),
+        })?;
+    check_field(&*component, path, field_path, value)?;
+    set_field(&mut *component, path, field_path, value)
+}
+
+/// Sets the value at `path` in a resource to `value`, where `path` starts with the full or short
+/// name of the resource type, like `"Time.paused"`.
+///
+/// The resource is marked as changed.
+pub fn edit_resource(
+    world: &mut World,
+    registry: &TypeRegistry,
+    path: &str,
+    value: &dyn Reflect,
+) -> Result<(), InspectorError> {
+    let (registration, field_path) = split_path(registry, path)?;
+    let mut resource = reflect_resource(registration)?
+        .reflect_mut(world)
+        .ok_or(InspectorError::MissingResource(registration.type_name()))?;
+    check_field(&*resource, path, field_path, value)?;
+    set_field(&mut *resource, path, field_path, value)
+}
+
+/// Splits `path` into the registration of the type it starts with, and the path in it.
+fn split_path<'r, 'p>(
+    registry: &'r TypeRegistry,
+    path: &'p str,
+) -> Result<(&'r TypeRegistration, &'p str), InspectorError> {
+    // Type names can contain dots in generic parameters, such as `Handle<bevy_render::Image>`.
+    let mut depth = 0;
+    let split = path.char_indices().find_map(|(index, c)| {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            '.' | '[' if depth == 0 => return Some(index),
+            _ => {}
+        }
+        None
+    });
+    let (type_name, field_path) = match split {
+        Some(index) => (&path[..index], path[index..].trim_start_matches('.')),
+        None => (path, ""),
+    };
+    let registration = registry
+        .get_with_name(type_name)
+        .or_else(|| registry.get_with_short_name(type_name))
+        .ok_or_else(|| InspectorError::UnknownType(type_name.to_string()))?;
+    Ok((registration, field_path))
+}
+
+fn reflect_component(registration: &TypeRegistration) -> Result<&ReflectComponent, InspectorError> {
+    registration
+        .data::<ReflectComponent>()
+        .ok_or(InspectorError::NotAComponent(registration.type_name()))
+}
+
+fn reflect_resource(registration: &TypeRegistration) -> Result<&ReflectResource, InspectorError> {
+    registration
+        .data::<ReflectResource>()
+        .ok_or(InspectorError::NotAResource(registration.type_name()))
+}
+
+fn field<'a>(
+    value: &'a dyn Reflect,
+    path: &str,
+    field_path: &str,
+) -> Result<&'a dyn Reflect, InspectorError> {
+    if field_path.is_empty() {
+        return Ok(value);
+    }
+    value
+        .path(field_path)
+        .map_err(|err| InspectorError::InvalidPath {
+            path: path.to_string(),
+            error: err.to_string(),
+        })
+}
+
+fn check_field(
+    value: &dyn Reflect,
+    path: &str,
+    field_path: &str,
+    new_value: &dyn Reflect,
+) -> Result<(), InspectorError> {
+    let field = field(value, path, field_path)?;
+    if field.type_name() != new_value.type_name() {
+        return Err(InspectorError::TypeMismatch {
+            path: path.to_string(),
+            expected: field.type_name().to_string(),
+            found: new_value.type_name().to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn set_field(
+    value: &mut dyn Reflect,
+    path: &str,
+    field_path: &str,
+    new_value: &dyn Reflect,
+) -> Result<(), InspectorError> {
+    let field = if field_path.is_empty() {
+        value
+    } else {
+        value
+            .path_mut(field_path)
+            .map_err(|err| InspectorError::InvalidPath {
+                path: path.to_string(),
+                error: err.to_string(),
+            })?
+    };
+    field.apply(new_value);
+    Ok(())
+}
+
+/// An error reading or editing a [`World`] through a [`WorldInspector`] path.
+#[derive(Debug)]
+pub enum InspectorError {
+    /// The path doesn't start with the name of a registered type.
+    UnknownType(String),
+    /// The type isn't registered with [`ReflectComponent`].
+    NotAComponent(&'static str),
+    /// The type isn't registered with [`ReflectResource`].
+    NotAResource(&'static str),
+    NoSuchEntity(Entity),
+    MissingComponent {
+        entity: Entity,
+        type_name: &'static str,
+    },
+    MissingResource(&'static str),
+    /// The path doesn't lead to a field.
+    InvalidPath {
+        path: String,
+        error: String,
+    },
+    /// The edited field and the new value have different types.
+    TypeMismatch {
+        path: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl std::error::Error for InspectorError {}
+
+impl fmt::Display for InspectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InspectorError::UnknownType(type_name) => {
+                write!(f, "no type named `{}` is registered", type_name)
+            }
+            InspectorError::NotAComponent(type_name) => write!(
+                f,
+                "the type `{}` is not registered with ReflectComponent",
+                type_name
+            ),
+            InspectorError::NotAResource(type_name) => write!(
+                f,
+                "the type `{}` is not registered with ReflectResource",
+                type_name
+            ),
+            InspectorError::NoSuchEntity(entity) => {
+                write!(f, "the entity {:?} doesn't exist", entity)
+            }
+            InspectorError::MissingComponent { entity, type_name } => write!(
+                f,
+                "the entity {:?} doesn't have a `{}` component",
+                entity, type_name
+            ),
+            InspectorError::MissingResource(type_name) => {
+                write!(f, "the resource `{}` doesn't exist", type_name)
+            }
+            InspectorError::InvalidPath { path, error } => {
+                write!(f, "invalid path `{}`: {}", path, error)
+            }
+            InspectorError::TypeMismatch {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "cannot set `{}` of type `{}` to a value of type `{}`",
+                path, expected, found
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        edit_component, edit_resource, EntityChildren, EntityName, EntityParent, WorldInspector,
+    };
+    use crate::{
+        self as bevy_ecs,
+        component::Component,
+        entity::Entity,
+        inspector::{
+            InspectorError, ReflectEntityChildren, ReflectEntityName, ReflectEntityParent,
+        },
+        reflect::{ReflectComponent, ReflectResource},
+        system::Resource,
+        world::World,
+    };
+    use bevy_reflect::{Reflect, TypeRegistry};
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, EntityName)]
+    struct Name(String);
+
+    impl EntityName for Name {
+        fn entity_name(&self) -> &str {
+            &self.0
+        }
+    }
+
+    #[derive(Component, Reflect)]
+    #[reflect(Component, EntityParent)]
+    struct Parent(Entity);
+
+    impl Default for Parent {
+        fn default() -> Self {
+            Parent(Entity::from_raw(u32::MAX))
+        }
+    }
+
+    impl EntityParent for Parent {
+        fn parent_entity(&self) -> Entity {
+            self.0
+        }
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component, EntityChildren)]
+    struct Children(Vec<Entity>);
+
+    impl EntityChildren for Children {
+        fn child_entities(&self) -> &[Entity] {
+            &self.0
+        }
+    }
+
+    #[derive(Reflect, Default, PartialEq, Debug)]
+    struct Vec2 {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Position(Vec2);
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Gravity(Vec2);
+
+    #[derive(Component)]
+    struct NotReflected;
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Name>();
+        registry.register::<Parent>();
+        registry.register::<Children>();
+        registry.register::<Position>();
+        registry.register::<Gravity>();
+        registry
+    }
+
+    #[test]
+    fn list_entities() {
+        let registry = registry();
+        let mut world = World::new();
+        let parent = world.spawn(Name("parent".to_string())).id();
+        let child = world
+            .spawn((
+                Name("child".to_string()),
+                Position::default(),
+                NotReflected,
+                Parent(parent),
+            ))
+            .id();
+        world.entity_mut(parent).insert(Children(vec![child]));
+        world.insert_resource(Gravity(Vec2 { x: 0.0, y: -9.8 }));
+
+        let inspector = WorldInspector::new(&world, &registry);
+        assert_eq!(inspector.roots(), [parent]);
+        let entities = inspector.entities();
+        assert_eq!(entities.len(), 2);
+        let child = &entities[1];
+        assert_eq!(child.name, Some("child"));
+        assert_eq!(child.parent, Some(parent));
+        assert_eq!(child.components.len(), 4);
+        assert!(child
+            .components
+            .iter()
+            .any(|component| component.type_name.ends_with("NotReflected")
+                && component.value.is_none()));
+        assert_eq!(entities[0].children, [child.entity]);
+
+        let resources = inspector.resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(
+            inspector
+                .resource("Gravity.0.y")
+                .unwrap()
+                .downcast_ref::<f32>(),
+            Some(&-9.8)
+        );
+    }
+
+    #[test]
+    fn edit_by_path() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn(Position::default()).id();
+        world.insert_resource(Gravity::default());
+        world.clear_trackers();
+
+        edit_component(&mut world, &registry, entity, "Position.0.x", &3.0f32).unwrap();
+        let entity_ref = world.entity(entity);
+        assert_eq!(
+            entity_ref.get::<Position>().unwrap().0,
+            Vec2 { x: 3.0, y: 0.0 }
+        );
+        assert!(entity_ref
+            .get_change_ticks::<Position>()
+            .unwrap()
+            .is_changed(world.last_change_tick(), world.read_change_tick()));
+
+        let full_path = format!("{}.0", std::any::type_name::<Gravity>());
+        edit_resource(&mut world, &registry, &full_path, &Vec2 { x: 1.0, y: 2.0 }).unwrap();
+        assert_eq!(world.resource::<Gravity>().0, Vec2 { x: 1.0, y: 2.0 });
+        assert!(world.is_resource_changed::<Gravity>());
+
+        assert!(matches!(
+            edit_component(&mut world, &registry, entity, "Position.0.x", &3.0f64),
+            Err(InspectorError::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            edit_component(&mut world, &registry, entity, "Position.0.z", &3.0f32),
+            Err(InspectorError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            edit_component(&mut world, &registry, entity, "Velocity.0", &3.0f32),
+            Err(InspectorError::UnknownType(_))
+        ));
+        assert!(matches!(
+            edit_component(&mut world, &registry, entity, "Name.0", &String::new()),
+            Err(InspectorError::MissingComponent { .. })
+        ));
+    }
+}
@@ -10,10 +10,15 @@ pub mod change_detection;
 pub mod component;
 pub mod entity;
 pub mod event;
+#[cfg(feature = "bevy_reflect")]
+pub mod inspector;
 pub mod query;
 #[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod relation;
 pub mod schedule;
+#[cfg(feature = "bevy_reflect")]
+pub mod snapshot;
 pub mod storage;
 pub mod system;
 pub mod world;
@@ -34,8 +39,9 @@ pub mod prelude {
         event::{EventReader, EventWriter, Events},
         query::{Added, AnyOf, ChangeTrackers, Changed, Or, QueryState, With, Without},
         schedule::{
-            IntoSystemDescriptor, RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaLabel,
-            Schedule, Stage, StageLabel, State, SystemLabel, SystemSet, SystemStage,
+            common_conditions::*, IntoRunCondition, IntoSystemDescriptor, RunCriteria,
+            RunCriteriaDescriptorCoercion, RunCriteriaLabel, Schedule, Stage, StageLabel, State,
+            SystemLabel, SystemSet, SystemStage,
         },
         system::{
             adapter as system_adapter, Commands, In, IntoPipeSystem, IntoSystem, Local, NonSend,
@@ -57,7 +63,8 @@ mod tests {
         component::{Component, ComponentId},
         entity::Entity,
         query::{
-            Added, ChangeTrackers, Changed, FilteredAccess, ReadOnlyWorldQuery, With, Without,
+            Added, ChangeTrackers, Changed, Disabled, FilteredAccess, IncludeDisabled,
+            ReadOnlyWorldQuery, With, Without,
         },
         system::Resource,
         world::{Mut, World},
@@ -1282,14 +1289,41 @@ mod tests {
         let mut expected = FilteredAccess::<ComponentId>::default();
         let a_id = world.components.get_id(TypeId::of::<A>()).unwrap();
         let b_id = world.components.get_id(TypeId::of::<B>()).unwrap();
+        let disabled_id = world.components.get_id(TypeId::of::<Disabled>()).unwrap();
         expected.add_write(a_id);
         expected.add_read(b_id);
+        // Queries skip disabled entities by default.
+        expected.add_without(disabled_id);
         assert!(
             query.component_access.eq(&expected),
             "ComponentId access from query fetch and query filter should be combined"
         );
     }
 
+    #[test]
+    fn disabled_entities_are_skipped_by_default() {
+        let mut world = World::new();
+        let enabled = world.spawn(A(1)).id();
+        let disabled = world.spawn((A(2), Disabled)).id();
+
+        let mut query = world.query::<Entity>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), [enabled]);
+        assert!(query.get(&world, disabled).is_err());
+        let mut query = world.query_filtered::<Entity, With<Disabled>>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), [disabled]);
+        let mut query = world.query::<(Entity, Option<&Disabled>)>();
+        assert_eq!(query.iter(&world).count(), 2);
+        let mut query = world.query_filtered::<&A, IncludeDisabled>();
+        assert_eq!(query.iter(&world).count(), 2);
+
+        // Re-enabled entities keep their components.
+        world.entity_mut(disabled).remove::<Disabled>();
+        let mut query = world.query::<&A>();
+        let mut values: Vec<_> = query.iter(&world).map(|a| a.0).collect();
+        values.sort_unstable();
+        assert_eq!(values, [1, 2]);
+    }
+
     #[test]
     #[should_panic]
     fn multiple_worlds_same_query_get() {
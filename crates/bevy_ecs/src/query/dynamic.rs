@@ -0,0 +1,759 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
+    change_detection::{MutUntyped, Ticks},
+    component::ComponentId,
+    entity::{Entity, EntityLocation},
+    query::{Access, Disabled, FilteredAccess, QueryEntityError},
+    world::{get_component_and_ticks, World, WorldId},
+};
+use bevy_ptr::{Ptr, UnsafeCellDeref};
+use fixedbitset::FixedBitSet;
+use std::fmt;
+
+/// Builds a [`DynamicQueryState`] from [`ComponentId`]s known only at runtime.
+///
+/// This is the untyped counterpart of a [`Query`](crate::system::Query), for code that discovers
+/// components at runtime, like scripting languages or editors using the type registry.
+///
+/// Each [`read`](Self::read) or [`write`](Self::write) term adds a component to the items of the
+/// query, in the order the terms were added. [`with`](Self::with) and [`without`](Self::without)
+/// terms only filter the matched entities.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::query::QueryBuilder;
+/// #[derive(Component)]
+/// struct Health(u32);
+/// #[derive(Component)]
+/// struct Dead;
+///
+/// let mut world = World::new();
+/// world.spawn(Health(10));
+/// world.spawn((Health(0), Dead));
+///
+/// let health = world.init_component::<Health>();
+/// let dead = world.init_component::<Dead>();
+/// let mut query = QueryBuilder::new(&world).write(health).without(dead).build();
+///
+/// for mut item in query.iter_mut(&mut world) {
+///     let health = item.components_mut()[0].as_mut().unwrap();
+///     health.set_changed();
+///     // SAFETY: the component with the id `health` is a `Health`.
+///     unsafe { health.bypass_change_detection().as_ptr().cast::<Health>().as_mut() }
+///         .unwrap()
+///         .0 += 1;
+/// }
+/// # let mut query = world.query::<&Health>();
+/// # let mut values: Vec<_> = query.iter(&world).map(|h| h.0).collect();
+/// # values.sort();
+/// # assert_eq!(values, [0, 11]);
+/// ```
+pub struct QueryBuilder<'w> {
+    world: &'w World,
+    component_access: FilteredAccess<ComponentId>,
+    fetches: Vec<DynamicFetch>,
+    filters: Vec<(ComponentId, bool)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DynamicFetch {
+    component_id: ComponentId,
+    mutable: bool,
+}
+
+impl<'w> QueryBuilder<'w> {
+    /// Creates a builder for a query on `world` without any term.
+    pub fn new(world: &'w World) -> Self {
+        Self {
+            world,
+            component_access: FilteredAccess::default(),
+            fetches: Vec::new(),
+            filters: Vec::new(),
+        }
+    }
+
+    /// The world the query is built for, to look up the [`ComponentId`]s of the terms.
+    pub fn world(&self) -> &'w World {
+        self.world
+    }
+
+    /// Fetches a shared [`Ptr`] to the component with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is not registered in the world, or if it is already written by
+    /// this query.
+    pub fn read(&mut self, component_id: ComponentId) -> &mut Self {
+        let name = self.component_name(component_id);
+        assert!(
+            !self.component_access.access().has_write(component_id),
+            "&{} conflicts with a previous access in this query. Shared access cannot coincide with exclusive access.",
+            name,
+        );
+        self.component_access.add_read(component_id);
+        self.fetches.push(DynamicFetch {
+            component_id,
+            mutable: false,
+        });
+        self
+    }
+
+    /// Fetches a [`MutUntyped`] to the component with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is not registered in the world, or if it is already read or
+    /// written by this query.
+    pub fn write(&mut self, component_id: ComponentId) -> &mut Self {
+        let name = self.component_name(component_id);
+        assert!(
+            !self.component_access.access().has_read(component_id),
+            "&mut {} conflicts with a previous access in this query. Mutable component access must be unique.",
+            name,
+        );
+        self.component_access.add_write(component_id);
+        self.fetches.push(DynamicFetch {
+            component_id,
+            mutable: true,
+        });
+        self
+    }
+
+    /// Only matches entities that have the component with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is not registered in the world.
+    pub fn with(&mut self, component_id: ComponentId) -> &mut Self {
+        self.component_name(component_id);
+        self.component_access.add_with(component_id);
+        self.filters.push((component_id, true));
+        self
+    }
+
+    /// Only matches entities that don't have the component with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component is not registered in the world.
+    pub fn without(&mut self, component_id: ComponentId) -> &mut Self {
+        self.component_name(component_id);
+        self.component_access.add_without(component_id);
+        self.filters.push((component_id, false));
+        self
+    }
+
+    /// Matches entities whether they are [`Disabled`] or not, like the [`IncludeDisabled`] filter.
+    ///
+    /// [`IncludeDisabled`]: crate::query::IncludeDisabled
+    pub fn include_disabled(&mut self) -> &mut Self {
+        let disabled = self.disabled_id();
+        self.component_access.access_mut().add_read(disabled);
+        self
+    }
+
+    /// Creates the [`DynamicQueryState`] of the query, matching the archetypes of the world.
+    ///
+    /// Like other queries, the query skips [`Disabled`] entities unless one of its terms is
+    /// [`Disabled`] or [`include_disabled`](Self::include_disabled) was called.
+    pub fn build(&mut self) -> DynamicQueryState {
+        let mut component_access = self.component_access.clone();
+        let mut filters = self.filters.clone();
+        let disabled = self.disabled_id();
+        if !component_access.access().has_read(disabled)
+            && !component_access.has_with(disabled)
+            && !component_access.has_without(disabled)
+        {
+            component_access.add_without(disabled);
+            filters.push((disabled, false));
+        }
+        let mut state = DynamicQueryState {
+            world_id: self.world.id(),
+            archetype_generation: ArchetypeGeneration::initial(),
+            matched_archetypes: FixedBitSet::default(),
+            matched_archetype_ids: Vec::new(),
+            archetype_component_access: Access::default(),
+            component_access,
+            fetches: self.fetches.clone(),
+            filters,
+        };
+        state.update_archetypes(self.world);
+        state
+    }
+
+    fn disabled_id(&self) -> ComponentId {
+        // `Disabled` is registered when the world is created.
+        self.world.components().component_id::<Disabled>().unwrap()
+    }
+
+    fn component_name(&self, component_id: ComponentId) -> &'w str {
+        match self.world.components().get_info(component_id) {
+            Some(info) => info.name(),
+            None => panic!(
+                "{:?} is not registered in the World the query is built for.",
+                component_id
+            ),
+        }
+    }
+}
+
+/// The state of a query built at runtime with a [`QueryBuilder`].
+///
+/// Like a [`QueryState`](crate::query::QueryState), it caches the archetypes matched by the
+/// query, and can only be used with the [`World`] it was built for. Its
+/// [`component_access`](Self::component_access) lets it be checked against other queries, and
+/// the [`DynamicQuery`](crate::system::DynamicQuery) system parameter runs it in systems.
+pub struct DynamicQueryState {
+    world_id: WorldId,
+    archetype_generation: ArchetypeGeneration,
+    matched_archetypes: FixedBitSet,
+    matched_archetype_ids: Vec<ArchetypeId>,
+    archetype_component_access: Access<ArchetypeComponentId>,
+    component_access: FilteredAccess<ComponentId>,
+    fetches: Vec<DynamicFetch>,
+    filters: Vec<(ComponentId, bool)>,
+}
+
+impl fmt::Debug for DynamicQueryState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicQueryState")
+            .field("fetches", &self.fetches)
+            .field("filters", &self.filters)
+            .field("matched_archetypes", &self.matched_archetype_ids.len())
+            .finish()
+    }
+}
+
+impl DynamicQueryState {
+    /// The components accessed by the query, with the filters restricting the matched entities.
+    pub fn component_access(&self) -> &FilteredAccess<ComponentId> {
+        &self.component_access
+    }
+
+    /// The archetype components accessed by the query in the archetypes matched so far.
+    pub fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    /// Returns `true` if the query has no [`write`](QueryBuilder::write) term.
+    pub fn is_read_only(&self) -> bool {
+        !self.fetches.iter().any(|fetch| fetch.mutable)
+    }
+
+    /// Checks that `world` is the world the query was built for, and matches its new archetypes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `world.id()` does not equal the id of the world the query was built for.
+    pub fn update_archetypes(&mut self, world: &World) {
+        self.validate_world(world);
+        let archetypes = world.archetypes();
+        let new_generation = archetypes.generation();
+        let old_generation = std::mem::replace(&mut self.archetype_generation, new_generation);
+        for archetype_index in old_generation.value()..new_generation.value() {
+            self.new_archetype(&archetypes[ArchetypeId::new(archetype_index)]);
+        }
+    }
+
+    #[inline]
+    pub fn validate_world(&self, world: &World) {
+        assert!(
+            world.id() == self.world_id,
+            "Attempted to use a DynamicQueryState with a mismatched World. DynamicQueryStates can only be used with the World they were built for.",
+        );
+    }
+
+    /// Matches the query against a new [`Archetype`].
+    pub fn new_archetype(&mut self, archetype: &Archetype) {
+        if !self.matches_archetype(archetype) {
+            return;
+        }
+        for fetch in &self.fetches {
+            let id = archetype
+                .get_archetype_component_id(fetch.component_id)
+                .unwrap();
+            if fetch.mutable {
+                self.archetype_component_access.add_write(id);
+            } else {
+                self.archetype_component_access.add_read(id);
+            }
+        }
+        let archetype_index = archetype.id().index();
+        if !self.matched_archetypes.contains(archetype_index) {
+            self.matched_archetypes.grow(archetype_index + 1);
+            self.matched_archetypes.set(archetype_index, true);
+            self.matched_archetype_ids.push(archetype.id());
+        }
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        self.fetches
+            .iter()
+            .all(|fetch| archetype.contains(fetch.component_id))
+            && self
+                .filters
+                .iter()
+                .all(|&(component_id, with)| archetype.contains(component_id) == with)
+    }
+
+    /// Iterates over the entities matched by the query, with read-only access to all the fetched
+    /// components.
+    pub fn iter<'w, 's>(&'s mut self, world: &'w World) -> DynamicQueryIter<'w, 's> {
+        self.update_archetypes(world);
+        self.iter_manual(world)
+    }
+
+    /// Iterates over the entities matched by the query, with mutable access to the components of
+    /// [`write`](QueryBuilder::write) terms.
+    pub fn iter_mut<'w, 's>(&'s mut self, world: &'w mut World) -> DynamicQueryIter<'w, 's> {
+        self.update_archetypes(world);
+        let change_tick = world.read_change_tick();
+        // SAFETY: the world is borrowed mutably
+        unsafe { self.iter_unchecked_manual(world, world.last_change_tick(), change_tick) }
+    }
+
+    /// Iterates over the entities matched by the query, with read-only access to all the fetched
+    /// components, without matching the new archetypes of `world`.
+    ///
+    /// This should only be called after [`update_archetypes`](Self::update_archetypes), or
+    /// entities in new archetypes will be skipped.
+    pub fn iter_manual<'w, 's>(&'s self, world: &'w World) -> DynamicQueryIter<'w, 's> {
+        self.validate_world(world);
+        DynamicQueryIter::new(
+            world,
+            self,
+            false,
+            world.last_change_tick(),
+            world.read_change_tick(),
+        )
+    }
+
+    /// Iterates over the entities matched by the query, with mutable access to the components of
+    /// [`write`](QueryBuilder::write) terms, without matching the new archetypes of `world`.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched [`WorldId`] is unsound.
+    pub unsafe fn iter_unchecked_manual<'w, 's>(
+        &'s self,
+        world: &'w World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicQueryIter<'w, 's> {
+        DynamicQueryIter::new(world, self, true, last_change_tick, change_tick)
+    }
+
+    /// Gets the query item of the given [`Entity`], with read-only access to all the fetched
+    /// components.
+    pub fn get<'w>(
+        &mut self,
+        world: &'w World,
+        entity: Entity,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        self.update_archetypes(world);
+        // SAFETY: the components are only read
+        unsafe {
+            self.get_unchecked_manual(
+                world,
+                entity,
+                false,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Gets the query item of the given [`Entity`], with mutable access to the components of
+    /// [`write`](QueryBuilder::write) terms.
+    pub fn get_mut<'w>(
+        &mut self,
+        world: &'w mut World,
+        entity: Entity,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        self.update_archetypes(world);
+        let change_tick = world.read_change_tick();
+        // SAFETY: the world is borrowed mutably
+        unsafe {
+            self.get_unchecked_manual(world, entity, true, world.last_change_tick(), change_tick)
+        }
+    }
+
+    /// Gets the query item of the given [`Entity`], without matching the new archetypes of
+    /// `world`.
+    ///
+    /// # Safety
+    ///
+    /// If `mutable` is `true`, this does not check for mutable query correctness. To be safe,
+    /// make sure mutable queries have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched [`WorldId`] is unsound.
+    pub unsafe fn get_unchecked_manual<'w>(
+        &self,
+        world: &'w World,
+        entity: Entity,
+        mutable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Result<DynamicQueryItem<'w>, QueryEntityError> {
+        let location = world
+            .entities()
+            .get(entity)
+            .ok_or(QueryEntityError::NoSuchEntity(entity))?;
+        if !self
+            .matched_archetypes
+            .contains(location.archetype_id.index())
+        {
+            return Err(QueryEntityError::QueryDoesNotMatch(entity));
+        }
+        Ok(self.fetch(
+            world,
+            entity,
+            location,
+            mutable,
+            last_change_tick,
+            change_tick,
+        ))
+    }
+
+    /// # Safety
+    ///
+    /// `location` must be the location of `entity`, in an archetype matched by the query. If
+    /// `mutable` is `true`, the caller must have unique access to the written components.
+    unsafe fn fetch<'w>(
+        &self,
+        world: &'w World,
+        entity: Entity,
+        location: EntityLocation,
+        mutable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicQueryItem<'w> {
+        let components = self
+            .fetches
+            .iter()
+            .map(|fetch| {
+                // SAFETY: the archetype of the entity contains all the fetched components
+                let (value, ticks) =
+                    get_component_and_ticks(world, fetch.component_id, entity, location).unwrap();
+                if mutable && fetch.mutable {
+                    DynamicComponent::Write(MutUntyped {
+                        value: value.assert_unique(),
+                        ticks: Ticks {
+                            component_ticks: ticks.deref_mut(),
+                            last_change_tick,
+                            change_tick,
+                        },
+                    })
+                } else {
+                    DynamicComponent::Read(value)
+                }
+            })
+            .collect();
+        DynamicQueryItem { entity, components }
+    }
+}
+
+/// The item of a [`DynamicQueryState`] for one entity: the components of its
+/// [`read`](QueryBuilder::read) and [`write`](QueryBuilder::write) terms, in the order the terms
+/// were added.
+#[derive(Debug)]
+pub struct DynamicQueryItem<'w> {
+    entity: Entity,
+    components: Vec<DynamicComponent<'w>>,
+}
+
+impl<'w> DynamicQueryItem<'w> {
+    /// The entity the components belong to.
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The fetched components, in the order of the terms of the query.
+    #[inline]
+    pub fn components(&self) -> &[DynamicComponent<'w>] {
+        &self.components
+    }
+
+    /// The fetched components, in the order of the terms of the query.
+    #[inline]
+    pub fn components_mut(&mut self) -> &mut [DynamicComponent<'w>] {
+        &mut self.components
+    }
+
+    /// Returns the fetched components, in the order of the terms of the query.
+    #[inline]
+    pub fn into_components(self) -> Vec<DynamicComponent<'w>> {
+        self.components
+    }
+}
+
+/// A component fetched by a [`DynamicQueryState`].
+pub enum DynamicComponent<'w> {
+    /// A [`read`](QueryBuilder::read) term, or any term of a read-only iteration.
+    Read(Ptr<'w>),
+    /// A [`write`](QueryBuilder::write) term of a mutable iteration.
+    Write(MutUntyped<'w>),
+}
+
+impl fmt::Debug for DynamicComponent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicComponent::Read(ptr) => f.debug_tuple("Read").field(&ptr.as_ptr()).finish(),
+            DynamicComponent::Write(value) => f.debug_tuple("Write").field(value).finish(),
+        }
+    }
+}
+
+impl<'w> DynamicComponent<'w> {
+    /// Returns a shared pointer to the component.
+    #[inline]
+    pub fn as_ptr(&self) -> Ptr<'_> {
+        match self {
+            DynamicComponent::Read(ptr) => *ptr,
+            // SAFETY: the pointer is valid, and borrowed immutably for the lifetime of the result
+            DynamicComponent::Write(value) => unsafe {
+                Ptr::new(std::ptr::NonNull::new_unchecked(value.value.as_ptr()))
+            },
+        }
+    }
+
+    /// Returns the mutable access to the component, or `None` if it was fetched read-only.
+    #[inline]
+    pub fn as_mut(&mut self) -> Option<&mut MutUntyped<'w>> {
+        match self {
+            DynamicComponent::Read(_) => None,
+            DynamicComponent::Write(value) => Some(value),
+        }
+    }
+
+    /// Returns the mutable access to the component, or `None` if it was fetched read-only.
+    #[inline]
+    pub fn into_mut(self) -> Option<MutUntyped<'w>> {
+        match self {
+            DynamicComponent::Read(_) => None,
+            DynamicComponent::Write(value) => Some(value),
+        }
+    }
+}
+
+/// An [`Iterator`] over the items of a [`DynamicQueryState`].
+pub struct DynamicQueryIter<'w, 's> {
+    world: &'w World,
+    state: &'s DynamicQueryState,
+    archetype_ids: std::slice::Iter<'s, ArchetypeId>,
+    archetype: Option<&'w Archetype>,
+    index: usize,
+    mutable: bool,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, 's> DynamicQueryIter<'w, 's> {
+    pub(crate) fn new(
+        world: &'w World,
+        state: &'s DynamicQueryState,
+        mutable: bool,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        Self {
+            world,
+            state,
+            archetype_ids: state.matched_archetype_ids.iter(),
+            archetype: None,
+            index: 0,
+            mutable,
+            last_change_tick,
+            change_tick,
+        }
+    }
+}
+
+impl<'w, 's> Iterator for DynamicQueryIter<'w, 's> {
+    type Item = DynamicQueryItem<'w>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(archetype) = self.archetype {
+                if self.index < archetype.len() {
+                    let entity = archetype.entities()[self.index].entity();
+                    let location = EntityLocation {
+                        archetype_id: archetype.id(),
+                        index: self.index,
+                    };
+                    self.index += 1;
+                    // SAFETY: the archetype is matched by the query, and every entity is yielded
+                    // at most once so mutable accesses are unique
+                    return Some(unsafe {
+                        self.state.fetch(
+                            self.world,
+                            entity,
+                            location,
+                            self.mutable,
+                            self.last_change_tick,
+                            self.change_tick,
+                        )
+                    });
+                }
+            }
+            let archetype_id = self.archetype_ids.next()?;
+            self.archetype = Some(&self.world.archetypes()[*archetype_id]);
+            self.index = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        prelude::*,
+        query::{Disabled, QueryBuilder},
+        system::{DynamicQuery, DynamicQueryTerms},
+    };
+
+    #[derive(Component, Debug, PartialEq)]
+    struct A(u32);
+    #[derive(Component, Debug, PartialEq)]
+    #[component(storage = "SparseSet")]
+    struct B(u32);
+    #[derive(Component)]
+    struct C;
+
+    #[test]
+    fn dynamic_query_terms() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        let b = world.init_component::<B>();
+        let c = world.init_component::<C>();
+        let e1 = world.spawn((A(1), B(10))).id();
+        world.spawn((A(2), B(20), C));
+        world.spawn(A(3));
+
+        let mut query = QueryBuilder::new(&world)
+            .read(a)
+            .write(b)
+            .without(c)
+            .build();
+        assert!(!query.is_read_only());
+        let items: Vec<_> = query.iter(&world).collect();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].entity(), e1);
+        // SAFETY: the components have the types of their ids
+        unsafe {
+            assert_eq!(items[0].components()[0].as_ptr().deref::<A>(), &A(1));
+            assert_eq!(items[0].components()[1].as_ptr().deref::<B>(), &B(10));
+        }
+        // Read-only iterations don't give mutable access.
+        assert!(items.into_iter().all(|item| item
+            .into_components()
+            .into_iter()
+            .all(|component| component.into_mut().is_none())));
+
+        let mut query = QueryBuilder::new(&world).read(a).with(c).build();
+        assert_eq!(query.iter(&world).count(), 1);
+        assert!(query.get(&world, e1).is_err());
+    }
+
+    #[test]
+    fn dynamic_query_writes_with_change_detection() {
+        let mut world = World::new();
+        let b = world.init_component::<B>();
+        let e1 = world.spawn(B(1)).id();
+        let mut query = QueryBuilder::new(&world).write(b).build();
+
+        // Entities spawned after the query is built are matched.
+        let e2 = world.spawn((B(2), A(0))).id();
+        world.clear_trackers();
+        for item in query.iter_mut(&mut world) {
+            let mut value = item.into_components().pop().unwrap().into_mut().unwrap();
+            assert!(!value.is_changed());
+            value.set_changed();
+            // SAFETY: the component is a `B`
+            unsafe { value.into_inner().deref_mut::<B>().0 *= 10 };
+        }
+        assert_eq!(world.get::<B>(e1), Some(&B(10)));
+        assert_eq!(world.get::<B>(e2), Some(&B(20)));
+        let mut changed = world.query_filtered::<Entity, Changed<B>>();
+        assert_eq!(changed.iter(&world).count(), 2);
+    }
+
+    struct WriteB;
+
+    impl DynamicQueryTerms for WriteB {
+        fn terms(builder: &mut QueryBuilder) {
+            let components = builder.world().components();
+            let b = components.component_id::<B>().unwrap();
+            let c = components.component_id::<C>().unwrap();
+            builder.write(b).without(c);
+        }
+    }
+
+    #[test]
+    fn dynamic_query_system_param() {
+        fn system(mut dynamic: DynamicQuery<WriteB>, query: Query<(&A, &B), With<C>>) {
+            assert_eq!(query.iter().count(), 1);
+            for item in dynamic.iter_mut() {
+                let value = item.into_components().pop().unwrap().into_mut().unwrap();
+                // SAFETY: the component is a `B`
+                unsafe { value.into_inner().deref_mut::<B>().0 += 1 };
+            }
+        }
+
+        let mut world = World::new();
+        world.init_component::<C>();
+        let e1 = world.spawn(B(1)).id();
+        let e2 = world.spawn((A(2), B(2), C)).id();
+        let mut system = IntoSystem::into_system(system);
+        system.initialize(&mut world);
+        system.update_archetype_component_access(&world);
+        system.run((), &mut world);
+        assert_eq!(world.get::<B>(e1), Some(&B(2)));
+        assert_eq!(world.get::<B>(e2), Some(&B(2)));
+        assert!(system
+            .component_access()
+            .has_write(world.init_component::<B>()));
+    }
+
+    #[test]
+    #[should_panic = "error[B0001]"]
+    fn conflicting_dynamic_query_system_param_panics() {
+        fn system(_dynamic: DynamicQuery<WriteB>, _query: Query<&B>) {}
+
+        let mut world = World::new();
+        world.init_component::<B>();
+        world.init_component::<C>();
+        IntoSystem::into_system(system).initialize(&mut world);
+    }
+
+    #[test]
+    fn dynamic_query_skips_disabled_entities() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        world.spawn(A(1));
+        world.spawn((A(2), Disabled));
+
+        assert_eq!(
+            QueryBuilder::new(&world)
+                .read(a)
+                .build()
+                .iter(&world)
+                .count(),
+            1
+        );
+        let mut query = QueryBuilder::new(&world).read(a).include_disabled().build();
+        assert_eq!(query.iter(&world).count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_terms_panic() {
+        let mut world = World::new();
+        let a = world.init_component::<A>();
+        QueryBuilder::new(&world).read(a).write(a);
+    }
+}
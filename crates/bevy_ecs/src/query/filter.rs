@@ -1,4 +1,5 @@
 use crate::{
+    self as bevy_ecs,
     archetype::{Archetype, ArchetypeComponentId},
     component::{Component, ComponentId, ComponentStorage, ComponentTicks, StorageType},
     entity::Entity,
@@ -219,6 +220,132 @@ unsafe impl<T: Component> WorldQuery for Without<T> {
 // SAFETY: no component access or archetype component access
 unsafe impl<T: Component> ReadOnlyWorldQuery for Without<T> {}
 
+/// Marker component of disabled entities, that queries skip by default.
+///
+/// Disabling an entity "turns it off" without despawning it: it keeps its components and
+/// their allocations, for example to pool entities or hide a closed menu. Queries that don't
+/// mention `Disabled` behave as if they had a [`Without<Disabled>`] filter. Queries that read
+/// `Disabled`, like `Query<&Disabled>` or `Query<Option<&Disabled>>`, or filter on it directly,
+/// like `Query<Entity, With<Disabled>>`, match disabled entities as usual.
+/// [`IncludeDisabled`] matches entities whether they are disabled or not.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::query::{Disabled, IncludeDisabled};
+/// #[derive(Component)]
+/// struct Bullet;
+///
+/// let mut world = World::new();
+/// world.spawn(Bullet);
+/// world.spawn((Bullet, Disabled));
+///
+/// assert_eq!(world.query::<&Bullet>().iter(&world).count(), 1);
+/// assert_eq!(world.query_filtered::<&Bullet, IncludeDisabled>().iter(&world).count(), 2);
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Disabled;
+
+/// Filter that selects entities whether they are [`Disabled`] or not.
+///
+/// Queries skip disabled entities by default. This filter reads [`Disabled`], so it conflicts
+/// with queries writing it in the same system.
+///
+/// # Examples
+///
+/// ```
+/// # use bevy_ecs::component::Component;
+/// # use bevy_ecs::query::IncludeDisabled;
+/// # use bevy_ecs::system::Query;
+/// #
+/// # #[derive(Component)]
+/// # struct Bullet;
+/// #
+/// fn count_pooled_bullets_system(query: Query<&Bullet, IncludeDisabled>) {
+///     println!("{} bullets in the pool", query.iter().count());
+/// }
+/// # bevy_ecs::system::assert_is_system(count_pooled_bullets_system);
+/// ```
+pub struct IncludeDisabled;
+
+// SAFETY: `Self::ReadOnly` is the same as `Self`
+unsafe impl WorldQuery for IncludeDisabled {
+    type Fetch<'w> = ();
+    type Item<'w> = ();
+    type ReadOnly = Self;
+    type State = ComponentId;
+
+    fn shrink<'wlong: 'wshort, 'wshort>(_: Self::Item<'wlong>) -> Self::Item<'wshort> {}
+
+    unsafe fn init_fetch(
+        _world: &World,
+        _state: &ComponentId,
+        _last_change_tick: u32,
+        _change_tick: u32,
+    ) {
+    }
+
+    unsafe fn clone_fetch<'w>(_fetch: &Self::Fetch<'w>) -> Self::Fetch<'w> {}
+
+    const IS_DENSE: bool = true;
+
+    const IS_ARCHETYPAL: bool = true;
+
+    #[inline]
+    unsafe fn set_table(_fetch: &mut (), _state: &ComponentId, _table: &Table) {}
+
+    #[inline]
+    unsafe fn set_archetype(
+        _fetch: &mut (),
+        _state: &ComponentId,
+        _archetype: &Archetype,
+        _table: &Table,
+    ) {
+    }
+
+    #[inline(always)]
+    unsafe fn fetch<'w>(
+        _fetch: &mut Self::Fetch<'w>,
+        _entity: Entity,
+        _table_row: usize,
+    ) -> Self::Item<'w> {
+    }
+
+    #[inline]
+    fn update_component_access(&id: &ComponentId, access: &mut FilteredAccess<ComponentId>) {
+        assert!(
+            !access.access().has_write(id),
+            "IncludeDisabled conflicts with a previous access in this query. Shared access cannot coincide with exclusive access.",
+        );
+        // Like `Option<&Disabled>`, this reads `Disabled` without filtering on it, which disables
+        // the default `Without<Disabled>` filter of the query.
+        access.access_mut().add_read(id);
+    }
+
+    #[inline]
+    fn update_archetype_component_access(
+        _state: &ComponentId,
+        _archetype: &Archetype,
+        _access: &mut Access<ArchetypeComponentId>,
+    ) {
+    }
+
+    fn init_state(world: &mut World) -> ComponentId {
+        world.init_component::<Disabled>()
+    }
+
+    fn matches_component_set(
+        _state: &ComponentId,
+        _set_contains_id: &impl Fn(ComponentId) -> bool,
+    ) -> bool {
+        true
+    }
+}
+
+// SAFETY: only reads `Disabled`, without fetching it
+unsafe impl ReadOnlyWorldQuery for IncludeDisabled {}
+
 /// A filter that tests if any of the given filters apply.
 ///
 /// This is useful for example if a system with multiple components in a query only wants to run
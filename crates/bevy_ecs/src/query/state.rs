@@ -4,7 +4,8 @@ use crate::{
     entity::Entity,
     prelude::FromWorld,
     query::{
-        Access, DebugCheckedUnwrap, FilteredAccess, QueryCombinationIter, QueryIter, WorldQuery,
+        Access, DebugCheckedUnwrap, Disabled, FilteredAccess, QueryCombinationIter, QueryIter,
+        WorldQuery,
     },
     storage::TableId,
     world::{World, WorldId},
@@ -35,6 +36,8 @@ pub struct QueryState<Q: WorldQuery, F: ReadOnlyWorldQuery = ()> {
     pub(crate) matched_archetype_ids: Vec<ArchetypeId>,
     pub(crate) fetch_state: Q::State,
     pub(crate) filter_state: F::State,
+    // The id of `Disabled` if the query skips disabled entities.
+    disabled_filter: Option<ComponentId>,
 }
 
 impl<Q: WorldQuery, F: ReadOnlyWorldQuery> std::fmt::Debug for QueryState<Q, F> {
@@ -111,6 +114,18 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
         // properly considered in a global "cross-query" context (both within systems and across systems).
         component_access.extend(&filter_component_access);
 
+        // Queries that don't mention `Disabled` skip disabled entities.
+        let disabled = world.init_component::<Disabled>();
+        let disabled_filter = if component_access.access().has_read(disabled)
+            || component_access.has_with(disabled)
+            || component_access.has_without(disabled)
+        {
+            None
+        } else {
+            component_access.add_without(disabled);
+            Some(disabled)
+        };
+
         let mut state = Self {
             world_id: world.id(),
             archetype_generation: ArchetypeGeneration::initial(),
@@ -119,6 +134,7 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
             fetch_state,
             filter_state,
             component_access,
+            disabled_filter,
             matched_tables: Default::default(),
             matched_archetypes: Default::default(),
             archetype_component_access: Default::default(),
@@ -168,7 +184,12 @@ impl<Q: WorldQuery, F: ReadOnlyWorldQuery> QueryState<Q, F> {
 
     /// Creates a new [`Archetype`].
     pub fn new_archetype(&mut self, archetype: &Archetype) {
-        if Q::matches_component_set(&self.fetch_state, &|id| archetype.contains(id))
+        let disabled = match self.disabled_filter {
+            Some(id) => archetype.contains(id),
+            None => false,
+        };
+        if !disabled
+            && Q::matches_component_set(&self.fetch_state, &|id| archetype.contains(id))
             && F::matches_component_set(&self.filter_state, &|id| archetype.contains(id))
         {
             Q::update_archetype_component_access(
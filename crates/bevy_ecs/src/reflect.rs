@@ -404,9 +404,14 @@ impl<C: Resource + Reflect + FromWorld> FromType<C> for ReflectResource {
 impl_reflect_value!(Entity(Hash, PartialEq, Serialize, Deserialize));
 impl_from_reflect_value!(Entity);
 
+/// The function creating a component from a reflected value and mapping its entities.
+type MapValueFn =
+    fn(&mut World, &dyn Reflect, &EntityMap) -> Result<Box<dyn Reflect>, MapEntitiesError>;
+
 #[derive(Clone)]
 pub struct ReflectMapEntities {
     map_entities: fn(&mut World, &EntityMap) -> Result<(), MapEntitiesError>,
+    map_value: MapValueFn,
 }
 
 impl ReflectMapEntities {
@@ -417,9 +422,21 @@ impl ReflectMapEntities {
     ) -> Result<(), MapEntitiesError> {
         (self.map_entities)(world, entity_map)
     }
+
+    /// Creates a component from the reflected `value`, which may be a dynamic clone of it, and maps
+    /// its entities with its [`MapEntities`] implementation. The components of `world` are left
+    /// unchanged, it is only used to create the component with [`FromWorld`].
+    pub fn map_value(
+        &self,
+        world: &mut World,
+        value: &dyn Reflect,
+        entity_map: &EntityMap,
+    ) -> Result<Box<dyn Reflect>, MapEntitiesError> {
+        (self.map_value)(world, value, entity_map)
+    }
 }
 
-impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
+impl<C: Component + MapEntities + Reflect + FromWorld> FromType<C> for ReflectMapEntities {
     fn from_type() -> Self {
         ReflectMapEntities {
             map_entities: |world, entity_map| {
@@ -430,6 +447,12 @@ impl<C: Component + MapEntities> FromType<C> for ReflectMapEntities {
                 }
                 Ok(())
             },
+            map_value: |world, value, entity_map| {
+                let mut component = C::from_world(world);
+                component.apply(value);
+                component.map_entities(entity_map)?;
+                Ok(Box::new(component))
+            },
         }
     }
 }
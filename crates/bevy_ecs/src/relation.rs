@@ -0,0 +1,484 @@
+//! Relations between entities, kept consistent in both directions.
+//!
+//! A [`Relation`] `R` from a *source* entity to *target* entities is stored as a [`Targets<R>`]
+//! component on the source and a [`TargetedBy<R>`] component on each target, so both sides can be
+//! looked up directly. Relations are added and removed with [`EntityMut::add_relation`] and
+//! [`EntityMut::remove_relation`], or their [`EntityCommands`] counterparts, which update both
+//! components. As they are regular components, they can be used in query filters, like
+//! `With<Targets<R>>` or `Without<TargetedBy<R>>`.
+//!
+//! When a source is despawned, it is removed from the [`TargetedBy<R>`] of its targets. When a
+//! target is despawned, [`Relation::DESPAWN_POLICY`] decides what happens to its sources.
+//!
+//! ```
+//! # use bevy_ecs::{prelude::*, relation::*};
+//! struct ContainedIn;
+//!
+//! impl Relation for ContainedIn {
+//!     // Items are despawned with their container.
+//!     const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::Cascade;
+//! }
+//!
+//! let mut world = World::new();
+//! let chest = world.spawn_empty().id();
+//! let sword = world.spawn_empty().add_relation::<ContainedIn>(chest).id();
+//! assert_eq!(world.get::<TargetedBy<ContainedIn>>(chest).unwrap().sources(), [sword]);
+//!
+//! world.despawn(chest);
+//! assert!(world.get_entity(sword).is_none());
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    self as bevy_ecs,
+    component::{Component, ComponentId},
+    entity::Entity,
+    system::{Command, EntityCommands},
+    world::{DeferredWorld, EntityMut, World},
+};
+
+/// What happens to the sources of a [`Relation`] when one of their targets is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DespawnPolicy {
+    /// The sources are despawned too, applying the despawn policies of their own relations.
+    Cascade,
+    /// The sources lose their [`Targets<R>`] component, even if they had other targets.
+    Orphan,
+    /// The despawned target is removed from the [`Targets<R>`] of the sources, which lose the
+    /// component if it was their last target.
+    RemoveRelation,
+}
+
+/// A kind of relation between entities, see the [module documentation](self).
+pub trait Relation: Send + Sync + 'static {
+    /// What happens to the sources of the relation when one of their targets is despawned.
+    const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::RemoveRelation;
+}
+
+/// The targets of the [`Relation`] `R` from this entity.
+#[derive(Component)]
+pub struct Targets<R: Relation> {
+    targets: Vec<Entity>,
+    marker: PhantomData<R>,
+}
+
+impl<R: Relation> Targets<R> {
+    /// Returns the targets, in the order they were added.
+    #[inline]
+    pub fn targets(&self) -> &[Entity] {
+        &self.targets
+    }
+
+    #[inline]
+    pub fn contains(&self, target: Entity) -> bool {
+        self.targets.contains(&target)
+    }
+}
+
+impl<R: Relation> fmt::Debug for Targets<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Targets").field(&self.targets).finish()
+    }
+}
+
+/// The sources of the [`Relation`] `R` targeting this entity.
+///
+/// It may be empty for a short time after the last source stopped targeting the entity, until
+/// the commands queued by its hooks are applied.
+#[derive(Component)]
+pub struct TargetedBy<R: Relation> {
+    sources: Vec<Entity>,
+    marker: PhantomData<R>,
+}
+
+impl<R: Relation> TargetedBy<R> {
+    /// Returns the sources, in the order they were added.
+    #[inline]
+    pub fn sources(&self) -> &[Entity] {
+        &self.sources
+    }
+
+    #[inline]
+    pub fn contains(&self, source: Entity) -> bool {
+        self.sources.contains(&source)
+    }
+}
+
+impl<R: Relation> fmt::Debug for TargetedBy<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TargetedBy").field(&self.sources).finish()
+    }
+}
+
+/// Registers the hooks keeping both sides of `R` consistent.
+fn init_relation<R: Relation>(world: &mut World) {
+    let hooks = world.register_component_hooks::<Targets<R>>();
+    if hooks.on_remove.is_none() {
+        hooks.on_remove(on_targets_removed::<R>);
+    }
+    let hooks = world.register_component_hooks::<TargetedBy<R>>();
+    if hooks.on_remove.is_none() {
+        hooks.on_remove(on_targeted_by_removed::<R>);
+    }
+}
+
+/// Removes the source from its targets when it loses its [`Targets<R>`], e.g. when despawned.
+fn on_targets_removed<R: Relation>(mut world: DeferredWorld, source: Entity, _: ComponentId) {
+    let targets = world.get::<Targets<R>>(source).unwrap().targets.clone();
+    for target in targets {
+        if let Some(mut targeted_by) = world.get_mut::<TargetedBy<R>>(target) {
+            targeted_by.sources.retain(|entity| *entity != source);
+            if targeted_by.sources.is_empty() {
+                world.commands().add(RemoveEmptyTargetedBy::<R> {
+                    target,
+                    marker: PhantomData,
+                });
+            }
+        }
+    }
+}
+
+/// Applies the despawn policy of `R` once the target lost its [`TargetedBy<R>`].
+fn on_targeted_by_removed<R: Relation>(mut world: DeferredWorld, target: Entity, _: ComponentId) {
+    let sources = world.get::<TargetedBy<R>>(target).unwrap().sources.clone();
+    if !sources.is_empty() {
+        world.commands().add(ApplyDespawnPolicy::<R> {
+            target,
+            sources,
+            marker: PhantomData,
+        });
+    }
+}
+
+fn add_relation<R: Relation>(world: &mut World, source: Entity, target: Entity) {
+    init_relation::<R>(world);
+    assert!(
+        world.get_entity(target).is_some(),
+        "Could not add a relation (of type `{}`) to entity {:?} because it doesn't exist in this World.",
+        std::any::type_name::<R>(),
+        target
+    );
+
+    let mut source_mut = world.entity_mut(source);
+    match source_mut.get_mut::<Targets<R>>() {
+        Some(targets) if targets.contains(target) => return,
+        Some(mut targets) => targets.targets.push(target),
+        None => {
+            source_mut.insert(Targets::<R> {
+                targets: vec![target],
+                marker: PhantomData,
+            });
+        }
+    }
+
+    let mut target_mut = world.entity_mut(target);
+    match target_mut.get_mut::<TargetedBy<R>>() {
+        Some(mut targeted_by) => targeted_by.sources.push(source),
+        None => {
+            target_mut.insert(TargetedBy::<R> {
+                sources: vec![source],
+                marker: PhantomData,
+            });
+        }
+    }
+}
+
+fn remove_relation<R: Relation>(world: &mut World, source: Entity, target: Entity) {
+    let mut source_mut = world.entity_mut(source);
+    let is_empty = match source_mut.get_mut::<Targets<R>>() {
+        Some(mut targets) if targets.contains(target) => {
+            targets.targets.retain(|entity| *entity != target);
+            targets.targets.is_empty()
+        }
+        _ => return,
+    };
+    if is_empty {
+        source_mut.remove::<Targets<R>>();
+    }
+
+    if let Some(mut target_mut) = world.get_entity_mut(target) {
+        if let Some(mut targeted_by) = target_mut.get_mut::<TargetedBy<R>>() {
+            targeted_by.sources.retain(|entity| *entity != source);
+            if targeted_by.sources.is_empty() {
+                target_mut.remove::<TargetedBy<R>>();
+            }
+        }
+    }
+}
+
+impl<'w> EntityMut<'w> {
+    /// Adds `target` to the targets of the [`Relation`] `R` from this entity, and this entity to
+    /// the sources of `target`. Does nothing if the relation already exists.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` does not exist.
+    pub fn add_relation<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.world_scope(|world| add_relation::<R>(world, source, target));
+        self
+    }
+
+    /// Removes `target` from the targets of the [`Relation`] `R` from this entity. The despawn
+    /// policy of `R` does not apply.
+    pub fn remove_relation<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.world_scope(|world| remove_relation::<R>(world, source, target));
+        self
+    }
+}
+
+impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
+    /// Adds `target` to the targets of the [`Relation`] `R` from this entity.
+    ///
+    /// See [`EntityMut::add_relation`].
+    pub fn add_relation<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.commands().add(AddRelation::<R> {
+            source,
+            target,
+            marker: PhantomData,
+        });
+        self
+    }
+
+    /// Removes `target` from the targets of the [`Relation`] `R` from this entity.
+    ///
+    /// See [`EntityMut::remove_relation`].
+    pub fn remove_relation<R: Relation>(&mut self, target: Entity) -> &mut Self {
+        let source = self.id();
+        self.commands().add(RemoveRelation::<R> {
+            source,
+            target,
+            marker: PhantomData,
+        });
+        self
+    }
+}
+
+pub struct AddRelation<R: Relation> {
+    pub source: Entity,
+    pub target: Entity,
+    pub marker: PhantomData<R>,
+}
+
+impl<R: Relation> Command for AddRelation<R> {
+    fn write(self, world: &mut World) {
+        if let Some(mut source) = world.get_entity_mut(self.source) {
+            source.add_relation::<R>(self.target);
+        } else {
+            panic!("error[B0003]: Could not add a relation (of type `{}`) from entity {:?} because it doesn't exist in this World.", std::any::type_name::<R>(), self.source);
+        }
+    }
+}
+
+pub struct RemoveRelation<R: Relation> {
+    pub source: Entity,
+    pub target: Entity,
+    pub marker: PhantomData<R>,
+}
+
+impl<R: Relation> Command for RemoveRelation<R> {
+    fn write(self, world: &mut World) {
+        if let Some(mut source) = world.get_entity_mut(self.source) {
+            source.remove_relation::<R>(self.target);
+        }
+    }
+}
+
+/// Removes the [`TargetedBy<R>`] of `target` if no source was added back since it became empty.
+struct RemoveEmptyTargetedBy<R: Relation> {
+    target: Entity,
+    marker: PhantomData<R>,
+}
+
+impl<R: Relation> Command for RemoveEmptyTargetedBy<R> {
+    fn write(self, world: &mut World) {
+        if let Some(mut target) = world.get_entity_mut(self.target) {
+            if matches!(target.get::<TargetedBy<R>>(), Some(targeted_by) if targeted_by.sources.is_empty())
+            {
+                target.remove::<TargetedBy<R>>();
+            }
+        }
+    }
+}
+
+/// Updates the sources of a target that lost its [`TargetedBy<R>`].
+struct ApplyDespawnPolicy<R: Relation> {
+    target: Entity,
+    sources: Vec<Entity>,
+    marker: PhantomData<R>,
+}
+
+impl<R: Relation> Command for ApplyDespawnPolicy<R> {
+    fn write(self, world: &mut World) {
+        // The component can also be removed from a target that is still alive, in which case only
+        // the relations to it are removed.
+        let policy = if world.get_entity(self.target).is_some() {
+            DespawnPolicy::RemoveRelation
+        } else {
+            R::DESPAWN_POLICY
+        };
+
+        for source in self.sources {
+            let mut source_mut = match world.get_entity_mut(source) {
+                Some(source_mut) => source_mut,
+                None => continue,
+            };
+            let is_empty = match source_mut.get_mut::<Targets<R>>() {
+                Some(mut targets) if targets.contains(self.target) => {
+                    targets.targets.retain(|entity| *entity != self.target);
+                    targets.targets.is_empty()
+                }
+                _ => continue,
+            };
+
+            match policy {
+                DespawnPolicy::Cascade => source_mut.despawn(),
+                DespawnPolicy::Orphan => {
+                    source_mut.remove::<Targets<R>>();
+                }
+                DespawnPolicy::RemoveRelation => {
+                    if is_empty {
+                        source_mut.remove::<Targets<R>>();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        system::{CommandQueue, SystemState},
+    };
+
+    struct Likes;
+
+    impl Relation for Likes {}
+
+    struct ChildOf;
+
+    impl Relation for ChildOf {
+        const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::Cascade;
+    }
+
+    struct Follows;
+
+    impl Relation for Follows {
+        const DESPAWN_POLICY: DespawnPolicy = DespawnPolicy::Orphan;
+    }
+
+    fn targets<R: Relation>(world: &World, entity: Entity) -> Vec<Entity> {
+        world
+            .get::<Targets<R>>(entity)
+            .map(|targets| targets.targets().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn sources<R: Relation>(world: &World, entity: Entity) -> Vec<Entity> {
+        world
+            .get::<TargetedBy<R>>(entity)
+            .map(|targeted_by| targeted_by.sources().to_vec())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn both_sides_are_updated() {
+        let mut world = World::new();
+        let a = world.spawn_empty().id();
+        let b = world.spawn_empty().id();
+        let c = world.spawn_empty().id();
+
+        world
+            .entity_mut(a)
+            .add_relation::<Likes>(b)
+            .add_relation::<Likes>(c)
+            .add_relation::<Likes>(b);
+        world.entity_mut(c).add_relation::<Likes>(b);
+        assert_eq!(targets::<Likes>(&world, a), vec![b, c]);
+        assert_eq!(sources::<Likes>(&world, b), vec![a, c]);
+        assert_eq!(sources::<Likes>(&world, c), vec![a]);
+
+        world.entity_mut(a).remove_relation::<Likes>(c);
+        assert_eq!(targets::<Likes>(&world, a), vec![b]);
+        assert!(!world.entity(c).contains::<TargetedBy<Likes>>());
+
+        // Despawning a source removes it from its targets.
+        world.despawn(c);
+        assert_eq!(sources::<Likes>(&world, b), vec![a]);
+
+        world.entity_mut(a).remove::<Targets<Likes>>();
+        world.flush_commands();
+        assert!(!world.entity(b).contains::<TargetedBy<Likes>>());
+    }
+
+    #[test]
+    fn relations_are_components() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn_empty().add_relation::<ChildOf>(parent).id();
+        world.spawn_empty().add_relation::<Likes>(parent);
+
+        let mut state: SystemState<(
+            Query<Entity, With<Targets<ChildOf>>>,
+            Query<Entity, (With<TargetedBy<ChildOf>>, Without<Targets<Likes>>)>,
+        )> = SystemState::new(&mut world);
+        let (children, parents) = state.get(&world);
+        assert_eq!(children.iter().collect::<Vec<_>>(), vec![child]);
+        assert_eq!(parents.iter().collect::<Vec<_>>(), vec![parent]);
+    }
+
+    #[test]
+    fn despawn_policies() {
+        let mut world = World::new();
+        let target = world.spawn_empty().id();
+        let other = world.spawn_empty().id();
+        let liker = world
+            .spawn_empty()
+            .add_relation::<Likes>(target)
+            .add_relation::<Likes>(other)
+            .id();
+        let follower = world
+            .spawn_empty()
+            .add_relation::<Follows>(target)
+            .add_relation::<Follows>(other)
+            .id();
+        let child = world.spawn_empty().add_relation::<ChildOf>(target).id();
+        let grandchild = world.spawn_empty().add_relation::<ChildOf>(child).id();
+
+        world.despawn(target);
+
+        assert_eq!(targets::<Likes>(&world, liker), vec![other]);
+        assert_eq!(sources::<Likes>(&world, other), vec![liker]);
+        assert!(!world.entity(follower).contains::<Targets<Follows>>());
+        assert!(!world.entity(other).contains::<TargetedBy<Follows>>());
+        assert!(world.get_entity(child).is_none());
+        assert!(world.get_entity(grandchild).is_none());
+    }
+
+    #[test]
+    fn relations_from_commands() {
+        let mut world = World::new();
+        let target = world.spawn_empty().id();
+        let mut queue = CommandQueue::default();
+
+        let mut commands = Commands::new(&mut queue, &world);
+        let child = commands.spawn_empty().add_relation::<ChildOf>(target).id();
+        let liker = commands.spawn_empty().add_relation::<Likes>(target).id();
+        queue.apply(&mut world);
+        assert_eq!(sources::<ChildOf>(&world, target), vec![child]);
+        assert_eq!(sources::<Likes>(&world, target), vec![liker]);
+
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(liker).remove_relation::<Likes>(target);
+        commands.entity(target).despawn();
+        queue.apply(&mut world);
+        assert!(world.get_entity(child).is_none());
+        assert!(!world.entity(liker).contains::<Targets<Likes>>());
+    }
+}
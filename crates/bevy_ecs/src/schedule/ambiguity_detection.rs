@@ -16,9 +16,10 @@ struct SystemOrderAmbiguity {
     conflicts: Vec<String>,
 }
 
-/// Which part of a [`SystemStage`] was a [`SystemOrderAmbiguity`] detected in?
+/// A part of a [`SystemStage`] whose systems are ordered relative to each other, but not to the
+/// systems of other parts.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
-enum SystemStageSegment {
+pub enum SystemStageSegment {
     Parallel,
     ExclusiveAtStart,
     ExclusiveBeforeCommands,
@@ -195,7 +196,9 @@ impl SystemStage {
 /// Returns vector containing all pairs of indices of systems with ambiguous execution order,
 /// along with specific components that have triggered the warning.
 /// Systems must be topologically sorted beforehand.
-fn find_ambiguities(systems: &[SystemContainer]) -> Vec<(usize, usize, Vec<ComponentId>)> {
+pub(super) fn find_ambiguities(
+    systems: &[SystemContainer],
+) -> Vec<(usize, usize, Vec<ComponentId>)> {
     // Check if we should ignore ambiguities between `system_a` and `system_b`.
     fn should_ignore(system_a: &SystemContainer, system_b: &SystemContainer) -> bool {
         fn should_ignore_inner(
@@ -0,0 +1,321 @@
+use crate::{
+    system::{BoxedSystem, IntoSystem},
+    world::World,
+};
+use std::{borrow::Cow, fmt::Debug};
+
+/// A system that determines whether other systems should run.
+pub type BoxedCondition = BoxedSystem<(), bool>;
+
+/// A boolean condition for running systems, composed of systems returning `bool`.
+///
+/// Conditions are added to systems and [`SystemSet`](crate::schedule::SystemSet)s with `run_if`,
+/// and are evaluated once each time their stage runs, before its systems. Unlike
+/// [`RunCriteria`](crate::schedule::RunCriteria), a system can have several conditions, which all
+/// have to be met for it to run, and they can be combined with the run criteria of the system.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Resource)]
+/// # struct Score(u32);
+/// # #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// # enum GameState { Playing }
+/// # fn update_score() {}
+/// SystemStage::parallel().with_system(
+///     update_score.run_if(resource_exists::<Score>().and(in_state(GameState::Playing))),
+/// );
+/// ```
+pub enum RunCondition {
+    /// Met when the system returns `true`.
+    System(BoxedCondition),
+    /// Met when both conditions are met. The second one is only evaluated if the first one is met.
+    And(Box<RunCondition>, Box<RunCondition>),
+    /// Met when either condition is met. The second one is only evaluated if the first one isn't
+    /// met.
+    Or(Box<RunCondition>, Box<RunCondition>),
+    /// Met when the condition isn't met.
+    Not(Box<RunCondition>),
+}
+
+impl RunCondition {
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            RunCondition::System(system) => system.name(),
+            RunCondition::And(a, b) => format!("({} and {})", a.name(), b.name()).into(),
+            RunCondition::Or(a, b) => format!("({} or {})", a.name(), b.name()).into(),
+            RunCondition::Not(condition) => format!("not {}", condition.name()).into(),
+        }
+    }
+
+    pub(crate) fn initialize(&mut self, world: &mut World) {
+        match self {
+            RunCondition::System(system) => system.initialize(world),
+            RunCondition::And(a, b) | RunCondition::Or(a, b) => {
+                a.initialize(world);
+                b.initialize(world);
+            }
+            RunCondition::Not(condition) => condition.initialize(world),
+        }
+    }
+
+    pub(crate) fn evaluate(&mut self, world: &mut World) -> bool {
+        match self {
+            RunCondition::System(system) => {
+                let met = system.run((), world);
+                system.apply_buffers(world);
+                met
+            }
+            RunCondition::And(a, b) => a.evaluate(world) && b.evaluate(world),
+            RunCondition::Or(a, b) => a.evaluate(world) || b.evaluate(world),
+            RunCondition::Not(condition) => !condition.evaluate(world),
+        }
+    }
+
+    pub(crate) fn check_change_tick(&mut self, change_tick: u32) {
+        match self {
+            RunCondition::System(system) => system.check_change_tick(change_tick),
+            RunCondition::And(a, b) | RunCondition::Or(a, b) => {
+                a.check_change_tick(change_tick);
+                b.check_change_tick(change_tick);
+            }
+            RunCondition::Not(condition) => condition.check_change_tick(change_tick),
+        }
+    }
+}
+
+impl Debug for RunCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RunCondition({})", self.name())
+    }
+}
+
+/// Types that can be converted into a [`RunCondition`], and combined with other conditions.
+pub trait IntoRunCondition<Marker>: Sized {
+    fn into_run_condition(self) -> RunCondition;
+
+    /// Returns a condition met when both `self` and `other` are met.
+    fn and<M>(self, other: impl IntoRunCondition<M>) -> RunCondition {
+        RunCondition::And(
+            Box::new(self.into_run_condition()),
+            Box::new(other.into_run_condition()),
+        )
+    }
+
+    /// Returns a condition met when either `self` or `other` is met.
+    fn or<M>(self, other: impl IntoRunCondition<M>) -> RunCondition {
+        RunCondition::Or(
+            Box::new(self.into_run_condition()),
+            Box::new(other.into_run_condition()),
+        )
+    }
+
+    /// Returns a condition met when `self` isn't met.
+    fn not(self) -> RunCondition {
+        RunCondition::Not(Box::new(self.into_run_condition()))
+    }
+}
+
+impl IntoRunCondition<()> for RunCondition {
+    fn into_run_condition(self) -> RunCondition {
+        self
+    }
+}
+
+impl IntoRunCondition<()> for BoxedCondition {
+    fn into_run_condition(self) -> RunCondition {
+        RunCondition::System(self)
+    }
+}
+
+impl<S, Param> IntoRunCondition<Param> for S
+where
+    S: IntoSystem<(), bool, Param>,
+{
+    fn into_run_condition(self) -> RunCondition {
+        RunCondition::System(Box::new(IntoSystem::into_system(self)))
+    }
+}
+
+/// A [`RunCondition`] added to a stage, with its result for the current run of the stage.
+#[derive(Debug)]
+pub(crate) struct RunConditionContainer {
+    condition: RunCondition,
+    initialized: bool,
+    pub(crate) met: bool,
+}
+
+impl RunConditionContainer {
+    pub(crate) fn new(condition: RunCondition) -> Self {
+        Self {
+            condition,
+            initialized: false,
+            met: false,
+        }
+    }
+
+    pub(crate) fn evaluate(&mut self, world: &mut World) {
+        if !self.initialized {
+            self.condition.initialize(world);
+            self.initialized = true;
+        }
+        #[cfg(feature = "trace")]
+        let _span =
+            bevy_utils::tracing::info_span!("run condition", name = &*self.condition.name())
+                .entered();
+        self.met = self.condition.evaluate(world);
+    }
+
+    pub(crate) fn check_change_tick(&mut self, change_tick: u32) {
+        if self.initialized {
+            self.condition.check_change_tick(change_tick);
+        }
+    }
+}
+
+/// Commonly used [`RunCondition`]s.
+pub mod common_conditions {
+    use crate::{
+        event::{Event, EventReader},
+        schedule::{State, StateData},
+        system::{Res, Resource},
+    };
+
+    /// Returns a condition met when the resource `T` exists.
+    pub fn resource_exists<T: Resource>() -> impl FnMut(Option<Res<T>>) -> bool {
+        move |resource: Option<Res<T>>| resource.is_some()
+    }
+
+    /// Returns a condition met when the resource `T` exists, and was added or changed since the
+    /// condition was last evaluated.
+    pub fn resource_changed<T: Resource>() -> impl FnMut(Option<Res<T>>) -> bool {
+        move |resource: Option<Res<T>>| match resource {
+            Some(resource) => resource.is_changed(),
+            None => false,
+        }
+    }
+
+    /// Returns a condition met when the current [`State<S>`] is `state`.
+    pub fn in_state<S: StateData>(state: S) -> impl FnMut(Option<Res<State<S>>>) -> bool {
+        move |current: Option<Res<State<S>>>| match current {
+            Some(current) => *current.current() == state,
+            None => false,
+        }
+    }
+
+    /// Returns a condition met when events of type `T` were sent since the condition was last
+    /// evaluated.
+    pub fn on_event<T: Event>() -> impl FnMut(EventReader<T>) -> bool {
+        // The reader must be read to mark the events as read.
+        move |mut reader: EventReader<T>| reader.iter().count() > 0
+    }
+}
+
+pub use common_conditions::*;
+
+#[cfg(test)]
+mod tests {
+    use crate as bevy_ecs;
+    use crate::{
+        event::Events,
+        prelude::*,
+        schedule::{IntoRunCondition, ShouldRun},
+    };
+
+    #[derive(Resource, Default)]
+    struct Counter(u32);
+
+    #[derive(Resource)]
+    struct Flag(bool);
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum AppState {
+        Menu,
+        Playing,
+    }
+
+    fn increment(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn flag(flag: Res<Flag>) -> bool {
+        flag.0
+    }
+
+    fn run(stage: &mut SystemStage, world: &mut World) -> u32 {
+        stage.run(world);
+        world.resource::<Counter>().0
+    }
+
+    #[test]
+    fn combined_conditions() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(Flag(false));
+        let mut stage = SystemStage::single_threaded()
+            .with_system(increment.run_if(flag.or(resource_exists::<State<AppState>>())))
+            .with_system(increment.run_if(flag.not().and(flag)));
+
+        assert_eq!(run(&mut stage, &mut world), 0);
+        world.insert_resource(Flag(true));
+        assert_eq!(run(&mut stage, &mut world), 1);
+        world.insert_resource(Flag(false));
+        world.insert_resource(State::new(AppState::Menu));
+        assert_eq!(run(&mut stage, &mut world), 2);
+    }
+
+    #[test]
+    fn multiple_conditions_and_run_criteria() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(Flag(true));
+        world.insert_resource(State::new(AppState::Menu));
+        let mut stage = SystemStage::single_threaded().with_system(
+            increment
+                .run_if(flag)
+                .run_if(in_state(AppState::Playing))
+                .with_run_criteria(ShouldRun::once),
+        );
+
+        assert_eq!(run(&mut stage, &mut world), 0);
+        world.insert_resource(State::new(AppState::Playing));
+        // The run criteria was used up by the first run.
+        assert_eq!(run(&mut stage, &mut world), 0);
+
+        let mut stage = SystemStage::single_threaded()
+            .with_system(increment.run_if(flag).run_if(in_state(AppState::Playing)));
+        assert_eq!(run(&mut stage, &mut world), 1);
+    }
+
+    #[test]
+    fn system_set_conditions() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.init_resource::<Events<()>>();
+        let mut stage = SystemStage::single_threaded().with_system_set(
+            SystemSet::new()
+                .run_if(on_event::<()>())
+                .with_system(increment)
+                .with_system(increment),
+        );
+
+        assert_eq!(run(&mut stage, &mut world), 0);
+        world.send_event(());
+        assert_eq!(run(&mut stage, &mut world), 2);
+        assert_eq!(run(&mut stage, &mut world), 2);
+    }
+
+    #[test]
+    fn resource_changed_condition() {
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        world.insert_resource(Flag(false));
+        let mut stage = SystemStage::single_threaded()
+            .with_system(increment.run_if(resource_changed::<Flag>()));
+
+        assert_eq!(run(&mut stage, &mut world), 1);
+        assert_eq!(run(&mut stage, &mut world), 1);
+        world.resource_mut::<Flag>().0 = true;
+        assert_eq!(run(&mut stage, &mut world), 2);
+    }
+}
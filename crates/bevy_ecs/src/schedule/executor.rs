@@ -1,4 +1,7 @@
-use crate::{schedule::SystemContainer, world::World};
+use crate::{
+    schedule::{SystemContainer, SystemTimings},
+    world::World,
+};
 use core::fmt::Debug;
 use downcast_rs::{impl_downcast, Downcast};
 
@@ -24,12 +27,13 @@ impl ParallelSystemExecutor for SingleThreadedExecutor {
     fn rebuild_cached_data(&mut self, _: &[SystemContainer]) {}
 
     fn run_systems(&mut self, systems: &mut [SystemContainer], world: &mut World) {
+        let record_time = world.contains_resource::<SystemTimings>();
         for system in systems {
             if system.should_run() {
                 #[cfg(feature = "trace")]
                 let _system_span =
                     bevy_utils::tracing::info_span!("system", name = &*system.name()).entered();
-                system.system_mut().run((), world);
+                system.run(world, record_time);
             }
         }
     }
@@ -4,32 +4,41 @@
 //!  [`Stage`], which then lives within a [`Schedule`].
 
 mod ambiguity_detection;
+mod condition;
 mod executor;
 mod executor_parallel;
 pub mod graph_utils;
 mod label;
 mod run_criteria;
+mod schedule_graph;
 mod stage;
 mod state;
+mod stepping;
 mod system_container;
 mod system_descriptor;
 mod system_set;
+mod system_timings;
 
+pub use ambiguity_detection::SystemStageSegment;
+pub use condition::*;
 pub use executor::*;
 pub use executor_parallel::*;
 pub use graph_utils::GraphNode;
 pub use label::*;
 pub use run_criteria::*;
+pub use schedule_graph::*;
 pub use stage::*;
 pub use state::*;
+pub use stepping::*;
 pub use system_container::*;
 pub use system_descriptor::*;
 pub use system_set::*;
+pub use system_timings::*;
 
 use std::fmt::Debug;
 
 use crate::{system::IntoSystem, world::World};
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, Instant};
 
 /// A container of [`Stage`]s set to be run in a linear order.
 ///
@@ -363,11 +372,31 @@ impl Schedule {
 
     /// Executes each [`Stage`] contained in the schedule, one at a time.
     pub fn run_once(&mut self, world: &mut World) {
+        if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+            stepping.enter_schedule();
+        }
         for label in &self.stage_order {
             #[cfg(feature = "trace")]
             let _stage_span = bevy_utils::tracing::info_span!("stage", name = ?label).entered();
             let stage = self.stages.get_mut(label).unwrap();
+            let start = world
+                .contains_resource::<SystemTimings>()
+                .then(Instant::now);
+            if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+                stepping.set_running_stage(Some(*label));
+            }
             stage.run(world);
+            if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+                stepping.set_running_stage(None);
+            }
+            if let Some(start) = start {
+                if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+                    timings.add_stage_time(*label, start.elapsed());
+                }
+            }
+        }
+        if let Some(mut stepping) = world.get_resource_mut::<Stepping>() {
+            stepping.exit_schedule();
         }
     }
 
@@ -377,6 +406,19 @@ impl Schedule {
             .iter()
             .map(move |&label| (label, &*self.stages[&label]))
     }
+
+    /// Initializes the [`SystemStage`]s of this schedule and of its nested schedules without
+    /// running them, so that their final system order can be inspected with [`Schedule::graph`].
+    pub fn initialize(&mut self, world: &mut World) {
+        for label in &self.stage_order {
+            let stage = self.stages.get_mut(label).unwrap();
+            if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                stage.initialize(world);
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                schedule.initialize(world);
+            }
+        }
+    }
 }
 
 impl Stage for Schedule {
@@ -80,6 +80,10 @@ impl BoxedRunCriteria {
         self.initialized = false;
     }
 
+    pub(crate) fn name(&self) -> Option<Cow<'static, str>> {
+        self.criteria_system.as_ref().map(|system| system.name())
+    }
+
     pub(crate) fn should_run(&mut self, world: &mut World) -> ShouldRun {
         if let Some(ref mut run_criteria) = self.criteria_system {
             if !self.initialized {
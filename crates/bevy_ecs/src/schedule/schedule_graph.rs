@@ -0,0 +1,773 @@
+//! Exports the structure of a [`Schedule`] to visualize the order of its systems.
+
+use std::fmt::Write;
+
+use crate::{
+    schedule::{
+        ambiguity_detection::find_ambiguities, GraphNode, RunCriteriaInner, RunCriteriaLabel,
+        Schedule, Stage, StageLabel, SystemContainer, SystemLabel, SystemLabelId, SystemStage,
+        SystemStageSegment,
+    },
+    world::World,
+};
+
+/// The structure of a [`Schedule`], returned by [`Schedule::graph`].
+///
+/// It can be written in the DOT format with [`ScheduleGraph::to_dot`] to be rendered by Graphviz,
+/// or in JSON with [`ScheduleGraph::to_json`] to be processed by other tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleGraph {
+    /// The name of the run criteria of the schedule.
+    pub run_criteria: Option<String>,
+    /// The stages of the schedule, in execution order.
+    pub stages: Vec<StageGraph>,
+}
+
+/// A stage of a [`ScheduleGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageGraph {
+    pub label: String,
+    pub kind: StageGraphKind,
+}
+
+/// The content of a [`StageGraph`], depending on the type of the [`Stage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageGraphKind {
+    SystemStage(SystemStageGraph),
+    Schedule(ScheduleGraph),
+    /// A custom [`Stage`], whose content is unknown.
+    Unknown,
+}
+
+/// The systems of a [`SystemStage`] and their ordering.
+///
+/// Systems added through a [`SystemSet`](crate::schedule::SystemSet) carry the labels, ordering
+/// constraints and run criteria of the set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemStageGraph {
+    /// Whether the stage was initialized since its systems were last modified. Systems of a stage
+    /// that is not initialized are listed in insertion order, and their ambiguities are not
+    /// detected.
+    pub initialized: bool,
+    /// The name of the run criteria of the stage.
+    pub run_criteria: Option<String>,
+    /// The run criteria of the systems of the stage.
+    pub system_run_criteria: Vec<RunCriteriaNode>,
+    /// The systems of the stage, segment by segment in execution order.
+    pub systems: Vec<SystemNode>,
+    /// The ordering constraints between systems, resolved from their labels.
+    pub edges: Vec<SystemEdge>,
+    /// The pairs of systems with an ambiguous execution order.
+    pub ambiguities: Vec<SystemAmbiguity>,
+}
+
+/// A system of a [`SystemStageGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemNode {
+    pub name: String,
+    pub segment: SystemStageSegment,
+    pub labels: Vec<String>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+    /// The index of the run criteria of the system in [`SystemStageGraph::system_run_criteria`].
+    pub run_criteria: Option<usize>,
+}
+
+/// A run criteria of a [`SystemStageGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCriteriaNode {
+    pub name: String,
+    pub label: Option<String>,
+    /// The index of the run criteria this one is piped from.
+    pub piped_from: Option<usize>,
+}
+
+/// An ordering constraint between two systems of a [`SystemStageGraph`], given by their indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemEdge {
+    pub before: usize,
+    pub after: usize,
+}
+
+/// Two systems of a [`SystemStageGraph`], given by their indices, with an ambiguous execution
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemAmbiguity {
+    pub systems: [usize; 2],
+    /// The names of the components both systems access, at least one of them mutably. Empty for
+    /// exclusive systems, which conflict with every system.
+    pub conflicts: Vec<String>,
+}
+
+impl Schedule {
+    /// Returns the structure of this schedule and of its nested schedules.
+    ///
+    /// Stages are only sorted and checked for ambiguities once they have run, or have been
+    /// initialized with [`Schedule::initialize`].
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// fn spawn() {}
+    /// fn update() {}
+    ///
+    /// let mut world = World::new();
+    /// let mut schedule = Schedule::default();
+    /// schedule.add_stage(
+    ///     "update",
+    ///     SystemStage::parallel()
+    ///         .with_system(update.after("spawn"))
+    ///         .with_system(spawn.label("spawn")),
+    /// );
+    /// schedule.initialize(&mut world);
+    ///
+    /// let graph = schedule.graph(&world);
+    /// assert!(graph.to_dot().starts_with("digraph schedule {"));
+    /// assert!(graph.to_json().starts_with("{\"run_criteria\":null,\"stages\":[{\"label\":\"update\""));
+    /// ```
+    pub fn graph(&self, world: &World) -> ScheduleGraph {
+        ScheduleGraph {
+            run_criteria: self.run_criteria.name().map(|name| name.into_owned()),
+            stages: self
+                .iter_stages()
+                .map(|(label, stage)| StageGraph {
+                    label: label.as_str().to_owned(),
+                    kind: stage_graph_kind(stage, world),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn stage_graph_kind(stage: &dyn Stage, world: &World) -> StageGraphKind {
+    if let Some(stage) = stage.downcast_ref::<SystemStage>() {
+        StageGraphKind::SystemStage(stage.graph(world))
+    } else if let Some(schedule) = stage.downcast_ref::<Schedule>() {
+        StageGraphKind::Schedule(schedule.graph(world))
+    } else {
+        StageGraphKind::Unknown
+    }
+}
+
+impl SystemStage {
+    /// Returns the systems of this stage and their ordering.
+    ///
+    /// Systems are only sorted and checked for ambiguities once the stage has run, or has been
+    /// initialized with [`SystemStage::initialize`].
+    pub fn graph(&self, world: &World) -> SystemStageGraph {
+        let system_run_criteria: Vec<_> = self
+            .run_criteria
+            .iter()
+            .map(|criteria| RunCriteriaNode {
+                name: criteria.name().into_owned(),
+                label: criteria.label.map(|label| label.as_str().to_owned()),
+                piped_from: None,
+            })
+            .collect();
+        let criteria_index = |label: &str| {
+            system_run_criteria
+                .iter()
+                .position(|criteria| criteria.label.as_deref() == Some(label))
+        };
+        let piped_from: Vec<_> = self
+            .run_criteria
+            .iter()
+            .map(|criteria| match &criteria.inner {
+                // Piped run criteria are always created by `RunCriteriaPiping::pipe`, which sets
+                // the parent label as the only `after` constraint.
+                RunCriteriaInner::Piped { .. } => criteria
+                    .after
+                    .first()
+                    .and_then(|label| criteria_index(label.as_str())),
+                RunCriteriaInner::Single(_) => None,
+            })
+            .collect();
+        let mut graph = SystemStageGraph {
+            initialized: self.is_initialized(),
+            run_criteria: self.stage_run_criteria.name().map(|name| name.into_owned()),
+            system_run_criteria,
+            systems: Vec::new(),
+            edges: Vec::new(),
+            ambiguities: Vec::new(),
+        };
+        for (criteria, piped_from) in graph.system_run_criteria.iter_mut().zip(piped_from) {
+            criteria.piped_from = piped_from;
+        }
+
+        for (segment, systems) in [
+            (
+                SystemStageSegment::ExclusiveAtStart,
+                &self.exclusive_at_start,
+            ),
+            (SystemStageSegment::Parallel, &self.parallel),
+            (
+                SystemStageSegment::ExclusiveBeforeCommands,
+                &self.exclusive_before_commands,
+            ),
+            (SystemStageSegment::ExclusiveAtEnd, &self.exclusive_at_end),
+        ] {
+            graph.add_segment(segment, systems, world);
+        }
+        graph
+    }
+}
+
+impl SystemStageGraph {
+    fn add_segment(
+        &mut self,
+        segment: SystemStageSegment,
+        systems: &[SystemContainer],
+        world: &World,
+    ) {
+        let offset = self.systems.len();
+        for system in systems {
+            let run_criteria = match system.run_criteria_label() {
+                Some(label) => self
+                    .system_run_criteria
+                    .iter()
+                    .position(|criteria| criteria.label.as_deref() == Some(label.as_str())),
+                None => system.run_criteria(),
+            };
+            self.systems.push(SystemNode {
+                name: system.name().into_owned(),
+                segment,
+                labels: label_names(system.labels()),
+                before: label_names(system.before()),
+                after: label_names(system.after()),
+                run_criteria,
+            });
+        }
+
+        // Constraints only apply within a segment, the segments themselves run in a fixed order.
+        let mut edges = Vec::new();
+        for (index, system) in systems.iter().enumerate() {
+            for (other_index, other) in systems.iter().enumerate() {
+                if index == other_index {
+                    continue;
+                }
+                if system
+                    .before()
+                    .iter()
+                    .any(|label| other.labels().contains(label))
+                {
+                    edges.push(SystemEdge {
+                        before: offset + index,
+                        after: offset + other_index,
+                    });
+                }
+                if system
+                    .after()
+                    .iter()
+                    .any(|label| other.labels().contains(label))
+                {
+                    edges.push(SystemEdge {
+                        before: offset + other_index,
+                        after: offset + index,
+                    });
+                }
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        self.edges.extend(edges);
+
+        if self.initialized {
+            for (index_a, index_b, component_ids) in find_ambiguities(systems) {
+                let mut conflicts: Vec<_> = component_ids
+                    .iter()
+                    .map(|id| world.components().get_info(*id).unwrap().name().to_owned())
+                    .collect();
+                conflicts.sort();
+                self.ambiguities.push(SystemAmbiguity {
+                    systems: [offset + index_a, offset + index_b],
+                    conflicts,
+                });
+            }
+        }
+    }
+}
+
+fn label_names(labels: &[SystemLabelId]) -> Vec<String> {
+    labels
+        .iter()
+        .map(|label| label.as_str().to_owned())
+        .collect()
+}
+
+impl ScheduleGraph {
+    /// Writes the graph in the DOT format.
+    ///
+    /// Each stage is drawn as a cluster containing one cluster per non-empty
+    /// [`SystemStageSegment`]. Ordering constraints are drawn as arrows, run criteria as diamonds
+    /// linked to their systems by dotted lines, and ambiguities as dashed red lines labelled with
+    /// the conflicting components.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph schedule {{").unwrap();
+        writeln!(dot, "    compound=true;").unwrap();
+        writeln!(dot, "    node [shape=box];").unwrap();
+        self.write_dot(&mut dot, "", 1);
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, prefix: &str, depth: usize) {
+        let indent = "    ".repeat(depth);
+        for (stage_index, stage) in self.stages.iter().enumerate() {
+            let id = format!("{prefix}s{stage_index}");
+            let mut label = stage.label.clone();
+            let run_criteria = match &stage.kind {
+                StageGraphKind::SystemStage(stage) => stage.run_criteria.as_ref(),
+                StageGraphKind::Schedule(schedule) => schedule.run_criteria.as_ref(),
+                StageGraphKind::Unknown => None,
+            };
+            if let Some(run_criteria) = run_criteria {
+                write!(label, "\nrun criteria: {run_criteria}").unwrap();
+            }
+
+            writeln!(dot, "{indent}subgraph \"cluster_{id}\" {{").unwrap();
+            writeln!(dot, "{indent}    label={};", dot_str(&label)).unwrap();
+            // Stages may be empty: an invisible node anchors the edges between stages.
+            writeln!(dot, "{indent}    \"{id}\" [shape=point, style=invis];").unwrap();
+            match &stage.kind {
+                StageGraphKind::SystemStage(stage) => stage.write_dot(dot, &id, depth + 1),
+                StageGraphKind::Schedule(schedule) => {
+                    schedule.write_dot(dot, &format!("{id}_"), depth + 1);
+                }
+                StageGraphKind::Unknown => {}
+            }
+            writeln!(dot, "{indent}}}").unwrap();
+
+            if stage_index > 0 {
+                let previous = format!("{prefix}s{}", stage_index - 1);
+                writeln!(
+                    dot,
+                    "{indent}\"{previous}\" -> \"{id}\" [ltail=\"cluster_{previous}\", lhead=\"cluster_{id}\", style=bold];"
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Writes the graph in JSON.
+    ///
+    /// Systems, run criteria, edges and ambiguities of a [`SystemStage`] reference each other by
+    /// their index, like in [`SystemStageGraph`].
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str("{\"run_criteria\":");
+        json_opt_str(json, self.run_criteria.as_deref());
+        json.push_str(",\"stages\":[");
+        for (index, stage) in self.stages.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"label\":");
+            json_str(json, &stage.label);
+            match &stage.kind {
+                StageGraphKind::SystemStage(stage) => {
+                    json.push_str(",\"kind\":\"system_stage\",\"stage\":");
+                    stage.write_json(json);
+                }
+                StageGraphKind::Schedule(schedule) => {
+                    json.push_str(",\"kind\":\"schedule\",\"schedule\":");
+                    schedule.write_json(json);
+                }
+                StageGraphKind::Unknown => json.push_str(",\"kind\":\"unknown\""),
+            }
+            json.push('}');
+        }
+        json.push_str("]}");
+    }
+}
+
+impl SystemStageGraph {
+    fn write_dot(&self, dot: &mut String, id: &str, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let system_id = |index: usize| format!("{id}_{index}");
+        let criteria_id = |index: usize| format!("{id}_rc{index}");
+
+        for (index, criteria) in self.system_run_criteria.iter().enumerate() {
+            let mut label = criteria.name.clone();
+            if let Some(criteria_label) = &criteria.label {
+                write!(label, "\n[{criteria_label}]").unwrap();
+            }
+            writeln!(
+                dot,
+                "{indent}\"{}\" [shape=diamond, label={}];",
+                criteria_id(index),
+                dot_str(&label)
+            )
+            .unwrap();
+            if let Some(piped_from) = criteria.piped_from {
+                writeln!(
+                    dot,
+                    "{indent}\"{}\" -> \"{}\" [style=dotted];",
+                    criteria_id(piped_from),
+                    criteria_id(index)
+                )
+                .unwrap();
+            }
+        }
+
+        let mut start = 0;
+        while start < self.systems.len() {
+            let segment = self.systems[start].segment;
+            let end = self.systems[start..]
+                .iter()
+                .position(|system| system.segment != segment)
+                .map_or(self.systems.len(), |len| start + len);
+
+            writeln!(
+                dot,
+                "{indent}subgraph \"cluster_{id}_{}\" {{",
+                segment_key(segment)
+            )
+            .unwrap();
+            writeln!(dot, "{indent}    label={};", dot_str(segment.desc())).unwrap();
+            writeln!(dot, "{indent}    style=dashed;").unwrap();
+            for (index, system) in self.systems.iter().enumerate().take(end).skip(start) {
+                let mut label = system.name.clone();
+                if !system.labels.is_empty() {
+                    write!(label, "\n[{}]", system.labels.join(", ")).unwrap();
+                }
+                let style = if segment == SystemStageSegment::Parallel {
+                    ""
+                } else {
+                    ", style=filled, fillcolor=lightgrey"
+                };
+                writeln!(
+                    dot,
+                    "{indent}    \"{}\" [label={}{style}];",
+                    system_id(index),
+                    dot_str(&label)
+                )
+                .unwrap();
+            }
+            writeln!(dot, "{indent}}}").unwrap();
+            start = end;
+        }
+
+        for (index, system) in self.systems.iter().enumerate() {
+            if let Some(run_criteria) = system.run_criteria {
+                writeln!(
+                    dot,
+                    "{indent}\"{}\" -> \"{}\" [style=dotted];",
+                    criteria_id(run_criteria),
+                    system_id(index)
+                )
+                .unwrap();
+            }
+        }
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "{indent}\"{}\" -> \"{}\";",
+                system_id(edge.before),
+                system_id(edge.after)
+            )
+            .unwrap();
+        }
+        for ambiguity in &self.ambiguities {
+            writeln!(
+                dot,
+                "{indent}\"{}\" -> \"{}\" [dir=none, style=dashed, color=red, constraint=false, label={}];",
+                system_id(ambiguity.systems[0]),
+                system_id(ambiguity.systems[1]),
+                dot_str(&ambiguity.conflicts.join("\n"))
+            )
+            .unwrap();
+        }
+    }
+
+    fn write_json(&self, json: &mut String) {
+        write!(
+            json,
+            "{{\"initialized\":{},\"run_criteria\":",
+            self.initialized
+        )
+        .unwrap();
+        json_opt_str(json, self.run_criteria.as_deref());
+
+        json.push_str(",\"system_run_criteria\":[");
+        for (index, criteria) in self.system_run_criteria.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"name\":");
+            json_str(json, &criteria.name);
+            json.push_str(",\"label\":");
+            json_opt_str(json, criteria.label.as_deref());
+            json.push_str(",\"piped_from\":");
+            json_opt_index(json, criteria.piped_from);
+            json.push('}');
+        }
+
+        json.push_str("],\"systems\":[");
+        for (index, system) in self.systems.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"name\":");
+            json_str(json, &system.name);
+            write!(json, ",\"segment\":\"{}\"", segment_key(system.segment)).unwrap();
+            json.push_str(",\"labels\":");
+            json_str_array(json, &system.labels);
+            json.push_str(",\"before\":");
+            json_str_array(json, &system.before);
+            json.push_str(",\"after\":");
+            json_str_array(json, &system.after);
+            json.push_str(",\"run_criteria\":");
+            json_opt_index(json, system.run_criteria);
+            json.push('}');
+        }
+
+        json.push_str("],\"edges\":[");
+        for (index, edge) in self.edges.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            write!(json, "[{},{}]", edge.before, edge.after).unwrap();
+        }
+
+        json.push_str("],\"ambiguities\":[");
+        for (index, ambiguity) in self.ambiguities.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"systems\":[{},{}],\"conflicts\":",
+                ambiguity.systems[0], ambiguity.systems[1]
+            )
+            .unwrap();
+            json_str_array(json, &ambiguity.conflicts);
+            json.push('}');
+        }
+        json.push_str("]}");
+    }
+}
+
+fn segment_key(segment: SystemStageSegment) -> &'static str {
+    match segment {
+        SystemStageSegment::Parallel => "parallel",
+        SystemStageSegment::ExclusiveAtStart => "exclusive_at_start",
+        SystemStageSegment::ExclusiveBeforeCommands => "exclusive_before_commands",
+        SystemStageSegment::ExclusiveAtEnd => "exclusive_at_end",
+    }
+}
+
+/// Quotes a DOT string. Line breaks are kept as DOT escape sequences.
+fn dot_str(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn json_str(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+fn json_opt_str(json: &mut String, value: Option<&str>) {
+    match value {
+        Some(value) => json_str(json, value),
+        None => json.push_str("null"),
+    }
+}
+
+fn json_opt_index(json: &mut String, value: Option<usize>) {
+    match value {
+        Some(value) => write!(json, "{value}").unwrap(),
+        None => json.push_str("null"),
+    }
+}
+
+fn json_str_array(json: &mut String, values: &[String]) {
+    json.push('[');
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        json_str(json, value);
+    }
+    json.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        prelude::*,
+        schedule::{
+            RunCriteriaDescriptorCoercion, RunCriteriaNode, ScheduleGraph, StageGraphKind,
+            SystemEdge, SystemStageGraph, SystemStageSegment,
+        },
+    };
+
+    #[derive(Component)]
+    struct A;
+
+    fn read_a(_query: Query<&A>) {}
+    fn write_a(_query: Query<&mut A>) {}
+    fn write_a_again(_query: Query<&mut A>) {}
+    fn exclusive(_world: &mut World) {}
+    fn every_frame() -> crate::schedule::ShouldRun {
+        crate::schedule::ShouldRun::Yes
+    }
+
+    fn stage_graph(graph: &ScheduleGraph, index: usize) -> &SystemStageGraph {
+        match &graph.stages[index].kind {
+            StageGraphKind::SystemStage(stage) => stage,
+            kind => panic!("expected a system stage, found {kind:?}"),
+        }
+    }
+
+    #[test]
+    fn systems_edges_and_ambiguities() {
+        let mut world = World::new();
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "update",
+            SystemStage::parallel()
+                .with_system(write_a.label("write").after("read"))
+                .with_system(read_a.label("read"))
+                .with_system(
+                    write_a_again.with_run_criteria(RunCriteriaDescriptorCoercion::label(
+                        every_frame,
+                        "every frame",
+                    )),
+                )
+                .with_system(exclusive.at_end()),
+        );
+        schedule.add_stage("empty", SystemStage::single_threaded());
+
+        let graph = schedule.graph(&world);
+        let update = stage_graph(&graph, 0);
+        assert!(!update.initialized);
+        assert!(update.ambiguities.is_empty());
+
+        schedule.initialize(&mut world);
+        let graph = schedule.graph(&world);
+        assert_eq!(graph.stages[0].label, "update");
+        assert_eq!(graph.stages[1].label, "empty");
+        let update = stage_graph(&graph, 0);
+        assert!(update.initialized);
+
+        let index = |name: &str| {
+            update
+                .systems
+                .iter()
+                .position(|system| system.name.ends_with(&format!("::{name}")))
+                .unwrap()
+        };
+        let (read, write, write_again) =
+            (index("read_a"), index("write_a"), index("write_a_again"));
+        // Exclusive systems at the end of the stage come after the parallel ones.
+        assert_eq!(index("exclusive"), 3);
+        assert_eq!(
+            update.systems[3].segment,
+            SystemStageSegment::ExclusiveAtEnd
+        );
+        assert!(read < write);
+        assert_eq!(update.systems[write].segment, SystemStageSegment::Parallel);
+        // Function systems are also labelled by their own type.
+        assert!(update.systems[write].labels.contains(&"write".to_owned()));
+        assert_eq!(update.systems[write].after, ["read"]);
+        assert_eq!(
+            update.edges,
+            [SystemEdge {
+                before: read,
+                after: write
+            }]
+        );
+
+        assert_eq!(update.system_run_criteria.len(), 1);
+        assert_eq!(
+            update.system_run_criteria[0],
+            RunCriteriaNode {
+                name: update.system_run_criteria[0].name.clone(),
+                label: Some("every frame".to_owned()),
+                piped_from: None,
+            }
+        );
+        assert_eq!(update.systems[write_again].run_criteria, Some(0));
+
+        // `write_a_again` is unordered with both other parallel systems.
+        let mut ambiguous: Vec<_> = update
+            .ambiguities
+            .iter()
+            .map(|ambiguity| {
+                let mut systems = ambiguity.systems;
+                systems.sort_unstable();
+                assert_eq!(ambiguity.conflicts.len(), 1);
+                assert!(ambiguity.conflicts[0].ends_with("::A"));
+                systems
+            })
+            .collect();
+        ambiguous.sort_unstable();
+        let mut expected = [[read, write_again], [write, write_again]];
+        for pair in &mut expected {
+            pair.sort_unstable();
+        }
+        expected.sort_unstable();
+        assert_eq!(ambiguous, expected);
+    }
+
+    #[test]
+    fn nested_schedules_and_output() {
+        let mut world = World::new();
+        let mut inner = Schedule::default();
+        inner.add_stage(
+            "inner \"quoted\"",
+            SystemStage::parallel().with_system(read_a),
+        );
+        let mut schedule = Schedule::default();
+        schedule.add_stage("outer", inner);
+        schedule.initialize(&mut world);
+
+        let graph = schedule.graph(&world);
+        let inner = match &graph.stages[0].kind {
+            StageGraphKind::Schedule(inner) => inner,
+            kind => panic!("expected a schedule, found {kind:?}"),
+        };
+        assert_eq!(stage_graph(inner, 0).systems.len(), 1);
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("subgraph \"cluster_s0\""));
+        assert!(dot.contains("subgraph \"cluster_s0_s0\""));
+        assert!(dot.contains("label=\"inner \\\"quoted\\\"\";"));
+        assert!(dot.ends_with("}\n"));
+
+        let json = graph.to_json();
+        assert!(json.starts_with(
+            "{\"run_criteria\":null,\"stages\":[{\"label\":\"outer\",\"kind\":\"schedule\",\"schedule\":{\"run_criteria\":null,\"stages\":[{\"label\":\"inner \\\"quoted\\\"\",\"kind\":\"system_stage\""
+        ));
+        assert!(json.contains("\"segment\":\"parallel\",\"labels\":[\"bevy_ecs::schedule::schedule_graph::tests::read_a\"],\"before\":[],\"after\":[],\"run_criteria\":null}"));
+        assert!(json.ends_with("\"edges\":[],\"ambiguities\":[]}}]}}]}"));
+    }
+}
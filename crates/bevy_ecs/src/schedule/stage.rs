@@ -6,9 +6,10 @@ use crate::{
     schedule::{
         graph_utils::{self, DependencyGraphError},
         BoxedRunCriteria, DuplicateLabelStrategy, ExclusiveInsertionPoint, GraphNode,
-        ParallelExecutor, ParallelSystemExecutor, RunCriteriaContainer, RunCriteriaDescriptor,
-        RunCriteriaDescriptorOrLabel, RunCriteriaInner, RunCriteriaLabelId, ShouldRun,
-        SingleThreadedExecutor, SystemContainer, SystemDescriptor, SystemLabelId, SystemSet,
+        ParallelExecutor, ParallelSystemExecutor, RunCondition, RunConditionContainer,
+        RunCriteriaContainer, RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel,
+        RunCriteriaInner, RunCriteriaLabelId, ShouldRun, SingleThreadedExecutor, Stepping,
+        SystemContainer, SystemDescriptor, SystemLabelId, SystemSet, SystemTimings,
     },
     world::{World, WorldId},
 };
@@ -16,6 +17,7 @@ use bevy_ecs_macros::Resource;
 use bevy_utils::{tracing::warn, HashMap, HashSet};
 use core::fmt::Debug;
 use downcast_rs::{impl_downcast, Downcast};
+use fixedbitset::FixedBitSet;
 
 use super::{IntoSystemDescriptor, Schedule};
 
@@ -70,9 +72,11 @@ pub struct SystemStage {
     /// Instance of a scheduling algorithm for running the systems.
     executor: Box<dyn ParallelSystemExecutor>,
     /// Determines whether the stage should run.
-    stage_run_criteria: BoxedRunCriteria,
+    pub(super) stage_run_criteria: BoxedRunCriteria,
     /// Topologically sorted run criteria of systems.
-    run_criteria: Vec<RunCriteriaContainer>,
+    pub(super) run_criteria: Vec<RunCriteriaContainer>,
+    /// Run conditions of systems and system sets, evaluated once per run of the stage.
+    pub(super) conditions: Vec<RunConditionContainer>,
     /// Topologically sorted exclusive systems that want to be run at the start of the stage.
     pub(super) exclusive_at_start: Vec<SystemContainer>,
     /// Topologically sorted exclusive systems that want to be run after parallel systems but
@@ -82,6 +86,8 @@ pub struct SystemStage {
     pub(super) exclusive_at_end: Vec<SystemContainer>,
     /// Topologically sorted parallel systems.
     pub(super) parallel: Vec<SystemContainer>,
+    /// The number of batches the parallel systems are split into by sync points.
+    parallel_batches: usize,
     /// Determines if the stage was modified and needs to rebuild its graphs and orders.
     pub(super) systems_modified: bool,
     /// Determines if the stage's executor was changed.
@@ -110,11 +116,13 @@ impl SystemStage {
             executor,
             stage_run_criteria: Default::default(),
             run_criteria: vec![],
+            conditions: vec![],
             uninitialized_run_criteria: vec![],
             exclusive_at_start: Default::default(),
             exclusive_before_commands: Default::default(),
             exclusive_at_end: Default::default(),
             parallel: vec![],
+            parallel_batches: 1,
             systems_modified: true,
             executor_modified: true,
             uninitialized_parallel: vec![],
@@ -164,7 +172,7 @@ impl SystemStage {
     }
 
     pub fn add_system<Params>(&mut self, system: impl IntoSystemDescriptor<Params>) -> &mut Self {
-        self.add_system_inner(system.into_descriptor(), None);
+        self.add_system_inner(system.into_descriptor(), None, &[]);
         self
     }
 
@@ -172,11 +180,17 @@ impl SystemStage {
         &mut self,
         mut descriptor: SystemDescriptor,
         default_run_criteria: Option<usize>,
+        set_conditions: &[usize],
     ) {
         self.systems_modified = true;
+        let mut conditions = set_conditions.to_vec();
+        for condition in descriptor.conditions.drain(..) {
+            conditions.push(self.add_condition(condition));
+        }
         if let Some(insertion_point) = descriptor.exclusive_insertion_point {
             let criteria = descriptor.run_criteria.take();
             let mut container = SystemContainer::from_descriptor(descriptor);
+            container.conditions = conditions;
             match criteria {
                 Some(RunCriteriaDescriptorOrLabel::Label(label)) => {
                     container.run_criteria_label = Some(label);
@@ -210,6 +224,7 @@ impl SystemStage {
         } else {
             let criteria = descriptor.run_criteria.take();
             let mut container = SystemContainer::from_descriptor(descriptor);
+            container.conditions = conditions;
             match criteria {
                 Some(RunCriteriaDescriptorOrLabel::Label(label)) => {
                     container.run_criteria_label = Some(label);
@@ -228,6 +243,11 @@ impl SystemStage {
         }
     }
 
+    fn add_condition(&mut self, condition: RunCondition) -> usize {
+        self.conditions.push(RunConditionContainer::new(condition));
+        self.conditions.len() - 1
+    }
+
     pub fn apply_buffers(&mut self, world: &mut World) {
         for container in &mut self.parallel {
             let system = container.system_mut();
@@ -279,7 +299,11 @@ impl SystemStage {
 
     pub fn add_system_set(&mut self, system_set: SystemSet) -> &mut Self {
         self.systems_modified = true;
-        let (run_criteria, mut systems) = system_set.bake();
+        let (run_criteria, conditions, mut systems) = system_set.bake();
+        let set_conditions: Vec<_> = conditions
+            .into_iter()
+            .map(|condition| self.add_condition(condition))
+            .collect();
         let set_run_criteria_index = run_criteria.and_then(|criteria| {
             // validate that no systems have criteria
             for descriptor in &mut systems {
@@ -310,7 +334,7 @@ impl SystemStage {
             }
         });
         for system in systems {
-            self.add_system_inner(system, set_run_criteria_index);
+            self.add_system_inner(system, set_run_criteria_index, &set_conditions);
         }
         self
     }
@@ -535,6 +559,9 @@ impl SystemStage {
             for parallel_system in &mut self.parallel {
                 parallel_system.system_mut().check_change_tick(change_tick);
             }
+            for condition in &mut self.conditions {
+                condition.check_change_tick(change_tick);
+            }
 
             // Check all component change ticks.
             world.check_change_ticks();
@@ -690,8 +717,17 @@ fn process_systems(
     Ok(())
 }
 
-impl Stage for SystemStage {
-    fn run(&mut self, world: &mut World) {
+impl SystemStage {
+    /// Initializes the systems and run criteria added since the last run and resolves their order,
+    /// without running them.
+    ///
+    /// This is done automatically when the stage runs; calling it beforehand is only needed to
+    /// inspect the final order, for example with [`Schedule::graph`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stage was already initialized or run with another [`World`].
+    pub fn initialize(&mut self, world: &mut World) {
         if let Some(world_id) = self.world_id {
             assert!(
                 world.id() == world_id,
@@ -704,6 +740,7 @@ impl Stage for SystemStage {
         if self.systems_modified {
             self.initialize_systems(world);
             self.rebuild_orders_and_dependencies();
+            self.assign_parallel_batches();
             self.systems_modified = false;
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
@@ -717,12 +754,93 @@ impl Stage for SystemStage {
             self.executor.rebuild_cached_data(&self.parallel);
             self.executor_modified = false;
         }
+    }
+
+    /// Splits the sorted parallel systems into batches at their sync points, see
+    /// [`apply_system_buffers`](crate::schedule::apply_system_buffers).
+    fn assign_parallel_batches(&mut self) {
+        self.parallel_batches = 1;
+        for index in 0..self.parallel.len() {
+            let container = &self.parallel[index];
+            let batch = container
+                .dependencies()
+                .iter()
+                .map(|&dependency| {
+                    let dependency = &self.parallel[dependency];
+                    if dependency.sync_point || container.apply_buffers_before {
+                        dependency.batch + 1
+                    } else {
+                        dependency.batch
+                    }
+                })
+                .max()
+                .unwrap_or(0);
+            self.parallel[index].batch = batch;
+            self.parallel_batches = self.parallel_batches.max(batch + 1);
+        }
+    }
+
+    /// Returns which systems are allowed to run by [`Stepping`], in stepping order, or [`None`] if
+    /// all of them are.
+    fn stepping_allowed_systems(&self, world: &mut World) -> Option<FixedBitSet> {
+        let mut stepping = world.get_resource_mut::<Stepping>()?;
+        if !stepping.is_enabled() {
+            return None;
+        }
+        stepping.allowed_systems(
+            self.exclusive_at_start
+                .iter()
+                .chain(&self.parallel)
+                .chain(&self.exclusive_before_commands)
+                .chain(&self.exclusive_at_end)
+                .map(|container| container.labels()),
+        )
+    }
+
+    /// Moves the run times of the systems into [`SystemTimings`].
+    fn record_system_timings(&mut self, world: &mut World) {
+        if let Some(mut timings) = world.get_resource_mut::<SystemTimings>() {
+            for container in self
+                .exclusive_at_start
+                .iter_mut()
+                .chain(&mut self.parallel)
+                .chain(&mut self.exclusive_before_commands)
+                .chain(&mut self.exclusive_at_end)
+            {
+                if let Some(run_time) = container.take_run_time() {
+                    timings.add_system_time(container.name(), run_time);
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if no systems or run criteria were added since the stage was last
+    /// initialized.
+    pub fn is_initialized(&self) -> bool {
+        !self.systems_modified
+    }
+}
+
+fn stepping_allows(stepping: &Option<FixedBitSet>, index: usize) -> bool {
+    match stepping {
+        Some(allowed) => allowed[index],
+        None => true,
+    }
+}
+
+impl Stage for SystemStage {
+    fn run(&mut self, world: &mut World) {
+        self.initialize(world);
+        let record_time = world.contains_resource::<SystemTimings>();
+        // The systems allowed to run by `Stepping`, decided once when the stage starts running.
+        let mut stepping = None;
+        let mut stepping_checked = false;
 
         let mut run_stage_loop = true;
         while run_stage_loop {
             let should_run = self.stage_run_criteria.should_run(world);
             match should_run {
-                ShouldRun::No => return,
+                ShouldRun::No => break,
                 ShouldRun::NoAndCheckAgain => continue,
                 ShouldRun::YesAndCheckAgain => (),
                 ShouldRun::Yes => {
@@ -730,6 +848,16 @@ impl Stage for SystemStage {
                 }
             };
 
+            if !stepping_checked {
+                stepping = self.stepping_allowed_systems(world);
+                stepping_checked = true;
+
+                // Evaluate system run conditions, once per run of the stage.
+                for condition in &mut self.conditions {
+                    condition.evaluate(world);
+                }
+            }
+
             // Evaluate system run criteria.
             for index in 0..self.run_criteria.len() {
                 let (run_criteria, tail) = self.run_criteria.split_at_mut(index);
@@ -758,6 +886,7 @@ impl Stage for SystemStage {
                 fn should_run(
                     container: &SystemContainer,
                     run_criteria: &[RunCriteriaContainer],
+                    conditions: &[RunConditionContainer],
                     default: ShouldRun,
                 ) -> bool {
                     matches!(
@@ -766,12 +895,21 @@ impl Stage for SystemStage {
                             .map(|index| run_criteria[index].should_run)
                             .unwrap_or(default),
                         ShouldRun::Yes | ShouldRun::YesAndCheckAgain
-                    )
+                    ) && container
+                        .conditions
+                        .iter()
+                        .all(|&index| conditions[index].met)
                 }
 
                 // Run systems that want to be at the start of stage.
-                for container in &mut self.exclusive_at_start {
-                    if should_run(container, &self.run_criteria, default_should_run) {
+                for (index, container) in self.exclusive_at_start.iter_mut().enumerate() {
+                    if should_run(
+                        container,
+                        &self.run_criteria,
+                        &self.conditions,
+                        default_should_run,
+                    ) && stepping_allows(&stepping, index)
+                    {
                         {
                             #[cfg(feature = "trace")]
                             let _system_span = bevy_utils::tracing::info_span!(
@@ -779,7 +917,7 @@ impl Stage for SystemStage {
                                 name = &*container.name()
                             )
                             .entered();
-                            container.system_mut().run((), world);
+                            container.run(world, record_time);
                         }
                         {
                             #[cfg(feature = "trace")]
@@ -795,15 +933,51 @@ impl Stage for SystemStage {
 
                 // Run parallel systems using the executor.
                 // TODO: hard dependencies, nested sets, whatever... should be evaluated here.
-                for container in &mut self.parallel {
-                    container.should_run =
-                        should_run(container, &self.run_criteria, default_should_run);
+                // Batches separated by sync points run one after the other, and the buffers of
+                // all but the last batch are applied right after they run.
+                let offset = self.exclusive_at_start.len();
+                for batch in 0..self.parallel_batches {
+                    let mut any_should_run = false;
+                    for (index, container) in self.parallel.iter_mut().enumerate() {
+                        container.should_run = container.batch == batch
+                            && should_run(
+                                container,
+                                &self.run_criteria,
+                                &self.conditions,
+                                default_should_run,
+                            )
+                            && stepping_allows(&stepping, offset + index);
+                        any_should_run |= container.should_run;
+                    }
+                    if !any_should_run {
+                        continue;
+                    }
+                    self.executor.run_systems(&mut self.parallel, world);
+                    if self.apply_buffers && batch + 1 < self.parallel_batches {
+                        for container in &mut self.parallel {
+                            if container.should_run {
+                                #[cfg(feature = "trace")]
+                                let _span = bevy_utils::tracing::info_span!(
+                                    "system_commands",
+                                    name = &*container.name()
+                                )
+                                .entered();
+                                container.system_mut().apply_buffers(world);
+                            }
+                        }
+                    }
                 }
-                self.executor.run_systems(&mut self.parallel, world);
 
                 // Run systems that want to be between parallel systems and their command buffers.
-                for container in &mut self.exclusive_before_commands {
-                    if should_run(container, &self.run_criteria, default_should_run) {
+                let offset = self.exclusive_at_start.len() + self.parallel.len();
+                for (index, container) in self.exclusive_before_commands.iter_mut().enumerate() {
+                    if should_run(
+                        container,
+                        &self.run_criteria,
+                        &self.conditions,
+                        default_should_run,
+                    ) && stepping_allows(&stepping, offset + index)
+                    {
                         {
                             #[cfg(feature = "trace")]
                             let _system_span = bevy_utils::tracing::info_span!(
@@ -811,7 +985,7 @@ impl Stage for SystemStage {
                                 name = &*container.name()
                             )
                             .entered();
-                            container.system_mut().run((), world);
+                            container.run(world, record_time);
                         }
                         {
                             #[cfg(feature = "trace")]
@@ -841,8 +1015,17 @@ impl Stage for SystemStage {
                 }
 
                 // Run systems that want to be at the end of stage.
-                for container in &mut self.exclusive_at_end {
-                    if should_run(container, &self.run_criteria, default_should_run) {
+                let offset = self.exclusive_at_start.len()
+                    + self.parallel.len()
+                    + self.exclusive_before_commands.len();
+                for (index, container) in self.exclusive_at_end.iter_mut().enumerate() {
+                    if should_run(
+                        container,
+                        &self.run_criteria,
+                        &self.conditions,
+                        default_should_run,
+                    ) && stepping_allows(&stepping, offset + index)
+                    {
                         {
                             #[cfg(feature = "trace")]
                             let _system_span = bevy_utils::tracing::info_span!(
@@ -850,7 +1033,7 @@ impl Stage for SystemStage {
                                 name = &*container.name()
                             )
                             .entered();
-                            container.system_mut().run((), world);
+                            container.run(world, record_time);
                         }
                         {
                             #[cfg(feature = "trace")]
@@ -905,6 +1088,10 @@ impl Stage for SystemStage {
                 default_should_run = ShouldRun::No;
             }
         }
+
+        if record_time {
+            self.record_system_timings(world);
+        }
     }
 }
 
@@ -914,8 +1101,8 @@ mod tests {
 
     use crate::{
         schedule::{
-            IntoSystemDescriptor, RunCriteria, RunCriteriaDescriptorCoercion, ShouldRun,
-            SingleThreadedExecutor, Stage, SystemLabel, SystemSet, SystemStage,
+            apply_system_buffers, IntoSystemDescriptor, RunCriteria, RunCriteriaDescriptorCoercion,
+            ShouldRun, SingleThreadedExecutor, Stage, SystemLabel, SystemSet, SystemStage,
         },
         system::{In, Local, Query, ResMut},
         world::World,
@@ -1648,4 +1835,53 @@ mod tests {
         stage_spawn.run(&mut world);
         assert_eq!(world.resource::<EntityCount>().0, vec![0, 2]);
     }
+
+    fn spawn_entity(mut commands: crate::prelude::Commands) {
+        commands.spawn(W(0usize));
+    }
+
+    fn count_entities(query: Query<&W<usize>>, mut res: ResMut<EntityCount>) {
+        res.0.push(query.iter().len());
+    }
+
+    #[test]
+    fn sync_point() {
+        let mut world = World::new();
+        world.init_resource::<EntityCount>();
+        let mut stage = SystemStage::parallel()
+            .with_system(spawn_entity.label("spawn"))
+            .with_system(apply_system_buffers().label("sync").after("spawn"))
+            .with_system(count_entities.label("count").after("sync"))
+            .with_system(spawn_entity.after("count"));
+        stage.run(&mut world);
+        stage.run(&mut world);
+        // The entity spawned by the last system is only visible in the next run.
+        assert_eq!(world.resource::<EntityCount>().0, vec![1, 3]);
+    }
+
+    #[test]
+    fn apply_buffers_before() {
+        let mut world = World::new();
+        world.init_resource::<EntityCount>();
+        let mut stage = SystemStage::parallel()
+            .with_system(spawn_entity.label("spawn"))
+            .with_system(count_entities.after("spawn").apply_buffers_before())
+            .with_system(count_entities.after("spawn"));
+        stage.run(&mut world);
+        let mut counts = world.resource::<EntityCount>().0.clone();
+        counts.sort_unstable();
+        assert_eq!(counts, vec![0, 1]);
+    }
+
+    #[test]
+    fn sync_point_without_applying_buffers() {
+        let mut world = World::new();
+        world.init_resource::<EntityCount>();
+        let mut stage = SystemStage::parallel()
+            .with_system(spawn_entity.label("spawn"))
+            .with_system(count_entities.after("spawn").apply_buffers_before());
+        stage.set_apply_buffers(false);
+        stage.run(&mut world);
+        assert_eq!(world.resource::<EntityCount>().0, vec![0]);
+    }
 }
@@ -0,0 +1,398 @@
+use bevy_utils::HashSet;
+use fixedbitset::FixedBitSet;
+
+use crate::{
+    self as bevy_ecs,
+    schedule::{StageLabelId, SystemLabelId},
+    system::{AsSystemLabel, Resource},
+};
+
+/// Pauses the systems of a [`Schedule`](crate::schedule::Schedule) to run them one system or one
+/// stage at a time, so that the world can be inspected between them.
+///
+/// While stepping is [enabled](Stepping::enable), the stages of the schedules of the world holding
+/// this resource only run the systems allowed by the last action, and the others are skipped as if
+/// their run criteria returned [`ShouldRun::No`](crate::schedule::ShouldRun::No). The position of
+/// the next system to run, the cursor, is kept across frames until it reaches the end of the
+/// schedule:
+/// - [`step_system`](Stepping::step_system) runs the next system,
+/// - [`step_stage`](Stepping::step_stage) runs the remaining systems of the current stage,
+/// - [`continue_frame`](Stepping::continue_frame) runs the remaining systems of the frame, or until
+///   a system with a [breakpoint](Stepping::add_breakpoint).
+///
+/// Systems marked with [`always_run`](Stepping::always_run), like input handling or rendering,
+/// run every frame and are ignored by the cursor.
+///
+/// Systems are stepped in the order they run in their stage: exclusive systems at the start of the
+/// stage, parallel systems, exclusive systems before commands, and exclusive systems at the end of
+/// the stage. Parallel systems are stepped in their topological order, which is also the order of
+/// [`SystemStageGraph::systems`](crate::schedule::SystemStageGraph::systems). Only stages run by a
+/// [`Schedule`](crate::schedule::Schedule) are stepped.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::Stepping;
+/// #[derive(Resource, Default)]
+/// struct Log(Vec<&'static str>);
+///
+/// fn input(mut log: ResMut<Log>) {
+///     log.0.push("input");
+/// }
+/// fn a(mut log: ResMut<Log>) {
+///     log.0.push("a");
+/// }
+/// fn b(mut log: ResMut<Log>) {
+///     log.0.push("b");
+/// }
+///
+/// let mut world = World::new();
+/// world.init_resource::<Log>();
+/// let mut schedule = Schedule::default();
+/// schedule.add_stage(
+///     "update",
+///     SystemStage::single_threaded()
+///         .with_system(input)
+///         .with_system(a.after(input))
+///         .with_system(b.after(a)),
+/// );
+///
+/// let mut stepping = Stepping::default();
+/// stepping.enable().always_run(input);
+/// world.insert_resource(stepping);
+///
+/// // Only the systems that always run are run while waiting for an action.
+/// schedule.run(&mut world);
+/// assert_eq!(world.resource::<Log>().0, ["input"]);
+///
+/// world.resource_mut::<Stepping>().step_system();
+/// schedule.run(&mut world);
+/// assert_eq!(world.resource::<Log>().0, ["input", "input", "a"]);
+///
+/// world.resource_mut::<Stepping>().continue_frame();
+/// schedule.run(&mut world);
+/// assert_eq!(world.resource::<Log>().0, ["input", "input", "a", "input", "b"]);
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct Stepping {
+    enabled: bool,
+    action: StepAction,
+    /// Whether the last action did not run any system yet, so that a breakpoint on the system at
+    /// the cursor does not stop it from continuing.
+    resumed: bool,
+    breakpoints: HashSet<SystemLabelId>,
+    always_run: HashSet<SystemLabelId>,
+    /// The stage being run by a schedule.
+    running_stage: Option<StageLabelId>,
+    /// Depth of the schedules being run, to detect the end of a frame.
+    schedule_depth: usize,
+    /// The stage in progress and the index of the next system to run in it.
+    cursor: Option<(StageLabelId, usize)>,
+    /// The stages the cursor went through since the start of the frame.
+    completed_stages: HashSet<StageLabelId>,
+    /// The stages run since the start of the frame.
+    seen_stages: HashSet<StageLabelId>,
+}
+
+/// The last action given to [`Stepping`], returned by [`Stepping::action`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StepAction {
+    /// Systems are not run until another action is given.
+    #[default]
+    Wait,
+    /// The next system runs.
+    StepSystem,
+    /// The remaining systems of the current stage run.
+    StepStage,
+    /// The remaining systems of the frame run, until a system with a breakpoint.
+    Continue,
+}
+
+impl Stepping {
+    /// Starts stepping from the start of the next frame.
+    pub fn enable(&mut self) -> &mut Self {
+        if !self.enabled {
+            self.enabled = true;
+            self.action = StepAction::Wait;
+            self.cursor = None;
+            self.completed_stages.clear();
+            self.seen_stages.clear();
+        }
+        self
+    }
+
+    /// Stops stepping: all systems run normally.
+    pub fn disable(&mut self) -> &mut Self {
+        self.enabled = false;
+        self
+    }
+
+    /// Returns `true` if stepping is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the last action, or [`StepAction::Wait`] once it is done.
+    pub fn action(&self) -> StepAction {
+        self.action
+    }
+
+    /// Returns the stage in progress and the index of the next system to run in it, or [`None`]
+    /// if the cursor is between two stages.
+    pub fn cursor(&self) -> Option<(StageLabelId, usize)> {
+        self.cursor
+    }
+
+    /// Runs the next system that does not [always run](Stepping::always_run).
+    pub fn step_system(&mut self) -> &mut Self {
+        self.set_action(StepAction::StepSystem)
+    }
+
+    /// Runs the remaining systems of the current stage, or of the next stage if the cursor is
+    /// between two stages.
+    pub fn step_stage(&mut self) -> &mut Self {
+        self.set_action(StepAction::StepStage)
+    }
+
+    /// Runs the remaining systems of the frame, stopping before the next system with a
+    /// [breakpoint](Stepping::add_breakpoint).
+    pub fn continue_frame(&mut self) -> &mut Self {
+        self.set_action(StepAction::Continue)
+    }
+
+    fn set_action(&mut self, action: StepAction) -> &mut Self {
+        self.action = action;
+        self.resumed = true;
+        self
+    }
+
+    /// Stops [`continue_frame`](Stepping::continue_frame) before running the systems with the
+    /// given label.
+    pub fn add_breakpoint<M>(&mut self, label: impl AsSystemLabel<M>) -> &mut Self {
+        self.breakpoints.insert(label.as_system_label());
+        self
+    }
+
+    /// Removes a breakpoint added with [`add_breakpoint`](Stepping::add_breakpoint).
+    pub fn remove_breakpoint<M>(&mut self, label: impl AsSystemLabel<M>) -> &mut Self {
+        self.breakpoints.remove(&label.as_system_label());
+        self
+    }
+
+    /// Lets the systems with the given label run every frame while stepping.
+    pub fn always_run<M>(&mut self, label: impl AsSystemLabel<M>) -> &mut Self {
+        self.always_run.insert(label.as_system_label());
+        self
+    }
+
+    /// Removes a label added with [`always_run`](Stepping::always_run).
+    pub fn remove_always_run<M>(&mut self, label: impl AsSystemLabel<M>) -> &mut Self {
+        self.always_run.remove(&label.as_system_label());
+        self
+    }
+
+    pub(crate) fn enter_schedule(&mut self) {
+        self.schedule_depth += 1;
+    }
+
+    pub(crate) fn set_running_stage(&mut self, stage: Option<StageLabelId>) {
+        self.running_stage = stage;
+    }
+
+    /// Starts a new frame at the end of the outermost schedule if the cursor went through all of
+    /// the stages that ran.
+    pub(crate) fn exit_schedule(&mut self) {
+        self.schedule_depth = self.schedule_depth.saturating_sub(1);
+        if self.schedule_depth > 0 || !self.enabled || self.cursor.is_some() {
+            return;
+        }
+        if self.seen_stages.is_subset(&self.completed_stages) {
+            self.completed_stages.clear();
+            if self.action == StepAction::Continue {
+                self.action = StepAction::Wait;
+            }
+        }
+        self.seen_stages.clear();
+    }
+
+    /// Returns which of the systems of the running stage, given by their labels in stepping order,
+    /// are allowed to run, and moves the cursor past them. Returns [`None`] if all systems can run.
+    pub(crate) fn allowed_systems<'a>(
+        &mut self,
+        systems: impl Iterator<Item = &'a [SystemLabelId]>,
+    ) -> Option<FixedBitSet> {
+        if !self.enabled {
+            return None;
+        }
+        let stage = self.running_stage?;
+        self.seen_stages.insert(stage);
+
+        let mut always_run = FixedBitSet::new();
+        let mut breakpoints = FixedBitSet::new();
+        for (index, labels) in systems.enumerate() {
+            let has_label = |set: &HashSet<SystemLabelId>| labels.iter().any(|l| set.contains(l));
+            always_run.grow(index + 1);
+            breakpoints.grow(index + 1);
+            always_run.set(index, has_label(&self.always_run));
+            breakpoints.set(index, has_label(&self.breakpoints));
+        }
+        let mut allowed = always_run.clone();
+
+        let mut next = match self.cursor {
+            _ if self.completed_stages.contains(&stage) => return Some(allowed),
+            Some((cursor_stage, next)) if cursor_stage == stage => next,
+            // The cursor is in an other stage, which runs before this one.
+            Some(_) => return Some(allowed),
+            None => 0,
+        };
+
+        while next < allowed.len() {
+            if always_run[next] {
+                next += 1;
+                continue;
+            }
+            match self.action {
+                StepAction::Wait => break,
+                StepAction::StepSystem => {
+                    allowed.insert(next);
+                    self.action = StepAction::Wait;
+                }
+                StepAction::StepStage => allowed.insert(next),
+                StepAction::Continue => {
+                    if breakpoints[next] && !self.resumed {
+                        self.action = StepAction::Wait;
+                        break;
+                    }
+                    allowed.insert(next);
+                }
+            }
+            self.resumed = false;
+            next += 1;
+        }
+
+        if next < allowed.len() {
+            self.cursor = Some((stage, next));
+        } else {
+            self.cursor = None;
+            self.completed_stages.insert(stage);
+            if self.action == StepAction::StepStage {
+                self.action = StepAction::Wait;
+            }
+        }
+        Some(allowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        prelude::*,
+        schedule::{StageLabel, StepAction, Stepping},
+    };
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<&'static str>);
+
+    fn make_system(name: &'static str) -> impl FnMut(ResMut<Log>) {
+        move |mut log| log.0.push(name)
+    }
+
+    fn setup() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        let mut schedule = Schedule::default();
+        schedule
+            .add_stage(
+                "first",
+                SystemStage::parallel()
+                    .with_system(make_system("input").label("input"))
+                    .with_system(make_system("a").label("a").after("input"))
+                    .with_system(make_system("b").label("b").after("a")),
+            )
+            .add_stage(
+                "second",
+                SystemStage::parallel()
+                    .with_system(make_system("c").label("c"))
+                    .with_system(
+                        (|mut log: ResMut<Log>| log.0.push("exclusive"))
+                            .label("exclusive")
+                            .at_start(),
+                    ),
+            );
+        let mut stepping = Stepping::default();
+        stepping.enable().always_run("input");
+        world.insert_resource(stepping);
+        (world, schedule)
+    }
+
+    fn run(world: &mut World, schedule: &mut Schedule) -> Vec<&'static str> {
+        schedule.run(world);
+        std::mem::take(&mut world.resource_mut::<Log>().0)
+    }
+
+    #[test]
+    fn step_systems_and_stages() {
+        let (mut world, mut schedule) = setup();
+        assert_eq!(run(&mut world, &mut schedule), ["input"]);
+        assert_eq!(run(&mut world, &mut schedule), ["input"]);
+
+        world.resource_mut::<Stepping>().step_system();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "a"]);
+        assert_eq!(
+            world.resource::<Stepping>().cursor(),
+            Some((StageLabel::as_label(&"first"), 2))
+        );
+
+        // Step to the end of the current stage.
+        world.resource_mut::<Stepping>().step_stage();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "b"]);
+        // The next stage ran too, without running any system.
+        assert_eq!(
+            world.resource::<Stepping>().cursor(),
+            Some((StageLabel::as_label(&"second"), 0))
+        );
+        assert_eq!(world.resource::<Stepping>().action(), StepAction::Wait);
+
+        // The exclusive system at the start of the stage comes first.
+        world.resource_mut::<Stepping>().step_system();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "exclusive"]);
+        world.resource_mut::<Stepping>().step_system();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "c"]);
+
+        // The cursor wrapped around to the next frame.
+        world.resource_mut::<Stepping>().step_system();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "a"]);
+
+        world.resource_mut::<Stepping>().disable();
+        assert_eq!(
+            run(&mut world, &mut schedule),
+            ["input", "a", "b", "exclusive", "c"]
+        );
+    }
+
+    #[test]
+    fn continue_to_breakpoint() {
+        let (mut world, mut schedule) = setup();
+        world
+            .resource_mut::<Stepping>()
+            .add_breakpoint("c")
+            .continue_frame();
+        assert_eq!(
+            run(&mut world, &mut schedule),
+            ["input", "a", "b", "exclusive"]
+        );
+        assert_eq!(world.resource::<Stepping>().action(), StepAction::Wait);
+
+        // Continuing runs the system at the breakpoint, then the frame ends.
+        world.resource_mut::<Stepping>().continue_frame();
+        assert_eq!(run(&mut world, &mut schedule), ["input", "c"]);
+        assert_eq!(world.resource::<Stepping>().action(), StepAction::Wait);
+        assert_eq!(run(&mut world, &mut schedule), ["input"]);
+
+        world.resource_mut::<Stepping>().continue_frame();
+        assert_eq!(
+            run(&mut world, &mut schedule),
+            ["input", "a", "b", "exclusive"]
+        );
+    }
+}
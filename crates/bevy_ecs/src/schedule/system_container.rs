@@ -5,7 +5,9 @@ use crate::{
         AmbiguityDetection, GraphNode, RunCriteriaLabelId, SystemDescriptor, SystemLabelId,
     },
     system::System,
+    world::World,
 };
+use bevy_utils::{Duration, Instant};
 use core::fmt::Debug;
 use std::borrow::Cow;
 
@@ -13,6 +15,8 @@ pub struct SystemContainer {
     system: Box<dyn System<In = (), Out = ()>>,
     pub(crate) run_criteria_index: Option<usize>,
     pub(crate) run_criteria_label: Option<RunCriteriaLabelId>,
+    /// Indices of the run conditions of the system in its stage.
+    pub(crate) conditions: Vec<usize>,
     pub(crate) should_run: bool,
     is_exclusive: bool,
     dependencies: Vec<usize>,
@@ -20,6 +24,14 @@ pub struct SystemContainer {
     before: Vec<SystemLabelId>,
     after: Vec<SystemLabelId>,
     pub(crate) ambiguity_detection: AmbiguityDetection,
+    pub(crate) sync_point: bool,
+    pub(crate) apply_buffers_before: bool,
+    /// The batch of parallel systems the system runs in. The command buffers of a batch are
+    /// applied before the next batch runs.
+    pub(crate) batch: usize,
+    /// Time spent running the system since it was last taken, when
+    /// [`SystemTimings`](crate::schedule::SystemTimings) are recorded.
+    run_time: Option<Duration>,
 }
 
 impl SystemContainer {
@@ -29,12 +41,17 @@ impl SystemContainer {
             should_run: false,
             run_criteria_index: None,
             run_criteria_label: None,
+            conditions: Vec::new(),
             dependencies: Vec::new(),
             labels: descriptor.labels,
             before: descriptor.before,
             after: descriptor.after,
             ambiguity_detection: descriptor.ambiguity_detection,
             is_exclusive: descriptor.exclusive_insertion_point.is_some(),
+            sync_point: descriptor.sync_point,
+            apply_buffers_before: descriptor.apply_buffers_before,
+            batch: 0,
+            run_time: None,
         }
     }
 
@@ -82,6 +99,33 @@ impl SystemContainer {
     pub fn is_exclusive(&self) -> bool {
         self.is_exclusive
     }
+
+    /// Returns `true` if this is a sync point added with
+    /// [`apply_system_buffers`](crate::schedule::apply_system_buffers).
+    pub fn is_sync_point(&self) -> bool {
+        self.sync_point
+    }
+
+    /// Returns the system along with its accumulated run time, so that the system can be timed
+    /// while it is borrowed.
+    pub(crate) fn system_and_run_time_mut(
+        &mut self,
+    ) -> (&mut dyn System<In = (), Out = ()>, &mut Option<Duration>) {
+        (&mut *self.system, &mut self.run_time)
+    }
+
+    /// Runs the system, adding the time it took to its run time if `record_time` is `true`.
+    pub(crate) fn run(&mut self, world: &mut World, record_time: bool) {
+        let start = record_time.then(Instant::now);
+        self.system.run((), world);
+        if let Some(start) = start {
+            add_run_time(&mut self.run_time, start.elapsed());
+        }
+    }
+
+    pub(crate) fn take_run_time(&mut self) -> Option<Duration> {
+        self.run_time.take()
+    }
 }
 
 impl Debug for SystemContainer {
@@ -109,3 +153,7 @@ impl GraphNode for SystemContainer {
         &self.after
     }
 }
+
+pub(crate) fn add_run_time(run_time: &mut Option<Duration>, time: Duration) {
+    *run_time = Some(run_time.unwrap_or_default() + time);
+}
@@ -1,5 +1,8 @@
 use crate::{
-    schedule::{IntoRunCriteria, RunCriteriaDescriptorOrLabel, SystemLabel, SystemLabelId},
+    schedule::{
+        IntoRunCondition, IntoRunCriteria, RunCondition, RunCriteriaDescriptorOrLabel, SystemLabel,
+        SystemLabelId,
+    },
     system::{AsSystemLabel, BoxedSystem, IntoSystem},
 };
 
@@ -46,10 +49,13 @@ pub struct SystemDescriptor {
     pub(crate) system: BoxedSystem<(), ()>,
     pub(crate) exclusive_insertion_point: Option<ExclusiveInsertionPoint>,
     pub(crate) run_criteria: Option<RunCriteriaDescriptorOrLabel>,
+    pub(crate) conditions: Vec<RunCondition>,
     pub(crate) labels: Vec<SystemLabelId>,
     pub(crate) before: Vec<SystemLabelId>,
     pub(crate) after: Vec<SystemLabelId>,
     pub(crate) ambiguity_detection: AmbiguityDetection,
+    pub(crate) sync_point: bool,
+    pub(crate) apply_buffers_before: bool,
 }
 
 impl SystemDescriptor {
@@ -63,13 +69,49 @@ impl SystemDescriptor {
             },
             system,
             run_criteria: None,
+            conditions: Vec::new(),
             before: Vec::new(),
             after: Vec::new(),
             ambiguity_detection: Default::default(),
+            sync_point: false,
+            apply_buffers_before: false,
         }
     }
 }
 
+/// Returns a sync point for the parallel systems of a [`SystemStage`](crate::schedule::SystemStage):
+/// the command buffers of the systems ordered before it are applied before the systems ordered
+/// after it run, so that these systems can see the entities spawned by the former.
+///
+/// The sync point has to be ordered with [`before`](IntoSystemDescriptor::before) and
+/// [`after`](IntoSystemDescriptor::after), like other systems.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::schedule::apply_system_buffers;
+/// # fn spawn_enemies() {}
+/// # fn target_enemies() {}
+/// #[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct SpawnEnemies;
+///
+/// #[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct EnemiesSpawned;
+///
+/// SystemStage::parallel()
+///     .with_system(spawn_enemies.label(SpawnEnemies))
+///     .with_system(apply_system_buffers().label(EnemiesSpawned).after(SpawnEnemies))
+///     .with_system(target_enemies.after(EnemiesSpawned));
+/// ```
+pub fn apply_system_buffers() -> SystemDescriptor {
+    fn apply_system_buffers() {}
+
+    let mut descriptor =
+        SystemDescriptor::new(Box::new(IntoSystem::into_system(apply_system_buffers)));
+    descriptor.sync_point = true;
+    descriptor
+}
+
 pub trait IntoSystemDescriptor<Params> {
     fn into_descriptor(self) -> SystemDescriptor;
     /// Assigns a run criteria to the system. Can be a new descriptor or a label of a
@@ -79,9 +121,17 @@ pub trait IntoSystemDescriptor<Params> {
         run_criteria: impl IntoRunCriteria<Marker>,
     ) -> SystemDescriptor;
 
+    /// Adds a condition for running the system; when several conditions are added, they all have
+    /// to be met. See [`RunCondition`].
+    fn run_if<Marker>(self, condition: impl IntoRunCondition<Marker>) -> SystemDescriptor;
+
     /// Assigns a label to the system; there can be more than one, and it doesn't have to be unique.
     fn label(self, label: impl SystemLabel) -> SystemDescriptor;
 
+    /// Specifies that the command buffers of the parallel systems this system runs after should
+    /// be applied before it runs, inserting a sync point after them. See [`apply_system_buffers`].
+    fn apply_buffers_before(self) -> SystemDescriptor;
+
     /// Specifies that the system should run before systems with the given label.
     fn before<Marker>(self, label: impl AsSystemLabel<Marker>) -> SystemDescriptor;
 
@@ -117,11 +167,21 @@ impl IntoSystemDescriptor<()> for SystemDescriptor {
         self
     }
 
+    fn run_if<Marker>(mut self, condition: impl IntoRunCondition<Marker>) -> SystemDescriptor {
+        self.conditions.push(condition.into_run_condition());
+        self
+    }
+
     fn label(mut self, label: impl SystemLabel) -> SystemDescriptor {
         self.labels.push(label.as_label());
         self
     }
 
+    fn apply_buffers_before(mut self) -> SystemDescriptor {
+        self.apply_buffers_before = true;
+        self
+    }
+
     fn before<Marker>(mut self, label: impl AsSystemLabel<Marker>) -> SystemDescriptor {
         self.before.push(label.as_system_label().as_label());
         self
@@ -184,10 +244,18 @@ where
             .with_run_criteria(run_criteria)
     }
 
+    fn run_if<Marker>(self, condition: impl IntoRunCondition<Marker>) -> SystemDescriptor {
+        SystemDescriptor::new(Box::new(IntoSystem::into_system(self))).run_if(condition)
+    }
+
     fn label(self, label: impl SystemLabel) -> SystemDescriptor {
         SystemDescriptor::new(Box::new(IntoSystem::into_system(self))).label(label)
     }
 
+    fn apply_buffers_before(self) -> SystemDescriptor {
+        SystemDescriptor::new(Box::new(IntoSystem::into_system(self))).apply_buffers_before()
+    }
+
     fn before<Marker>(self, label: impl AsSystemLabel<Marker>) -> SystemDescriptor {
         SystemDescriptor::new(Box::new(IntoSystem::into_system(self))).before(label)
     }
@@ -229,10 +297,18 @@ impl IntoSystemDescriptor<()> for BoxedSystem<(), ()> {
         SystemDescriptor::new(self).with_run_criteria(run_criteria)
     }
 
+    fn run_if<Marker>(self, condition: impl IntoRunCondition<Marker>) -> SystemDescriptor {
+        SystemDescriptor::new(self).run_if(condition)
+    }
+
     fn label(self, label: impl SystemLabel) -> SystemDescriptor {
         SystemDescriptor::new(self).label(label)
     }
 
+    fn apply_buffers_before(self) -> SystemDescriptor {
+        SystemDescriptor::new(self).apply_buffers_before()
+    }
+
     fn before<Marker>(self, label: impl AsSystemLabel<Marker>) -> SystemDescriptor {
         SystemDescriptor::new(self).before(label)
     }
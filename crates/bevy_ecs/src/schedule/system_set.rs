@@ -1,6 +1,6 @@
 use crate::schedule::{
-    IntoRunCriteria, IntoSystemDescriptor, RunCriteriaDescriptorOrLabel, State, StateData,
-    SystemDescriptor, SystemLabel, SystemLabelId,
+    IntoRunCondition, IntoRunCriteria, IntoSystemDescriptor, RunCondition,
+    RunCriteriaDescriptorOrLabel, State, StateData, SystemDescriptor, SystemLabel, SystemLabelId,
 };
 use crate::system::AsSystemLabel;
 
@@ -9,6 +9,7 @@ use crate::system::AsSystemLabel;
 pub struct SystemSet {
     pub(crate) systems: Vec<SystemDescriptor>,
     pub(crate) run_criteria: Option<RunCriteriaDescriptorOrLabel>,
+    pub(crate) conditions: Vec<RunCondition>,
     pub(crate) labels: Vec<SystemLabelId>,
     pub(crate) before: Vec<SystemLabelId>,
     pub(crate) after: Vec<SystemLabelId>,
@@ -80,6 +81,14 @@ impl SystemSet {
         self
     }
 
+    /// Adds a condition for running the systems of the set, evaluated once for all of them. When
+    /// several conditions are added, they all have to be met. See [`RunCondition`].
+    #[must_use]
+    pub fn run_if<Marker>(mut self, condition: impl IntoRunCondition<Marker>) -> Self {
+        self.conditions.push(condition.into_run_condition());
+        self
+    }
+
     #[must_use]
     pub fn label(mut self, label: impl SystemLabel) -> Self {
         self.labels.push(label.as_label());
@@ -98,10 +107,17 @@ impl SystemSet {
         self
     }
 
-    pub(crate) fn bake(self) -> (Option<RunCriteriaDescriptorOrLabel>, Vec<SystemDescriptor>) {
+    pub(crate) fn bake(
+        self,
+    ) -> (
+        Option<RunCriteriaDescriptorOrLabel>,
+        Vec<RunCondition>,
+        Vec<SystemDescriptor>,
+    ) {
         let SystemSet {
             mut systems,
             run_criteria,
+            conditions,
             labels,
             before,
             after,
@@ -111,6 +127,6 @@ impl SystemSet {
             descriptor.before.extend(before.iter().cloned());
             descriptor.after.extend(after.iter().cloned());
         }
-        (run_criteria, systems)
+        (run_criteria, conditions, systems)
     }
 }
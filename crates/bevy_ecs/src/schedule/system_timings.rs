@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+
+use bevy_utils::{Duration, HashMap};
+
+use crate::{self as bevy_ecs, schedule::StageLabelId, system::Resource};
+
+/// When this resource is present in the [`World`](crate::world::World), [`SystemStage`]s record
+/// the time spent running each of their systems, and [`Schedule`]s the time spent running each of
+/// their stages.
+///
+/// Times are accumulated until the resource is [`clear`](Self::clear)ed, usually once per frame
+/// by the code reading them. Systems are identified by their name, so multiple systems with the
+/// same name add up their times.
+///
+/// Recording adds the cost of reading the clock twice per system run, and nothing when the
+/// resource is absent.
+///
+/// [`SystemStage`]: crate::schedule::SystemStage
+/// [`Schedule`]: crate::schedule::Schedule
+#[derive(Resource, Debug, Default)]
+pub struct SystemTimings {
+    systems: HashMap<Cow<'static, str>, Duration>,
+    stages: HashMap<StageLabelId, Duration>,
+}
+
+impl SystemTimings {
+    /// Iterates over the systems that ran since the last [`clear`](Self::clear), with the time
+    /// spent running them.
+    pub fn systems(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.systems.iter().map(|(name, time)| (&**name, *time))
+    }
+
+    /// Iterates over the stages that ran since the last [`clear`](Self::clear), with the time
+    /// spent running them, including their run criteria and the application of commands.
+    pub fn stages(&self) -> impl Iterator<Item = (StageLabelId, Duration)> + '_ {
+        self.stages.iter().map(|(label, time)| (*label, *time))
+    }
+
+    /// Returns the time spent running the system with the given name since the last
+    /// [`clear`](Self::clear).
+    pub fn system(&self, name: &str) -> Option<Duration> {
+        self.systems.get(name).copied()
+    }
+
+    /// Returns the time spent running the stage with the given label since the last
+    /// [`clear`](Self::clear).
+    pub fn stage(&self, label: StageLabelId) -> Option<Duration> {
+        self.stages.get(&label).copied()
+    }
+
+    /// Forgets all recorded times.
+    pub fn clear(&mut self) {
+        self.systems.clear();
+        self.stages.clear();
+    }
+
+    pub(crate) fn add_system_time(&mut self, name: Cow<'static, str>, time: Duration) {
+        *self.systems.entry(name).or_default() += time;
+    }
+
+    pub(crate) fn add_stage_time(&mut self, label: StageLabelId, time: Duration) {
+        *self.stages.entry(label).or_default() += time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::*,
+        schedule::{ShouldRun, StageLabel, SystemTimings},
+    };
+
+    fn parallel_a() {}
+    fn parallel_b() {}
+    fn exclusive(_world: &mut World) {}
+
+    fn recorded_systems(world: &World) -> Vec<String> {
+        let mut systems: Vec<_> = world
+            .resource::<SystemTimings>()
+            .systems()
+            .map(|(name, _)| name.rsplit("::").next().unwrap().to_owned())
+            .collect();
+        systems.sort();
+        systems
+    }
+
+    #[test]
+    fn records_systems_and_stages() {
+        for stage in [SystemStage::parallel(), SystemStage::single_threaded()] {
+            let mut world = World::new();
+            let mut schedule = Schedule::default();
+            schedule.add_stage(
+                "update",
+                stage
+                    .with_system(parallel_a)
+                    .with_system(parallel_b)
+                    .with_system(exclusive.at_start()),
+            );
+
+            // Nothing is recorded without the resource.
+            schedule.run(&mut world);
+            world.init_resource::<SystemTimings>();
+
+            schedule.run(&mut world);
+            assert_eq!(
+                recorded_systems(&world),
+                ["exclusive", "parallel_a", "parallel_b"]
+            );
+            let timings = world.resource::<SystemTimings>();
+            let stage_time = timings.stage(StageLabel::as_label(&"update")).unwrap();
+            assert!(timings.systems().all(|(_, time)| time <= stage_time));
+
+            world.resource_mut::<SystemTimings>().clear();
+            assert!(recorded_systems(&world).is_empty());
+        }
+    }
+
+    #[test]
+    fn skipped_systems_are_not_recorded() {
+        let mut world = World::new();
+        world.init_resource::<SystemTimings>();
+        let mut stage = SystemStage::parallel()
+            .with_system(parallel_a)
+            .with_system(parallel_b.with_run_criteria(|| ShouldRun::No));
+        stage.run(&mut world);
+        assert_eq!(recorded_systems(&world), ["parallel_a"]);
+    }
+}
@@ -0,0 +1,771 @@
+//! Snapshots of the reflected state of a [`World`], to save it and roll it back.
+
+use std::{any::TypeId, cmp::Ordering, fmt};
+
+use bevy_reflect::{Reflect, TypeRegistry};
+use bevy_utils::HashSet;
+
+use crate::{
+    component::ComponentId,
+    entity::{Entity, EntityMap, MapEntitiesError},
+    reflect::{ReflectComponent, ReflectMapEntities, ReflectResource},
+    world::World,
+};
+
+/// Selects the types captured by a [`WorldSnapshot`].
+///
+/// By default every type registered with [`ReflectComponent`] or [`ReflectResource`] is captured.
+/// [`allow`](Self::allow) restricts the snapshot to the allowed types, and [`deny`](Self::deny)
+/// excludes types from it.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotFilter {
+    allowed: Option<HashSet<TypeId>>,
+    denied: HashSet<TypeId>,
+}
+
+impl SnapshotFilter {
+    /// Captures `T`, and only the other allowed types.
+    #[must_use]
+    pub fn allow<T: 'static>(self) -> Self {
+        self.allow_by_id(TypeId::of::<T>())
+    }
+
+    /// Captures the type with the given [`TypeId`], and only the other allowed types.
+    #[must_use]
+    pub fn allow_by_id(mut self, type_id: TypeId) -> Self {
+        self.allowed
+            .get_or_insert_with(HashSet::default)
+            .insert(type_id);
+        self
+    }
+
+    /// Doesn't capture `T`.
+    #[must_use]
+    pub fn deny<T: 'static>(self) -> Self {
+        self.deny_by_id(TypeId::of::<T>())
+    }
+
+    /// Doesn't capture the type with the given [`TypeId`].
+    #[must_use]
+    pub fn deny_by_id(mut self, type_id: TypeId) -> Self {
+        self.denied.insert(type_id);
+        self
+    }
+
+    /// Returns `true` if the type with the given [`TypeId`] is captured.
+    pub fn is_allowed(&self, type_id: TypeId) -> bool {
+        if self.denied.contains(&type_id) {
+            return false;
+        }
+        match &self.allowed {
+            Some(allowed) => allowed.contains(&type_id),
+            None => true,
+        }
+    }
+}
+
+/// The reflected state of the components and resources of a [`World`] at some point.
+///
+/// A snapshot contains a copy of every component and resource whose type is registered with
+/// [`ReflectComponent`] or [`ReflectResource`] in a [`TypeRegistry`] and allowed by a
+/// [`SnapshotFilter`]. Only entities with at least one captured component are part of the
+/// snapshot.
+///
+/// [`restore`](Self::restore) rolls the world back to the snapshot, and [`diff`](Self::diff)
+/// lists what changed between two snapshots, for example to only send the changes over the
+/// network.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::snapshot::{SnapshotFilter, WorldSnapshot};
+/// # use bevy_reflect::{Reflect, TypeRegistry};
+/// #[derive(Component, Reflect, Default)]
+/// #[reflect(Component)]
+/// struct Position(f32);
+///
+/// let mut registry = TypeRegistry::default();
+/// registry.register::<Position>();
+///
+/// let mut world = World::new();
+/// let entity = world.spawn(Position(0.0)).id();
+/// let snapshot = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+///
+/// world.get_mut::<Position>(entity).unwrap().0 = 10.0;
+/// world.spawn(Position(5.0));
+///
+/// snapshot.restore(&mut world, &registry).unwrap();
+/// assert_eq!(world.get::<Position>(entity).unwrap().0, 0.0);
+/// assert_eq!(world.query::<&Position>().iter(&world).count(), 1);
+/// ```
+pub struct WorldSnapshot {
+    filter: SnapshotFilter,
+    entities: Vec<EntitySnapshot>,
+    /// The entities that existed without captured components, ordered by [`Entity`].
+    uncaptured: Vec<Entity>,
+    resources: Vec<ReflectedValue>,
+}
+
+/// The captured components of an entity in a [`WorldSnapshot`].
+pub struct EntitySnapshot {
+    entity: Entity,
+    components: Vec<ReflectedValue>,
+}
+
+impl EntitySnapshot {
+    /// The captured entity.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The captured components of the entity, with their [`TypeId`].
+    pub fn components(&self) -> impl Iterator<Item = (TypeId, &dyn Reflect)> {
+        self.components
+            .iter()
+            .map(|value| (value.type_id, &*value.value))
+    }
+
+    fn get(&self, type_id: TypeId) -> Option<&dyn Reflect> {
+        self.components
+            .iter()
+            .find(|value| value.type_id == type_id)
+            .map(|value| &*value.value)
+    }
+}
+
+struct ReflectedValue {
+    type_id: TypeId,
+    value: Box<dyn Reflect>,
+}
+
+impl ReflectedValue {
+    fn new(type_id: TypeId, value: &dyn Reflect) -> Self {
+        Self {
+            type_id,
+            value: value.clone_value(),
+        }
+    }
+}
+
+impl fmt::Debug for WorldSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorldSnapshot")
+            .field("filter", &self.filter)
+            .field("entities", &self.entities.len())
+            .field("resources", &self.resources.len())
+            .finish()
+    }
+}
+
+impl WorldSnapshot {
+    /// Captures the components and resources of `world` registered in `registry` and allowed by
+    /// `filter`.
+    pub fn capture(world: &World, registry: &TypeRegistry, filter: SnapshotFilter) -> Self {
+        let components = captured_components(world, registry, &filter);
+        let mut entities = Vec::new();
+        let mut uncaptured = Vec::new();
+        let mut archetype_components = Vec::new();
+        for archetype in world.archetypes().iter() {
+            archetype_components.clear();
+            archetype_components.extend(
+                components
+                    .iter()
+                    .filter(|component| archetype.contains(component.component_id)),
+            );
+            if archetype_components.is_empty() {
+                uncaptured.extend(
+                    archetype
+                        .entities()
+                        .iter()
+                        .map(|archetype_entity| archetype_entity.entity()),
+                );
+                continue;
+            }
+            for archetype_entity in archetype.entities() {
+                let entity = archetype_entity.entity();
+                let components = archetype_components
+                    .iter()
+                    .filter_map(|component| {
+                        let value = component.reflect.reflect(world, entity)?;
+                        Some(ReflectedValue::new(component.type_id, value))
+                    })
+                    .collect();
+                entities.push(EntitySnapshot { entity, components });
+            }
+        }
+        entities.sort_unstable_by_key(|snapshot| snapshot.entity);
+        uncaptured.sort_unstable();
+
+        let resources = captured_resources(registry, &filter)
+            .filter_map(|(type_id, reflect)| {
+                Some(ReflectedValue::new(type_id, reflect.reflect(world)?))
+            })
+            .collect();
+
+        Self {
+            filter,
+            entities,
+            uncaptured,
+            resources,
+        }
+    }
+
+    /// The filter the snapshot was captured with.
+    pub fn filter(&self) -> &SnapshotFilter {
+        &self.filter
+    }
+
+    /// The captured entities, ordered by [`Entity`].
+    pub fn entities(&self) -> &[EntitySnapshot] {
+        &self.entities
+    }
+
+    /// Returns the captured components of the given entity.
+    pub fn entity(&self, entity: Entity) -> Option<&EntitySnapshot> {
+        self.entities
+            .binary_search_by_key(&entity, |snapshot| snapshot.entity)
+            .ok()
+            .map(|index| &self.entities[index])
+    }
+
+    /// The captured resources, with their [`TypeId`].
+    pub fn resources(&self) -> impl Iterator<Item = (TypeId, &dyn Reflect)> {
+        self.resources
+            .iter()
+            .map(|value| (value.type_id, &*value.value))
+    }
+
+    /// Rolls `world` back to the snapshot.
+    ///
+    /// For the types captured by the snapshot:
+    /// - entities spawned since the snapshot was captured are despawned, and entities that existed
+    ///   without captured components only lose the captured components they gained,
+    /// - entities of the snapshot that were despawned are spawned again with the same [`Entity`]
+    ///   id,
+    /// - components and resources are set to their captured value, inserted if they were removed,
+    ///   and removed if they were inserted.
+    ///
+    /// Components and resources that are already equal to their captured value according to
+    /// [`Reflect::reflect_partial_eq`] are left untouched, so only the restored values are marked
+    /// as changed or added at the current change tick of the world.
+    ///
+    /// If the id of a despawned entity was reused by an entity that is not part of the snapshot,
+    /// the entity is spawned with a new id, and the restored components referring to entities of
+    /// the snapshot are updated with their [`ReflectMapEntities`] type data.
+    pub fn restore(&self, world: &mut World, registry: &TypeRegistry) -> Result<(), SnapshotError> {
+        let components = captured_components(world, registry, &self.filter);
+
+        let mut despawned = Vec::new();
+        let mut uncaptured = Vec::new();
+        for archetype in world.archetypes().iter() {
+            if !components
+                .iter()
+                .any(|component| archetype.contains(component.component_id))
+            {
+                continue;
+            }
+            for archetype_entity in archetype.entities() {
+                let entity = archetype_entity.entity();
+                if self.entity(entity).is_some() {
+                    continue;
+                }
+                if self.uncaptured.binary_search(&entity).is_ok() {
+                    uncaptured.push(entity);
+                } else {
+                    despawned.push(entity);
+                }
+            }
+        }
+        for entity in despawned {
+            world.despawn(entity);
+        }
+        for entity in uncaptured {
+            for component in &components {
+                if world.entity(entity).contains_id(component.component_id) {
+                    component.reflect.remove(world, entity);
+                }
+            }
+        }
+
+        let mut entity_map = EntityMap::default();
+        let mut remapped = false;
+        for snapshot in &self.entities {
+            let entity = match world.get_or_spawn(snapshot.entity) {
+                Some(entity) => entity.id(),
+                None => {
+                    remapped = true;
+                    world.spawn_empty().id()
+                }
+            };
+            entity_map.insert(snapshot.entity, entity);
+        }
+
+        for snapshot in &self.entities {
+            let entity = entity_map.get(snapshot.entity).unwrap();
+            for value in &snapshot.components {
+                let registration = registry
+                    .get(value.type_id)
+                    .ok_or(SnapshotError::UnregisteredType(value.type_id))?;
+                let reflect = registration
+                    .data::<ReflectComponent>()
+                    .ok_or(SnapshotError::UnregisteredType(value.type_id))?;
+                let mapped;
+                let value = match registration.data::<ReflectMapEntities>() {
+                    Some(map_entities) if remapped => {
+                        mapped = map_entities
+                            .map_value(world, &*value.value, &entity_map)
+                            .map_err(SnapshotError::MapEntities)?;
+                        &*mapped
+                    }
+                    _ => &*value.value,
+                };
+                if !matches!(
+                    reflect
+                        .reflect(world, entity)
+                        .and_then(|current| current.reflect_partial_eq(value)),
+                    Some(true)
+                ) {
+                    reflect.apply_or_insert(world, entity, value);
+                }
+            }
+            for component in &components {
+                if snapshot.get(component.type_id).is_none()
+                    && world.entity(entity).contains_id(component.component_id)
+                {
+                    component.reflect.remove(world, entity);
+                }
+            }
+        }
+
+        for (type_id, reflect) in captured_resources(registry, &self.filter) {
+            match self.resources.iter().find(|value| value.type_id == type_id) {
+                Some(value) => {
+                    if !matches!(
+                        reflect
+                            .reflect(world)
+                            .and_then(|current| current.reflect_partial_eq(&*value.value)),
+                        Some(true)
+                    ) {
+                        reflect.apply_or_insert(world, &*value.value);
+                    }
+                }
+                None => reflect.remove(world),
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the changes from this snapshot to a `newer` one.
+    ///
+    /// Values are compared with [`Reflect::reflect_partial_eq`], and values that can't be
+    /// compared are considered changed.
+    pub fn diff(&self, newer: &WorldSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+        let mut old_entities = self.entities.iter().peekable();
+        let mut new_entities = newer.entities.iter().peekable();
+        loop {
+            let ordering = match (old_entities.peek(), new_entities.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(old), Some(new)) => old.entity.cmp(&new.entity),
+            };
+            match ordering {
+                Ordering::Less => {
+                    let old = old_entities.next().unwrap();
+                    diff.despawned.push(old.entity);
+                }
+                Ordering::Greater => {
+                    let new = new_entities.next().unwrap();
+                    diff.spawned.push(new.entity);
+                    diff.components.extend(new.components.iter().map(|value| {
+                        ComponentDiff::new(new.entity, value.type_id, ChangeKind::Added)
+                    }));
+                }
+                Ordering::Equal => {
+                    let old = old_entities.next().unwrap();
+                    let new = new_entities.next().unwrap();
+                    diff.components.extend(
+                        diff_values(&old.components, &new.components)
+                            .map(|(type_id, kind)| ComponentDiff::new(new.entity, type_id, kind)),
+                    );
+                }
+            }
+        }
+        diff.resources = diff_values(&self.resources, &newer.resources).collect();
+        diff
+    }
+}
+
+/// How a value changed between two [`WorldSnapshot`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The value is only in the newer snapshot.
+    Added,
+    /// The value is in both snapshots, with different values.
+    Changed,
+    /// The value is only in the older snapshot.
+    Removed,
+}
+
+/// A component that changed between two [`WorldSnapshot`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentDiff {
+    /// The entity of the component.
+    pub entity: Entity,
+    /// The type of the component.
+    pub type_id: TypeId,
+    /// How the component changed.
+    pub kind: ChangeKind,
+}
+
+impl ComponentDiff {
+    fn new(entity: Entity, type_id: TypeId, kind: ChangeKind) -> Self {
+        Self {
+            entity,
+            type_id,
+            kind,
+        }
+    }
+}
+
+/// The changes between two [`WorldSnapshot`]s, computed by [`WorldSnapshot::diff`].
+///
+/// The values of the changes can be read from the newer snapshot.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotDiff {
+    spawned: Vec<Entity>,
+    despawned: Vec<Entity>,
+    components: Vec<ComponentDiff>,
+    resources: Vec<(TypeId, ChangeKind)>,
+}
+
+impl SnapshotDiff {
+    /// The entities that are only in the newer snapshot. Their components are listed as
+    /// [`Added`](ChangeKind::Added) in [`components`](Self::components).
+    pub fn spawned(&self) -> &[Entity] {
+        &self.spawned
+    }
+
+    /// The entities that are only in the older snapshot.
+    pub fn despawned(&self) -> &[Entity] {
+        &self.despawned
+    }
+
+    /// The components that changed, ordered by [`Entity`].
+    pub fn components(&self) -> &[ComponentDiff] {
+        &self.components
+    }
+
+    /// The resources that changed.
+    pub fn resources(&self) -> &[(TypeId, ChangeKind)] {
+        &self.resources
+    }
+
+    /// Returns `true` if the snapshots are equal.
+    pub fn is_empty(&self) -> bool {
+        self.despawned.is_empty() && self.components.is_empty() && self.resources.is_empty()
+    }
+}
+
+/// An error restoring a [`WorldSnapshot`].
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The type of a captured value is not registered with [`ReflectComponent`] in the registry.
+    UnregisteredType(TypeId),
+    /// A restored entity got a new id, and the captured components referring to it couldn't be
+    /// updated.
+    MapEntities(MapEntitiesError),
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::UnregisteredType(type_id) => write!(
+                f,
+                "the captured type {:?} is not registered with ReflectComponent",
+                type_id
+            ),
+            SnapshotError::MapEntities(error) => {
+                write!(
+                    f,
+                    "failed to map the entities of a restored entity: {}",
+                    error
+                )
+            }
+        }
+    }
+}
+
+struct CapturedComponent<'a> {
+    type_id: TypeId,
+    component_id: ComponentId,
+    reflect: &'a ReflectComponent,
+}
+
+fn captured_components<'a>(
+    world: &World,
+    registry: &'a TypeRegistry,
+    filter: &SnapshotFilter,
+) -> Vec<CapturedComponent<'a>> {
+    registry
+        .iter()
+        .filter(|registration| filter.is_allowed(registration.type_id()))
+        .filter_map(|registration| {
+            Some(CapturedComponent {
+                type_id: registration.type_id(),
+                // Components that were never initialized are on no entity.
+                component_id: world.components().get_id(registration.type_id())?,
+                reflect: registration.data::<ReflectComponent>()?,
+            })
+        })
+        .collect()
+}
+
+fn captured_resources<'a>(
+    registry: &'a TypeRegistry,
+    filter: &'a SnapshotFilter,
+) -> impl Iterator<Item = (TypeId, &'a ReflectResource)> {
+    registry
+        .iter()
+        .filter(|registration| filter.is_allowed(registration.type_id()))
+        .filter_map(|registration| {
+            Some((
+                registration.type_id(),
+                registration.data::<ReflectResource>()?,
+            ))
+        })
+}
+
+fn diff_values<'a>(
+    old: &'a [ReflectedValue],
+    new: &'a [ReflectedValue],
+) -> impl Iterator<Item = (TypeId, ChangeKind)> + 'a {
+    let removed = old
+        .iter()
+        .filter(|old| !new.iter().any(|new| new.type_id == old.type_id))
+        .map(|old| (old.type_id, ChangeKind::Removed));
+    let added_or_changed =
+        new.iter().filter_map(
+            |new| match old.iter().find(|old| old.type_id == new.type_id) {
+                None => Some((new.type_id, ChangeKind::Added)),
+                Some(old) => match old.value.reflect_partial_eq(&*new.value) {
+                    Some(true) => None,
+                    _ => Some((new.type_id, ChangeKind::Changed)),
+                },
+            },
+        );
+    removed.chain(added_or_changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{self as bevy_ecs, entity::MapEntities, prelude::*};
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Position(f32);
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Resource, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Resource)]
+    struct Score(u32);
+
+    #[derive(Component, Reflect, Debug, PartialEq)]
+    #[reflect(Component, MapEntities)]
+    struct Target(Entity);
+
+    impl Default for Target {
+        fn default() -> Self {
+            Self(Entity::from_raw(u32::MAX))
+        }
+    }
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.0 = entity_map.get(self.0)?;
+            Ok(())
+        }
+    }
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Position>();
+        registry.register::<Health>();
+        registry.register::<Score>();
+        registry.register::<Target>();
+        registry
+    }
+
+    #[test]
+    fn restore_rolls_back_the_world() {
+        let registry = registry();
+        let mut world = World::new();
+        world.insert_resource(Score(1));
+        let a = world.spawn((Position(1.0), Health(10))).id();
+        let b = world.spawn(Position(2.0)).id();
+        let untouched = world.spawn(Health(3)).id();
+        let snapshot = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+        assert_eq!(snapshot.entities().len(), 3);
+
+        world.get_mut::<Position>(a).unwrap().0 = 5.0;
+        world.entity_mut(a).remove::<Health>();
+        world.entity_mut(b).insert(Health(1));
+        world.despawn(b);
+        let spawned = world.spawn(Position(3.0)).id();
+        world.resource_mut::<Score>().0 = 2;
+
+        world.clear_trackers();
+        snapshot.restore(&mut world, &registry).unwrap();
+        assert_eq!(world.get::<Position>(a), Some(&Position(1.0)));
+        assert_eq!(world.get::<Health>(a), Some(&Health(10)));
+        assert_eq!(world.get::<Position>(b), Some(&Position(2.0)));
+        assert_eq!(world.get::<Health>(b), None);
+        assert!(world.get_entity(spawned).is_none());
+        assert_eq!(world.resource::<Score>(), &Score(1));
+
+        // Only the restored values are marked as changed.
+        let mut changed = world.query_filtered::<Entity, Changed<Position>>();
+        let mut changed: Vec<_> = changed.iter(&world).collect();
+        changed.sort();
+        assert_eq!(changed, [a, b]);
+        let mut health = world.query_filtered::<Entity, Changed<Health>>();
+        assert_eq!(health.iter(&world).collect::<Vec<_>>(), [a]);
+        assert_eq!(world.get::<Health>(untouched), Some(&Health(3)));
+    }
+
+    #[test]
+    fn filtered_snapshots_only_restore_their_types() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn((Position(1.0), Health(10))).id();
+        let snapshot = WorldSnapshot::capture(
+            &world,
+            &registry,
+            SnapshotFilter::default().allow::<Position>(),
+        );
+        assert_eq!(snapshot.entity(entity).unwrap().components().count(), 1);
+
+        world.get_mut::<Position>(entity).unwrap().0 = 5.0;
+        world.get_mut::<Health>(entity).unwrap().0 = 5;
+        // Entities without captured components are not despawned.
+        let other = world.spawn(Health(1)).id();
+        snapshot.restore(&mut world, &registry).unwrap();
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1.0)));
+        assert_eq!(world.get::<Health>(entity), Some(&Health(5)));
+        assert!(world.get_entity(other).is_some());
+    }
+
+    #[test]
+    fn restore_keeps_entities_that_existed_without_captured_components() {
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn(Health(10)).id();
+        let snapshot = WorldSnapshot::capture(
+            &world,
+            &registry,
+            SnapshotFilter::default().allow::<Position>(),
+        );
+        assert!(snapshot.entity(entity).is_none());
+
+        world.entity_mut(entity).insert(Position(1.0));
+        let spawned = world.spawn((Position(2.0), Health(1))).id();
+        snapshot.restore(&mut world, &registry).unwrap();
+        assert_eq!(world.get::<Health>(entity), Some(&Health(10)));
+        assert_eq!(world.get::<Position>(entity), None);
+        assert!(world.get_entity(spawned).is_none());
+    }
+
+    #[test]
+    fn restore_remaps_reused_entity_ids() {
+        #[derive(Component)]
+        struct NotCaptured;
+
+        let registry = registry();
+        let mut world = World::new();
+        let entity = world.spawn(Position(1.0)).id();
+        let snapshot = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+
+        world.despawn(entity);
+        let reused = world.spawn(NotCaptured).id();
+        assert_eq!(reused.index(), entity.index());
+        snapshot.restore(&mut world, &registry).unwrap();
+        assert!(world.get_entity(reused).is_some());
+        let mut positions = world.query::<(Entity, &Position)>();
+        let (restored, position) = positions.single(&world);
+        assert_ne!(restored, entity);
+        assert_eq!(position, &Position(1.0));
+    }
+
+    #[test]
+    fn restore_maps_the_restored_references() {
+        #[derive(Component)]
+        struct NotCaptured;
+
+        let registry = registry();
+        let mut world = World::new();
+        let target = world.spawn(Position(1.0)).id();
+        let follower = world.spawn(Target(target)).id();
+        let snapshot = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+
+        world.despawn(target);
+        let reused = world.spawn(NotCaptured).id();
+        assert_eq!(reused.index(), target.index());
+        snapshot.restore(&mut world, &registry).unwrap();
+
+        let mut positions = world.query_filtered::<Entity, With<Position>>();
+        let restored = positions.single(&world);
+        assert_ne!(restored, target);
+        assert_eq!(world.get::<Target>(follower), Some(&Target(restored)));
+    }
+
+    #[test]
+    fn diff_lists_changes() {
+        let registry = registry();
+        let mut world = World::new();
+        let a = world.spawn((Position(1.0), Health(10))).id();
+        let b = world.spawn(Position(2.0)).id();
+        let c = world.spawn(Position(3.0)).id();
+        let old = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+        assert!(old
+            .diff(&WorldSnapshot::capture(
+                &world,
+                &registry,
+                SnapshotFilter::default()
+            ))
+            .is_empty());
+
+        world.get_mut::<Position>(a).unwrap().0 = 5.0;
+        world.entity_mut(a).remove::<Health>();
+        world.despawn(b);
+        world.entity_mut(c).insert(Health(3));
+        let d = world.spawn(Health(4)).id();
+        world.insert_resource(Score(1));
+        let new = WorldSnapshot::capture(&world, &registry, SnapshotFilter::default());
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.despawned(), [b]);
+        assert_eq!(diff.spawned(), [d]);
+        let position = TypeId::of::<Position>();
+        let health = TypeId::of::<Health>();
+        let mut components = diff.components().to_vec();
+        components.sort_by_key(|diff| diff.entity);
+        let mut expected = vec![
+            ComponentDiff::new(a, health, ChangeKind::Removed),
+            ComponentDiff::new(a, position, ChangeKind::Changed),
+            ComponentDiff::new(c, health, ChangeKind::Added),
+            ComponentDiff::new(d, health, ChangeKind::Added),
+        ];
+        expected.sort_by_key(|diff| diff.entity);
+        assert_eq!(components, expected);
+        assert_eq!(
+            diff.resources(),
+            [(TypeId::of::<Score>(), ChangeKind::Added)]
+        );
+    }
+}
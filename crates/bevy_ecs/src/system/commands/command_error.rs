@@ -0,0 +1,99 @@
+use std::fmt;
+
+use bevy_utils::tracing::warn;
+
+use crate::{self as bevy_ecs, entity::Entity, system::Resource, world::World};
+
+/// An error applying a fallible [`Command`](super::Command), like
+/// [`EntityCommands::try_insert`](super::EntityCommands::try_insert).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The entity the command targets doesn't exist, usually because it was despawned by a
+    /// command applied earlier.
+    NoSuchEntity {
+        /// The type name of the command.
+        command: &'static str,
+        /// The entity the command targets.
+        entity: Entity,
+    },
+}
+
+impl std::error::Error for CommandError {}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::NoSuchEntity { command, entity } => write!(
+                f,
+                "error[B0003]: Could not apply `{}` to entity {:?} because it doesn't exist in this World.",
+                command, entity
+            ),
+        }
+    }
+}
+
+/// What to do when a fallible [`Command`](super::Command) fails.
+///
+/// The handler of a command is the one set on its [`Commands`](super::Commands) with
+/// [`Commands::set_error_handler`](super::Commands::set_error_handler), or else the one in the
+/// [`DefaultCommandErrorHandler`] resource, or else [`Warn`](Self::Warn).
+#[derive(Clone, Copy, Debug, Default)]
+pub enum CommandErrorHandler {
+    /// Panics with the error.
+    Panic,
+    /// Logs the error as a warning.
+    #[default]
+    Warn,
+    /// Ignores the error.
+    Ignore,
+    /// Calls the function with the error.
+    Custom(fn(&mut World, CommandError)),
+}
+
+impl CommandErrorHandler {
+    /// Handles `error` according to this policy.
+    pub fn handle(self, world: &mut World, error: CommandError) {
+        match self {
+            CommandErrorHandler::Panic => panic!("{}", error),
+            CommandErrorHandler::Warn => warn!("{}", error),
+            CommandErrorHandler::Ignore => {}
+            CommandErrorHandler::Custom(handler) => handler(world, error),
+        }
+    }
+}
+
+/// The [`CommandErrorHandler`] of the fallible commands of a [`World`] that don't set one on their
+/// [`Commands`](super::Commands).
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::system::{CommandErrorHandler, DefaultCommandErrorHandler};
+/// let mut world = World::new();
+/// // Despawn races crash debug builds, and are logged in release builds.
+/// world.insert_resource(DefaultCommandErrorHandler(if cfg!(debug_assertions) {
+///     CommandErrorHandler::Panic
+/// } else {
+///     CommandErrorHandler::Warn
+/// }));
+/// ```
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct DefaultCommandErrorHandler(pub CommandErrorHandler);
+
+/// Handles `error` with `handler`, or the [`DefaultCommandErrorHandler`] of the world.
+pub(crate) fn handle_command_error(
+    handler: Option<CommandErrorHandler>,
+    world: &mut World,
+    error: CommandError,
+) {
+    let handler = match handler {
+        Some(handler) => handler,
+        None => {
+            world
+                .get_resource::<DefaultCommandErrorHandler>()
+                .copied()
+                .unwrap_or_default()
+                .0
+        }
+    };
+    handler.handle(world, error);
+}
@@ -76,10 +76,26 @@ impl CommandQueue {
         }
     }
 
+    /// Returns `true` if no commands are queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.metas.is_empty()
+    }
+
     /// Execute the queued [`Command`]s in the world.
     /// This clears the queue.
+    ///
+    /// The commands queued by [component hooks](crate::component::ComponentHooks) and the
+    /// observers triggered meanwhile are applied afterwards, see [`World::flush_commands`].
     #[inline]
     pub fn apply(&mut self, world: &mut World) {
+        self.apply_queued(world);
+        world.flush_commands();
+    }
+
+    /// Execute the queued [`Command`]s in the world, without flushing the world's own queue.
+    #[inline]
+    pub(crate) fn apply_queued(&mut self, world: &mut World) {
         // flush the previously queued entities
         world.flush();
 
@@ -1,3 +1,4 @@
+mod command_error;
 mod command_queue;
 mod parallel_scope;
 
@@ -7,11 +8,12 @@ use crate::{
     world::{FromWorld, World},
 };
 use bevy_utils::tracing::{error, info};
+pub use command_error::*;
 pub use command_queue::CommandQueue;
 pub use parallel_scope::*;
 use std::marker::PhantomData;
 
-use super::Resource;
+use super::{Resource, RunSystem, SystemId};
 
 /// A [`World`] mutation.
 ///
@@ -98,6 +100,7 @@ pub trait Command: Send + 'static {
 pub struct Commands<'w, 's> {
     queue: &'s mut CommandQueue,
     entities: &'w Entities,
+    error_handler: Option<CommandErrorHandler>,
 }
 
 impl<'w, 's> Commands<'w, 's> {
@@ -110,6 +113,7 @@ impl<'w, 's> Commands<'w, 's> {
         Self {
             queue,
             entities: world.entities(),
+            error_handler: None,
         }
     }
 
@@ -119,7 +123,41 @@ impl<'w, 's> Commands<'w, 's> {
     ///
     /// [system parameter]: crate::system::SystemParam
     pub fn new_from_entities(queue: &'s mut CommandQueue, entities: &'w Entities) -> Self {
-        Self { queue, entities }
+        Self {
+            queue,
+            entities,
+            error_handler: None,
+        }
+    }
+
+    /// Sets the [`CommandErrorHandler`] of the fallible commands pushed afterwards by this
+    /// `Commands`, like [`EntityCommands::try_insert`], instead of the
+    /// [`DefaultCommandErrorHandler`] of the world.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::system::CommandErrorHandler;
+    /// # #[derive(Component)]
+    /// # struct Burning;
+    /// fn ignite_system(mut commands: Commands, query: Query<Entity>) {
+    ///     // Entities despawned earlier in the stage don't need to burn.
+    ///     commands.set_error_handler(CommandErrorHandler::Ignore);
+    ///     for entity in &query {
+    ///         commands.entity(entity).try_insert(Burning);
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(ignite_system);
+    /// ```
+    pub fn set_error_handler(&mut self, error_handler: CommandErrorHandler) -> &mut Self {
+        self.error_handler = Some(error_handler);
+        self
+    }
+
+    /// Returns the [`CommandErrorHandler`] set with [`set_error_handler`](Self::set_error_handler).
+    pub fn error_handler(&self) -> Option<CommandErrorHandler> {
+        self.error_handler
     }
 
     /// Pushes a [`Command`] to the queue for creating a new empty [`Entity`],
@@ -499,6 +537,26 @@ impl<'w, 's> Commands<'w, 's> {
         });
     }
 
+    /// Pushes a [`Command`] to the queue for running a system registered with
+    /// [`World::register_system`](crate::world::World::register_system).
+    ///
+    /// The system runs when the commands are applied, and its own commands are applied right
+    /// after it. Nothing happens if the system has been removed in the meantime.
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, system::SystemId};
+    /// #[derive(Resource)]
+    /// struct ResetScore(SystemId);
+    ///
+    /// fn on_click(mut commands: Commands, reset_score: Res<ResetScore>) {
+    ///     commands.run_system(reset_score.0);
+    /// }
+    /// # bevy_ecs::system::assert_is_system(on_click);
+    /// ```
+    pub fn run_system(&mut self, id: SystemId) {
+        self.queue.push(RunSystem { system_id: id });
+    }
+
     /// Pushes a generic [`Command`] to the command queue.
     ///
     /// `command` can be a built-in command, custom struct that implements [`Command`] or a closure
@@ -688,6 +746,53 @@ impl<'w, 's, 'a> EntityCommands<'w, 's, 'a> {
         self.remove::<T>()
     }
 
+    /// Adds a [`Bundle`] of components to the entity, if it exists when the command is applied.
+    ///
+    /// Unlike [`insert`](Self::insert), this doesn't panic if the entity doesn't exist, and
+    /// reports a [`CommandError`] to the [`CommandErrorHandler`] of the command instead. See
+    /// [`Commands::set_error_handler`].
+    pub fn try_insert(&mut self, bundle: impl Bundle) -> &mut Self {
+        let error_handler = self.commands.error_handler;
+        self.commands.add(TryInsert {
+            entity: self.entity,
+            bundle,
+            error_handler,
+        });
+        self
+    }
+
+    /// Removes a [`Bundle`] of components from the entity, if it exists when the command is
+    /// applied.
+    ///
+    /// Unlike [`remove`](Self::remove), this reports a [`CommandError`] to the
+    /// [`CommandErrorHandler`] of the command if the entity doesn't exist. See
+    /// [`Commands::set_error_handler`].
+    pub fn try_remove<T>(&mut self) -> &mut Self
+    where
+        T: Bundle,
+    {
+        let error_handler = self.commands.error_handler;
+        self.commands.add(TryRemove::<T> {
+            entity: self.entity,
+            error_handler,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Despawns the entity, if it exists when the command is applied.
+    ///
+    /// Unlike [`despawn`](Self::despawn), this reports a [`CommandError`] to the
+    /// [`CommandErrorHandler`] of the command if the entity doesn't exist. See
+    /// [`Commands::set_error_handler`].
+    pub fn try_despawn(&mut self) {
+        let error_handler = self.commands.error_handler;
+        self.commands.add(TryDespawn {
+            entity: self.entity,
+            error_handler,
+        });
+    }
+
     /// Despawns the entity.
     ///
     /// See [`World::despawn`] for more details.
@@ -861,6 +966,82 @@ where
     }
 }
 
+pub struct TryInsert<T> {
+    pub entity: Entity,
+    pub bundle: T,
+    pub error_handler: Option<CommandErrorHandler>,
+}
+
+impl<T> Command for TryInsert<T>
+where
+    T: Bundle + 'static,
+{
+    fn write(self, world: &mut World) {
+        if let Some(mut entity) = world.get_entity_mut(self.entity) {
+            entity.insert(self.bundle);
+        } else {
+            handle_command_error(
+                self.error_handler,
+                world,
+                CommandError::NoSuchEntity {
+                    command: std::any::type_name::<Self>(),
+                    entity: self.entity,
+                },
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TryRemove<T> {
+    pub entity: Entity,
+    pub error_handler: Option<CommandErrorHandler>,
+    pub phantom: PhantomData<T>,
+}
+
+impl<T> Command for TryRemove<T>
+where
+    T: Bundle,
+{
+    fn write(self, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.remove_intersection::<T>();
+        } else {
+            handle_command_error(
+                self.error_handler,
+                world,
+                CommandError::NoSuchEntity {
+                    command: std::any::type_name::<Self>(),
+                    entity: self.entity,
+                },
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TryDespawn {
+    pub entity: Entity,
+    pub error_handler: Option<CommandErrorHandler>,
+}
+
+impl Command for TryDespawn {
+    fn write(self, world: &mut World) {
+        if world.get_entity(self.entity).is_some() {
+            world.despawn(self.entity);
+        } else {
+            handle_command_error(
+                self.error_handler,
+                world,
+                CommandError::NoSuchEntity {
+                    command: std::any::type_name::<Self>(),
+                    entity: self.entity,
+                },
+            );
+        }
+    }
+}
+
 pub struct InitResource<R: Resource + FromWorld> {
     _phantom: PhantomData<R>,
 }
@@ -913,7 +1094,10 @@ mod tests {
     use crate::{
         self as bevy_ecs,
         component::Component,
-        system::{CommandQueue, Commands, Resource},
+        system::{
+            CommandError, CommandErrorHandler, CommandQueue, Commands, DefaultCommandErrorHandler,
+            Resource,
+        },
         world::World,
     };
     use std::sync::{
@@ -1067,4 +1251,58 @@ mod tests {
         assert!(!world.contains_resource::<W<i32>>());
         assert!(world.contains_resource::<W<f64>>());
     }
+
+    #[test]
+    fn fallible_commands_report_errors() {
+        #[derive(Resource, Default)]
+        struct Errors(Vec<CommandError>);
+
+        fn record(world: &mut World, error: CommandError) {
+            world.resource_mut::<Errors>().0.push(error);
+        }
+
+        let mut world = World::default();
+        world.init_resource::<Errors>();
+        world.insert_resource(DefaultCommandErrorHandler(CommandErrorHandler::Custom(
+            record,
+        )));
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn(W(1u32)).id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(entity).despawn();
+            commands.entity(entity).try_insert(W(2u32));
+            commands.entity(entity).try_remove::<W<u32>>();
+            commands.entity(entity).try_despawn();
+            // The handler of the `Commands` replaces the default one.
+            commands.set_error_handler(CommandErrorHandler::Ignore);
+            commands.entity(entity).try_insert(W(3u32));
+        }
+        queue.apply(&mut world);
+
+        let errors = &world.resource::<Errors>().0;
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().all(
+            |error| matches!(error, CommandError::NoSuchEntity { entity: e, .. } if *e == entity)
+        ));
+        assert!(matches!(
+            errors[0],
+            CommandError::NoSuchEntity { command, .. } if command.contains("TryInsert")
+        ));
+    }
+
+    #[test]
+    #[should_panic = "error[B0003]"]
+    fn fallible_commands_can_panic() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn_empty().id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.set_error_handler(CommandErrorHandler::Panic);
+            commands.entity(entity).despawn();
+            commands.entity(entity).try_insert(W(1u32));
+        }
+        queue.apply(&mut world);
+    }
 }
@@ -124,6 +124,8 @@ where
     fn apply_buffers(&mut self, world: &mut World) {
         let param_state = self.param_state.as_mut().expect(PARAM_MESSAGE);
         param_state.apply(world);
+        // Run the observers triggered by the changes the system made directly to the world.
+        world.flush_commands();
     }
 
     #[inline]
@@ -2,12 +2,13 @@ use crate::{
     component::Component,
     entity::Entity,
     query::{
-        QueryCombinationIter, QueryEntityError, QueryIter, QueryManyIter, QuerySingleError,
-        QueryState, ROQueryItem, ReadOnlyWorldQuery, WorldQuery,
+        DynamicQueryItem, DynamicQueryIter, DynamicQueryState, QueryBuilder, QueryCombinationIter,
+        QueryEntityError, QueryIter, QueryManyIter, QuerySingleError, QueryState, ROQueryItem,
+        ReadOnlyWorldQuery, WorldQuery,
     },
     world::{Mut, World},
 };
-use std::{any::TypeId, borrow::Borrow, fmt::Debug};
+use std::{any::TypeId, borrow::Borrow, fmt::Debug, marker::PhantomData};
 
 /// [System parameter] that provides selective access to the [`Component`] data stored in a [`World`].
 ///
@@ -1539,3 +1540,124 @@ impl<'w, 's, Q: ReadOnlyWorldQuery, F: ReadOnlyWorldQuery> Query<'w, 's, Q, F> {
         }
     }
 }
+
+/// Declares the terms of a [`DynamicQuery`] system parameter.
+///
+/// [`terms`](Self::terms) is called once, when the system is initialized, with a builder giving
+/// access to the [`World`] to look up the [`ComponentId`](crate::component::ComponentId)s of the
+/// terms, for example from a resource filled by a scripting runtime.
+pub trait DynamicQueryTerms: Send + Sync + 'static {
+    /// Adds the terms of the query to `builder`.
+    fn terms(builder: &mut QueryBuilder);
+}
+
+/// [System parameter] running a query built at runtime, whose terms are declared by `T`.
+///
+/// Its access is registered with the system like the access of a [`Query`], so it conflicts with
+/// other parameters and systems accessing the same components, and can run in parallel with
+/// the others.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_ecs::query::QueryBuilder;
+/// # use bevy_ecs::system::{DynamicQuery, DynamicQueryTerms};
+/// # use bevy_ecs::component::ComponentId;
+/// #[derive(Resource)]
+/// struct ScriptedComponent(ComponentId);
+///
+/// struct ScriptedTerms;
+///
+/// impl DynamicQueryTerms for ScriptedTerms {
+///     fn terms(builder: &mut QueryBuilder) {
+///         let id = builder.world().resource::<ScriptedComponent>().0;
+///         builder.read(id);
+///     }
+/// }
+///
+/// fn scripted_system(query: DynamicQuery<ScriptedTerms>) {
+///     for item in query.iter() {
+///         let _ptr = item.components()[0].as_ptr();
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(scripted_system);
+/// ```
+///
+/// [System parameter]: crate::system::SystemParam
+pub struct DynamicQuery<'world, 'state, T: DynamicQueryTerms> {
+    pub(crate) world: &'world World,
+    pub(crate) state: &'state DynamicQueryState,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+    pub(crate) marker: PhantomData<fn() -> T>,
+}
+
+impl<'w, 's, T: DynamicQueryTerms> std::fmt::Debug for DynamicQuery<'w, 's, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicQuery")
+            .field("state", self.state)
+            .field("last_change_tick", &self.last_change_tick)
+            .field("change_tick", &self.change_tick)
+            .finish()
+    }
+}
+
+impl<'w, 's, T: DynamicQueryTerms> DynamicQuery<'w, 's, T> {
+    /// The state of the query.
+    pub fn state(&self) -> &'s DynamicQueryState {
+        self.state
+    }
+
+    /// Returns an [`Iterator`] over the query items, with read-only access to all the fetched
+    /// components.
+    pub fn iter(&self) -> DynamicQueryIter<'_, 's> {
+        DynamicQueryIter::new(
+            self.world,
+            self.state,
+            false,
+            self.last_change_tick,
+            self.change_tick,
+        )
+    }
+
+    /// Returns an [`Iterator`] over the query items, with mutable access to the components of
+    /// [`write`](QueryBuilder::write) terms.
+    pub fn iter_mut(&mut self) -> DynamicQueryIter<'_, 's> {
+        // SAFETY: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state
+                .iter_unchecked_manual(self.world, self.last_change_tick, self.change_tick)
+        }
+    }
+
+    /// Returns the query item for the given [`Entity`], with read-only access to all the fetched
+    /// components.
+    pub fn get(&self, entity: Entity) -> Result<DynamicQueryItem<'_>, QueryEntityError> {
+        // SAFETY: the components are only read
+        unsafe {
+            self.state.get_unchecked_manual(
+                self.world,
+                entity,
+                false,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns the query item for the given [`Entity`], with mutable access to the components of
+    /// [`write`](QueryBuilder::write) terms.
+    pub fn get_mut(&mut self, entity: Entity) -> Result<DynamicQueryItem<'_>, QueryEntityError> {
+        // SAFETY: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.get_unchecked_manual(
+                self.world,
+                entity,
+                true,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+}
@@ -6,9 +6,10 @@ use crate::{
     component::{Component, ComponentId, ComponentTicks, Components},
     entity::{Entities, Entity},
     query::{
-        Access, FilteredAccess, FilteredAccessSet, QueryState, ReadOnlyWorldQuery, WorldQuery,
+        Access, DynamicQueryState, FilteredAccess, FilteredAccessSet, QueryBuilder, QueryState,
+        ReadOnlyWorldQuery, WorldQuery,
     },
-    system::{CommandQueue, Commands, Query, SystemMeta},
+    system::{CommandQueue, Commands, DynamicQuery, DynamicQueryTerms, Query, SystemMeta},
     world::{FromWorld, World},
 };
 pub use bevy_ecs_macros::Resource;
@@ -156,8 +157,11 @@ unsafe impl<Q: WorldQuery + 'static, F: ReadOnlyWorldQuery + 'static> SystemPara
         let state = QueryState::new(world);
         assert_component_access_compatibility(
             &system_meta.name,
-            std::any::type_name::<Q>(),
-            std::any::type_name::<F>(),
+            &format!(
+                "Query<{}, {}>",
+                std::any::type_name::<Q>(),
+                std::any::type_name::<F>()
+            ),
             &system_meta.component_access_set,
             &state.component_access,
             world,
@@ -197,8 +201,7 @@ impl<'w, 's, Q: WorldQuery + 'static, F: ReadOnlyWorldQuery + 'static> SystemPar
 
 fn assert_component_access_compatibility(
     system_name: &str,
-    query_type: &'static str,
-    filter_type: &'static str,
+    query: &str,
     system_access: &FilteredAccessSet<ComponentId>,
     current: &FilteredAccess<ComponentId>,
     world: &World,
@@ -212,8 +215,73 @@ fn assert_component_access_compatibility(
         .map(|component_id| world.components.get_info(component_id).unwrap().name())
         .collect::<Vec<&str>>();
     let accesses = conflicting_components.join(", ");
-    panic!("error[B0001]: Query<{}, {}> in system {} accesses component(s) {} in a way that conflicts with a previous system parameter. Consider using `Without<T>` to create disjoint Queries or merging conflicting Queries into a `ParamSet`.",
-           query_type, filter_type, system_name, accesses);
+    panic!("error[B0001]: {} in system {} accesses component(s) {} in a way that conflicts with a previous system parameter. Consider using `Without<T>` to create disjoint Queries or merging conflicting Queries into a `ParamSet`.",
+           query, system_name, accesses);
+}
+
+impl<'w, 's, T: DynamicQueryTerms> SystemParam for DynamicQuery<'w, 's, T> {
+    type Fetch = DynamicQueryParamState<T>;
+}
+
+/// The [`SystemParamState`] of [`DynamicQuery<T>`].
+pub struct DynamicQueryParamState<T> {
+    state: DynamicQueryState,
+    marker: PhantomData<fn() -> T>,
+}
+
+// SAFETY: Relevant query ComponentId and ArchetypeComponentId access is applied to SystemMeta. If
+// this DynamicQueryState conflicts with any prior access, a panic will occur.
+unsafe impl<T: DynamicQueryTerms> SystemParamState for DynamicQueryParamState<T> {
+    fn init(world: &mut World, system_meta: &mut SystemMeta) -> Self {
+        let mut builder = QueryBuilder::new(world);
+        T::terms(&mut builder);
+        let state = builder.build();
+        assert_component_access_compatibility(
+            &system_meta.name,
+            &format!("DynamicQuery<{}>", std::any::type_name::<T>()),
+            &system_meta.component_access_set,
+            state.component_access(),
+            world,
+        );
+        system_meta
+            .component_access_set
+            .add(state.component_access().clone());
+        system_meta
+            .archetype_component_access
+            .extend(state.archetype_component_access());
+        Self {
+            state,
+            marker: PhantomData,
+        }
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype, system_meta: &mut SystemMeta) {
+        self.state.new_archetype(archetype);
+        system_meta
+            .archetype_component_access
+            .extend(self.state.archetype_component_access());
+    }
+}
+
+impl<'w, 's, T: DynamicQueryTerms> SystemParamFetch<'w, 's> for DynamicQueryParamState<T> {
+    type Item = DynamicQuery<'w, 's, T>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'s mut Self,
+        system_meta: &SystemMeta,
+        world: &'w World,
+        change_tick: u32,
+    ) -> Self::Item {
+        state.state.validate_world(world);
+        DynamicQuery {
+            world,
+            state: &state.state,
+            last_change_tick: system_meta.last_change_tick,
+            change_tick,
+            marker: PhantomData,
+        }
+    }
 }
 
 pub struct ParamSet<'w, 's, T: SystemParam> {
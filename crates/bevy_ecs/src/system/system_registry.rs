@@ -0,0 +1,276 @@
+use std::fmt;
+
+use crate::{
+    self as bevy_ecs,
+    component::Component,
+    entity::Entity,
+    system::{BoxedSystem, Command, IntoSystem},
+    world::World,
+};
+
+/// A [`BoxedSystem`] stored on its own entity by [`World::register_system`], along with whether
+/// it has been initialized yet.
+#[derive(Component)]
+struct RegisteredSystem {
+    initialized: bool,
+    system: BoxedSystem,
+}
+
+/// An identifier for a system registered with [`World::register_system`].
+///
+/// The system is stored on the entity wrapped by this id, so it is invalidated when the system is
+/// removed with [`World::remove_system`] or the entity is despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SystemId(Entity);
+
+impl SystemId {
+    /// Returns the entity storing the system.
+    pub fn entity(self) -> Entity {
+        self.0
+    }
+}
+
+impl World {
+    /// Registers a system and returns a [`SystemId`] so it can later be run with
+    /// [`World::run_system`] or [`Commands::run_system`](crate::system::Commands::run_system).
+    ///
+    /// The same system instance is reused by every run, so its [`Local`](crate::system::Local)s
+    /// and change detection ticks are kept between runs. Registering the same function twice
+    /// creates two independent systems.
+    pub fn register_system<Params, S: IntoSystem<(), (), Params> + 'static>(
+        &mut self,
+        system: S,
+    ) -> SystemId {
+        SystemId(
+            self.spawn(RegisteredSystem {
+                initialized: false,
+                system: Box::new(IntoSystem::into_system(system)),
+            })
+            .id(),
+        )
+    }
+
+    /// Removes a system registered with [`World::register_system`].
+    pub fn remove_system(&mut self, id: SystemId) -> Result<(), RegisteredSystemError> {
+        match self.get_entity_mut(id.0) {
+            Some(mut entity) => {
+                if entity.remove::<RegisteredSystem>().is_none() {
+                    return Err(RegisteredSystemError::SelfRemove(id));
+                }
+                entity.despawn();
+                Ok(())
+            }
+            None => Err(RegisteredSystemError::SystemIdNotRegistered(id)),
+        }
+    }
+
+    /// Runs a system registered with [`World::register_system`] once, then applies its
+    /// [`Commands`](crate::system::Commands).
+    ///
+    /// The system is initialized on its first run.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Resource, Default)]
+    /// struct Counter(u8);
+    ///
+    /// fn increment(mut counter: ResMut<Counter>, mut runs: Local<u8>) {
+    ///     *runs += 1;
+    ///     counter.0 = *runs;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.init_resource::<Counter>();
+    /// let id = world.register_system(increment);
+    /// world.run_system(id).unwrap();
+    /// world.run_system(id).unwrap();
+    /// assert_eq!(world.resource::<Counter>().0, 2);
+    /// ```
+    pub fn run_system(&mut self, id: SystemId) -> Result<(), RegisteredSystemError> {
+        let mut entity = self
+            .get_entity_mut(id.0)
+            .ok_or(RegisteredSystemError::SystemIdNotRegistered(id))?;
+
+        // The system is taken out of the world while it runs, so a missing component means it is
+        // already running.
+        let RegisteredSystem {
+            mut initialized,
+            mut system,
+        } = entity
+            .remove::<RegisteredSystem>()
+            .ok_or(RegisteredSystemError::Recursive(id))?;
+
+        if !initialized {
+            system.initialize(self);
+            initialized = true;
+        }
+        system.run((), self);
+        system.apply_buffers(self);
+
+        // The system may have despawned its own entity.
+        if let Some(mut entity) = self.get_entity_mut(id.0) {
+            entity.insert(RegisteredSystem {
+                initialized,
+                system,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Command`] running a system registered with [`World::register_system`].
+///
+/// Errors are ignored: the system may have been removed between the time the command was queued
+/// and the time it is applied.
+#[derive(Debug, Clone, Copy)]
+pub struct RunSystem {
+    pub system_id: SystemId,
+}
+
+impl Command for RunSystem {
+    fn write(self, world: &mut World) {
+        let _ = world.run_system(self.system_id);
+    }
+}
+
+/// An error returned by [`World::run_system`] and [`World::remove_system`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RegisteredSystemError {
+    /// No system is registered with this id.
+    SystemIdNotRegistered(SystemId),
+    /// The system tried to run itself.
+    Recursive(SystemId),
+    /// The system tried to remove itself.
+    SelfRemove(SystemId),
+}
+
+impl std::error::Error for RegisteredSystemError {}
+
+impl fmt::Display for RegisteredSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegisteredSystemError::SystemIdNotRegistered(id) => {
+                write!(f, "System {:?} was not registered.", id)
+            }
+            RegisteredSystemError::Recursive(id) => {
+                write!(f, "System {:?} tried to run itself recursively.", id)
+            }
+            RegisteredSystemError::SelfRemove(id) => {
+                write!(f, "System {:?} tried to remove itself.", id)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as bevy_ecs,
+        prelude::*,
+        system::{RegisteredSystemError, SystemId},
+    };
+
+    #[derive(Resource, Default, PartialEq, Debug)]
+    struct Counter(u8);
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[test]
+    fn locals_are_kept_between_runs() {
+        fn count_runs(mut counter: ResMut<Counter>, mut runs: Local<u8>) {
+            *runs += 1;
+            counter.0 = *runs;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let id = world.register_system(count_runs);
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(3));
+
+        // A second registration is a distinct system with its own locals.
+        let other = world.register_system(count_runs);
+        world.run_system(other).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+    }
+
+    #[test]
+    fn change_ticks_are_kept_between_runs() {
+        fn count_changes(mut counter: ResMut<Counter>, query: Query<(), Changed<Marker>>) {
+            counter.0 += query.iter().count() as u8;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let entity = world.spawn(Marker).id();
+        let id = world.register_system(count_changes);
+
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+
+        // Nothing changed since the last run.
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+
+        world.get_mut::<Marker>(entity).unwrap().set_changed();
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(2));
+    }
+
+    #[test]
+    fn run_system_from_commands() {
+        fn spawn_marker(mut commands: Commands) {
+            commands.spawn(Marker);
+        }
+
+        let mut world = World::new();
+        let id = world.register_system(spawn_marker);
+        let mut queue = bevy_ecs::system::CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.run_system(id);
+        commands.run_system(id);
+        queue.apply(&mut world);
+
+        assert_eq!(world.query::<&Marker>().iter(&world).count(), 2);
+    }
+
+    #[test]
+    fn invalid_ids() {
+        #[derive(Resource)]
+        struct SelfId(SystemId);
+
+        fn nested(world: &mut World) {
+            let id = world.resource::<SelfId>().0;
+            assert_eq!(
+                world.run_system(id),
+                Err(RegisteredSystemError::Recursive(id))
+            );
+            assert_eq!(
+                world.remove_system(id),
+                Err(RegisteredSystemError::SelfRemove(id))
+            );
+            world.resource_mut::<Counter>().0 += 1;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Counter>();
+        let id = world.register_system(nested);
+        world.insert_resource(SelfId(id));
+        world.run_system(id).unwrap();
+        assert_eq!(*world.resource::<Counter>(), Counter(1));
+
+        world.remove_system(id).unwrap();
+        assert_eq!(
+            world.run_system(id),
+            Err(RegisteredSystemError::SystemIdNotRegistered(id))
+        );
+        assert_eq!(
+            world.remove_system(id),
+            Err(RegisteredSystemError::SystemIdNotRegistered(id))
+        );
+    }
+}
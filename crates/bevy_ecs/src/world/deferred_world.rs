@@ -0,0 +1,72 @@
+use std::ops::Deref;
+
+use crate::{
+    change_detection::Mut,
+    component::Component,
+    entity::Entity,
+    event::Event,
+    system::{Commands, Resource},
+    world::World,
+};
+
+/// A [`World`] reference that can mutate components and resources, but not the structure of the
+/// world: spawning, despawning, inserting and removing components go through
+/// [`DeferredWorld::commands`] instead.
+///
+/// It is given to [component hooks](crate::component::ComponentHooks), which run in the middle of
+/// a structural change. The queued commands are applied by [`World::flush_commands`] once the
+/// structural change is complete.
+pub struct DeferredWorld<'w> {
+    world: &'w mut World,
+}
+
+impl<'w> DeferredWorld<'w> {
+    #[inline]
+    pub(crate) fn new(world: &'w mut World) -> Self {
+        Self { world }
+    }
+
+    /// Returns [`Commands`] applied to the world by [`World::flush_commands`].
+    #[inline]
+    pub fn commands(&mut self) -> Commands<'_, '_> {
+        Commands::new_from_entities(&mut self.world.command_queue, &self.world.entities)
+    }
+
+    /// Retrieves a mutable reference to the given `entity`'s [`Component`] of the given type.
+    /// Returns [`None`] if the `entity` does not have a [`Component`] of the given type.
+    #[inline]
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<'_, T>> {
+        self.world.get_mut(entity)
+    }
+
+    /// Gets a mutable reference to the resource of the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resource does not exist.
+    #[inline]
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.world.resource_mut()
+    }
+
+    /// Gets a mutable reference to the resource of the given type if it exists.
+    #[inline]
+    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<Mut<'_, R>> {
+        self.world.get_resource_mut()
+    }
+
+    /// Sends an [`Event`].
+    #[inline]
+    pub fn send_event<E: Event>(&mut self, event: E) {
+        self.world.send_event(event);
+    }
+}
+
+impl<'w> Deref for DeferredWorld<'w> {
+    type Target = World;
+
+    #[inline]
+    fn deref(&self) -> &World {
+        self.world
+    }
+}
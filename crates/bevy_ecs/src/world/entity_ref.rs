@@ -132,11 +132,16 @@ impl<'w> EntityRef<'w> {
 
 impl<'w> From<EntityMut<'w>> for EntityRef<'w> {
     fn from(entity_mut: EntityMut<'w>) -> EntityRef<'w> {
+        entity_mut.assert_not_despawned();
         EntityRef::new(entity_mut.world, entity_mut.entity, entity_mut.location)
     }
 }
 
 /// A mutable reference to a particular [`Entity`] and all of its components
+///
+/// Structural changes made through it apply the commands queued by
+/// [component hooks](crate::component::ComponentHooks) right away. If those commands despawn the
+/// entity, any further use of the `EntityMut` other than [`EntityMut::id`] panics.
 pub struct EntityMut<'w> {
     world: &'w mut World,
     entity: Entity,
@@ -145,7 +150,8 @@ pub struct EntityMut<'w> {
 
 impl<'w> EntityMut<'w> {
     /// # Safety
-    /// entity and location _must_ be valid
+    /// entity and location _must_ be valid, or location must be [`EntityLocation::INVALID`] if
+    /// the entity was despawned
     #[inline]
     pub(crate) unsafe fn new(
         world: &'w mut World,
@@ -167,11 +173,13 @@ impl<'w> EntityMut<'w> {
 
     #[inline]
     pub fn location(&self) -> EntityLocation {
+        self.assert_not_despawned();
         self.location
     }
 
     #[inline]
     pub fn archetype(&self) -> &Archetype {
+        self.assert_not_despawned();
         &self.world.archetypes[self.location.archetype_id]
     }
 
@@ -182,16 +190,19 @@ impl<'w> EntityMut<'w> {
 
     #[inline]
     pub fn contains_id(&self, component_id: ComponentId) -> bool {
+        self.assert_not_despawned();
         contains_component_with_id(self.world, component_id, self.location)
     }
 
     #[inline]
     pub fn contains_type_id(&self, type_id: TypeId) -> bool {
+        self.assert_not_despawned();
         contains_component_with_type(self.world, type_id, self.location)
     }
 
     #[inline]
     pub fn get<T: Component>(&self) -> Option<&'_ T> {
+        self.assert_not_despawned();
         // SAFETY: lifetimes enforce correct usage of returned borrow
         unsafe {
             get_component_with_type(self.world, TypeId::of::<T>(), self.entity, self.location)
@@ -209,6 +220,7 @@ impl<'w> EntityMut<'w> {
     /// detection in custom runtimes.
     #[inline]
     pub fn get_change_ticks<T: Component>(&self) -> Option<&ComponentTicks> {
+        self.assert_not_despawned();
         // SAFETY: entity location is valid
         unsafe {
             get_ticks_with_type(self.world, TypeId::of::<T>(), self.entity, self.location)
@@ -228,6 +240,7 @@ impl<'w> EntityMut<'w> {
     ///   operation on this world (non-exhaustive list).
     #[inline]
     pub unsafe fn get_unchecked_mut<T: Component>(&self) -> Option<Mut<'_, T>> {
+        self.assert_not_despawned();
         get_component_and_ticks_with_type(self.world, TypeId::of::<T>(), self.entity, self.location)
             .map(|(value, ticks)| Mut {
                 value: value.assert_unique().deref_mut::<T>(),
@@ -251,11 +264,15 @@ impl<'w> EntityMut<'w> {
     ///
     /// This will overwrite any previous value(s) of the same component type.
     pub fn insert<T: Bundle>(&mut self, bundle: T) -> &mut Self {
+        self.assert_not_despawned();
         let change_tick = self.world.change_tick();
+        let has_lifecycle_listeners = self.world.has_lifecycle_listeners();
+        let old_archetype_id = self.location.archetype_id;
         let bundle_info = self
             .world
             .bundles
             .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+        let component_ids = has_lifecycle_listeners.then(|| bundle_info.component_ids.clone());
         let mut bundle_inserter = bundle_info.get_bundle_inserter(
             &mut self.world.entities,
             &mut self.world.archetypes,
@@ -269,6 +286,16 @@ impl<'w> EntityMut<'w> {
             self.location = bundle_inserter.insert(self.entity, self.location.index, bundle);
         }
 
+        if let Some(component_ids) = component_ids {
+            self.world.trigger_on_add_and_insert(
+                self.entity,
+                Some(old_archetype_id),
+                &component_ids,
+            );
+            self.world.flush_commands();
+            self.update_location();
+        }
+
         self
     }
 
@@ -285,6 +312,24 @@ impl<'w> EntityMut<'w> {
     ///
     /// Returns `None` if the entity does not contain the bundle.
     pub fn remove<T: Bundle>(&mut self) -> Option<T> {
+        self.assert_not_despawned();
+        let has_lifecycle_listeners = self.world.has_lifecycle_listeners();
+        if has_lifecycle_listeners {
+            let bundle_info = self
+                .world
+                .bundles
+                .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+            let archetype = &self.world.archetypes[self.location.archetype_id];
+            if bundle_info
+                .component_ids
+                .iter()
+                .all(|id| archetype.contains(*id))
+            {
+                let component_ids = bundle_info.component_ids.clone();
+                self.world.trigger_on_remove(self.entity, &component_ids);
+            }
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -345,6 +390,11 @@ impl<'w> EntityMut<'w> {
             );
         }
 
+        if has_lifecycle_listeners {
+            self.world.flush_commands();
+            self.update_location();
+        }
+
         Some(result)
     }
 
@@ -418,6 +468,23 @@ impl<'w> EntityMut<'w> {
     // TODO: move to BundleInfo
     /// Remove any components in the bundle that the entity has.
     pub fn remove_intersection<T: Bundle>(&mut self) {
+        self.assert_not_despawned();
+        let has_lifecycle_listeners = self.world.has_lifecycle_listeners();
+        if has_lifecycle_listeners {
+            let bundle_info = self
+                .world
+                .bundles
+                .init_info::<T>(&mut self.world.components, &mut self.world.storages);
+            let archetype = &self.world.archetypes[self.location.archetype_id];
+            let component_ids: Vec<_> = bundle_info
+                .component_ids
+                .iter()
+                .copied()
+                .filter(|id| archetype.contains(*id))
+                .collect();
+            self.world.trigger_on_remove(self.entity, &component_ids);
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -478,11 +545,27 @@ impl<'w> EntityMut<'w> {
                 new_archetype_id,
             );
         }
+
+        if has_lifecycle_listeners {
+            self.world.flush_commands();
+            self.update_location();
+        }
     }
 
     pub fn despawn(self) {
+        self.assert_not_despawned();
         debug!("Despawning entity {:?}", self.entity);
         let world = self.world;
+        if world.has_lifecycle_listeners() {
+            let component_ids: Vec<_> = world.archetypes[self.location.archetype_id]
+                .components()
+                .collect();
+            world.trigger_on_remove(self.entity, &component_ids);
+        }
+        if !world.observers.is_empty() {
+            world.observers.remove(self.entity);
+        }
+
         world.flush();
         let location = world
             .entities
@@ -519,6 +602,8 @@ impl<'w> EntityMut<'w> {
             world.archetypes[moved_location.archetype_id]
                 .set_entity_table_row(moved_location.index, table_row);
         }
+
+        world.flush_commands();
     }
 
     #[inline]
@@ -555,7 +640,26 @@ impl<'w> EntityMut<'w> {
     /// [`World`]. This is only needed if the user called [`EntityMut::world`], which enables the
     /// location to change.
     pub fn update_location(&mut self) {
-        self.location = self.world.entities().get(self.entity).unwrap();
+        self.location = self
+            .world
+            .entities()
+            .get(self.entity)
+            .unwrap_or(EntityLocation::INVALID);
+    }
+
+    /// Returns `true` if the entity was despawned since this `EntityMut` was created, for example
+    /// by a command queued from a component hook.
+    #[inline]
+    pub fn is_despawned(&self) -> bool {
+        self.location.archetype_id == ArchetypeId::INVALID
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_not_despawned(&self) {
+        if self.is_despawned() {
+            panic!("Entity {:?} was despawned", self.entity);
+        }
     }
 }
 
@@ -570,6 +674,7 @@ impl<'w> EntityMut<'w> {
     /// which is only valid while the [`EntityMut`] is alive.
     #[inline]
     pub fn get_by_id(&self, component_id: ComponentId) -> Option<Ptr<'_>> {
+        self.assert_not_despawned();
         self.world.components().get_info(component_id)?;
         // SAFETY: entity_location is valid, component_id is valid as checked by the line above
         unsafe { get_component(self.world, component_id, self.entity, self.location) }
@@ -585,6 +690,7 @@ impl<'w> EntityMut<'w> {
     /// which is only valid while the [`EntityMut`] is alive.
     #[inline]
     pub fn get_mut_by_id(&mut self, component_id: ComponentId) -> Option<MutUntyped<'_>> {
+        self.assert_not_despawned();
         self.world.components().get_info(component_id)?;
         // SAFETY: entity_location is valid, component_id is valid as checked by the line above
         unsafe { get_mut_by_id(self.world, self.entity, self.location, component_id) }
@@ -630,7 +736,7 @@ pub(crate) unsafe fn get_component(
 /// # Safety
 /// Caller must ensure that `component_id` is valid
 #[inline]
-unsafe fn get_component_and_ticks(
+pub(crate) unsafe fn get_component_and_ticks(
     world: &World,
     component_id: ComponentId,
     entity: Entity,
@@ -0,0 +1,522 @@
+use bevy_utils::HashMap;
+
+use crate::{
+    self as bevy_ecs,
+    archetype::ArchetypeId,
+    component::{Component, ComponentHooks, ComponentId},
+    entity::Entity,
+    system::{Command, IntoSystem, System},
+    world::{DeferredWorld, World},
+};
+
+/// A change in the components of an entity, which [component hooks](ComponentHooks) and
+/// observers registered with [`World::observe`] react to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleEvent {
+    /// The component was added to an entity that did not have it.
+    OnAdd,
+    /// A value of the component was inserted, whether or not the entity already had one.
+    OnInsert,
+    /// The component is removed from an entity, or the entity is despawned.
+    OnRemove,
+}
+
+/// The entities of the observers of each [`LifecycleEvent`] and component.
+#[derive(Default)]
+pub(crate) struct Observers {
+    observers: HashMap<(LifecycleEvent, ComponentId), Vec<Entity>>,
+    /// The event and component each observer entity is registered for.
+    keys: HashMap<Entity, (LifecycleEvent, ComponentId)>,
+}
+
+impl Observers {
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    fn insert(&mut self, key: (LifecycleEvent, ComponentId), observer: Entity) {
+        self.observers.entry(key).or_default().push(observer);
+        self.keys.insert(observer, key);
+    }
+
+    /// Unregisters `entity` if it is an observer. Called when it is despawned.
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        let key = match self.keys.remove(&entity) {
+            Some(key) => key,
+            None => return,
+        };
+        if let Some(observers) = self.observers.get_mut(&key) {
+            observers.retain(|observer| *observer != entity);
+            if observers.is_empty() {
+                self.observers.remove(&key);
+            }
+        }
+    }
+}
+
+/// An observer system stored on its own entity by [`World::observe`].
+#[derive(Component)]
+struct Observer {
+    initialized: bool,
+    system: Box<dyn System<In = Entity, Out = ()>>,
+}
+
+impl World {
+    /// Returns the [`ComponentHooks`] of `T`, to register new hooks.
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, world::DeferredWorld, component::ComponentId};
+    /// # use bevy_utils::HashMap;
+    /// #[derive(Component)]
+    /// struct Name(&'static str);
+    ///
+    /// #[derive(Resource, Default)]
+    /// struct NameIndex(HashMap<&'static str, Entity>);
+    ///
+    /// fn index_name(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    ///     let name = world.get::<Name>(entity).unwrap().0;
+    ///     world.resource_mut::<NameIndex>().0.insert(name, entity);
+    /// }
+    ///
+    /// fn unindex_name(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    ///     let name = world.get::<Name>(entity).unwrap().0;
+    ///     world.resource_mut::<NameIndex>().0.remove(name);
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.init_resource::<NameIndex>();
+    /// world
+    ///     .register_component_hooks::<Name>()
+    ///     .on_insert(index_name)
+    ///     .on_remove(unindex_name);
+    ///
+    /// let entity = world.spawn(Name("crate")).id();
+    /// assert_eq!(world.resource::<NameIndex>().0["crate"], entity);
+    /// world.despawn(entity);
+    /// assert!(world.resource::<NameIndex>().0.is_empty());
+    /// ```
+    pub fn register_component_hooks<T: Component>(&mut self) -> &mut ComponentHooks {
+        let id = self.init_component::<T>();
+        self.components.get_hooks_mut(id).unwrap()
+    }
+
+    /// Registers a system run with the entity whenever `event` happens for the component `T`,
+    /// and returns the entity storing the observer. Despawn it to remove the observer.
+    ///
+    /// Unlike [component hooks](ComponentHooks), observers do not run in the middle of the
+    /// structural change that triggered them, but when [`World::flush_commands`] is called right
+    /// after it. By then, commands queued by hooks may have changed or despawned the entity; in
+    /// particular, removed components are already gone when [`LifecycleEvent::OnRemove`]
+    /// observers run.
+    ///
+    /// ```
+    /// # use bevy_ecs::{prelude::*, world::LifecycleEvent};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Dead;
+    ///
+    /// fn mark_dead(In(entity): In<Entity>, mut commands: Commands) {
+    ///     commands.entity(entity).insert(Dead);
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.observe::<Health, _>(LifecycleEvent::OnRemove, mark_dead);
+    ///
+    /// let entity = world.spawn(Health(10)).id();
+    /// world.entity_mut(entity).remove::<Health>();
+    /// assert!(world.entity(entity).contains::<Dead>());
+    /// ```
+    pub fn observe<T: Component, Params>(
+        &mut self,
+        event: LifecycleEvent,
+        system: impl IntoSystem<Entity, (), Params>,
+    ) -> Entity {
+        let component_id = self.init_component::<T>();
+        let observer = self
+            .spawn(Observer {
+                initialized: false,
+                system: Box::new(IntoSystem::into_system(system)),
+            })
+            .id();
+        self.observers.insert((event, component_id), observer);
+
+        observer
+    }
+
+    /// Applies the commands queued by [component hooks](ComponentHooks) and runs the pending
+    /// observers, until none are left.
+    ///
+    /// This is done automatically after every structural change of [`World`] and
+    /// [`EntityMut`](crate::world::EntityMut) that triggers lifecycle events, when
+    /// [`Commands`](crate::system::Commands) are applied, and after exclusive systems run.
+    pub fn flush_commands(&mut self) {
+        while !self.command_queue.is_empty() {
+            let mut queue = std::mem::take(&mut self.command_queue);
+            queue.apply_queued(self);
+        }
+    }
+
+    /// Returns `false` when structural changes can skip triggering lifecycle events.
+    #[inline]
+    pub(crate) fn has_lifecycle_listeners(&self) -> bool {
+        self.components.hooks_registered() || !self.observers.is_empty()
+    }
+
+    /// Triggers [`LifecycleEvent::OnAdd`] for the components that were not in `old_archetype_id`,
+    /// then [`LifecycleEvent::OnInsert`] for all of them. Must be called after the insertion.
+    pub(crate) fn trigger_on_add_and_insert(
+        &mut self,
+        entity: Entity,
+        old_archetype_id: Option<ArchetypeId>,
+        component_ids: &[ComponentId],
+    ) {
+        for &component_id in component_ids {
+            let added = match old_archetype_id {
+                Some(archetype_id) => !self.archetypes[archetype_id].contains(component_id),
+                None => true,
+            };
+            if added {
+                self.trigger_lifecycle(LifecycleEvent::OnAdd, entity, component_id);
+            }
+        }
+        for &component_id in component_ids {
+            self.trigger_lifecycle(LifecycleEvent::OnInsert, entity, component_id);
+        }
+    }
+
+    /// Triggers [`LifecycleEvent::OnRemove`]. Must be called before the removal.
+    pub(crate) fn trigger_on_remove(&mut self, entity: Entity, component_ids: &[ComponentId]) {
+        for &component_id in component_ids {
+            self.trigger_lifecycle(LifecycleEvent::OnRemove, entity, component_id);
+        }
+    }
+
+    fn trigger_lifecycle(
+        &mut self,
+        event: LifecycleEvent,
+        entity: Entity,
+        component_id: ComponentId,
+    ) {
+        let hooks = self.components.get_info(component_id).unwrap().hooks();
+        let hook = match event {
+            LifecycleEvent::OnAdd => hooks.on_add,
+            LifecycleEvent::OnInsert => hooks.on_insert,
+            LifecycleEvent::OnRemove => hooks.on_remove,
+        };
+        if let Some(hook) = hook {
+            hook(DeferredWorld::new(self), entity, component_id);
+        }
+
+        if self
+            .observers
+            .observers
+            .contains_key(&(event, component_id))
+        {
+            self.command_queue.push(TriggerObservers {
+                event,
+                component_id,
+                entity,
+            });
+        }
+    }
+}
+
+/// Runs the observers of `event` for `component_id` with `entity` as input.
+struct TriggerObservers {
+    event: LifecycleEvent,
+    component_id: ComponentId,
+    entity: Entity,
+}
+
+impl Command for TriggerObservers {
+    fn write(self, world: &mut World) {
+        let key = (self.event, self.component_id);
+        let observers = match world.observers.observers.get(&key) {
+            Some(observers) => observers.clone(),
+            None => return,
+        };
+
+        for observer in observers {
+            // The observer is taken out of the world while it runs, so a missing component means
+            // it triggered itself.
+            let state = match world.get_entity_mut(observer) {
+                Some(mut entity) => entity.remove::<Observer>(),
+                // The observer was despawned by one that ran before it.
+                None => continue,
+            };
+            let Observer {
+                mut initialized,
+                mut system,
+            } = match state {
+                Some(state) => state,
+                None => continue,
+            };
+
+            if !initialized {
+                system.initialize(world);
+                initialized = true;
+            }
+            system.run(self.entity, world);
+            system.apply_buffers(world);
+
+            if let Some(mut entity) = world.get_entity_mut(observer) {
+                entity.insert(Observer {
+                    initialized,
+                    system,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_utils::HashMap;
+
+    use crate::{
+        self as bevy_ecs,
+        component::ComponentId,
+        prelude::*,
+        system::CommandQueue,
+        world::{DeferredWorld, LifecycleEvent},
+    };
+
+    #[derive(Component)]
+    struct Name(&'static str);
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[derive(Resource, Default)]
+    struct NameIndex(HashMap<&'static str, Entity>);
+
+    #[derive(Resource, Default, Debug, PartialEq)]
+    struct Log(Vec<(&'static str, Entity)>);
+
+    fn index_name(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+        let name = world.get::<Name>(entity).unwrap().0;
+        world.resource_mut::<NameIndex>().0.insert(name, entity);
+    }
+
+    fn unindex_name(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+        let name = world.get::<Name>(entity).unwrap().0;
+        world.resource_mut::<NameIndex>().0.remove(name);
+    }
+
+    fn name_index_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<NameIndex>();
+        world
+            .register_component_hooks::<Name>()
+            .on_insert(index_name)
+            .on_remove(unindex_name);
+        world
+    }
+
+    fn index(world: &World) -> Vec<(&'static str, Entity)> {
+        let mut index: Vec<_> = world
+            .resource::<NameIndex>()
+            .0
+            .iter()
+            .map(|(name, entity)| (*name, *entity))
+            .collect();
+        index.sort();
+        index
+    }
+
+    #[test]
+    fn hooks_keep_an_index_consistent() {
+        let mut world = name_index_world();
+
+        let a = world.spawn(Name("a")).id();
+        let b = world.spawn((Marker, Name("b"))).id();
+        let c = world.spawn(Marker).id();
+        world.entity_mut(c).insert(Name("c"));
+        assert_eq!(index(&world), vec![("a", a), ("b", b), ("c", c)]);
+
+        world.entity_mut(a).remove::<Name>();
+        world.entity_mut(a).insert(Name("d"));
+        world.entity_mut(b).remove_intersection::<(Name, Marker)>();
+        world.despawn(c);
+        assert_eq!(index(&world), vec![("d", a)]);
+
+        let batch: Vec<_> = world.spawn_batch([Name("e"), Name("f")]).collect();
+        world.insert_or_spawn_batch([(b, Name("g"))]).unwrap();
+        assert_eq!(
+            index(&world),
+            vec![("d", a), ("e", batch[0]), ("f", batch[1]), ("g", b)]
+        );
+    }
+
+    #[test]
+    fn hooks_run_from_commands() {
+        let mut world = name_index_world();
+        let mut queue = CommandQueue::default();
+
+        let mut commands = Commands::new(&mut queue, &world);
+        let a = commands.spawn(Name("a")).id();
+        let b = commands.spawn(Name("b")).id();
+        commands.entity(b).remove::<Name>();
+        queue.apply(&mut world);
+        assert_eq!(index(&world), vec![("a", a)]);
+
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(a).despawn();
+        queue.apply(&mut world);
+        assert!(index(&world).is_empty());
+    }
+
+    #[test]
+    fn add_runs_only_for_new_components() {
+        fn log_add(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+            world.resource_mut::<Log>().0.push(("add", entity));
+        }
+
+        fn log_insert(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+            world.resource_mut::<Log>().0.push(("insert", entity));
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world
+            .register_component_hooks::<Marker>()
+            .on_add(log_add)
+            .on_insert(log_insert);
+
+        let entity = world.spawn(Marker).id();
+        world.entity_mut(entity).insert(Marker);
+        assert_eq!(
+            world.resource::<Log>().0,
+            vec![("add", entity), ("insert", entity), ("insert", entity)]
+        );
+    }
+
+    #[test]
+    fn hooks_defer_structural_changes() {
+        fn despawn_on_add(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+            world.commands().entity(entity).despawn();
+        }
+
+        let mut world = World::new();
+        world
+            .register_component_hooks::<Marker>()
+            .on_add(despawn_on_add);
+
+        let entity = world.spawn(Marker);
+        assert!(entity.is_despawned());
+        let id = entity.id();
+        assert!(world.get_entity(id).is_none());
+
+        let mut entity = world.spawn(Name("b"));
+        entity.insert(Marker);
+        assert!(entity.is_despawned());
+    }
+
+    #[test]
+    #[should_panic(expected = "was despawned")]
+    fn despawned_entity_mut_panics() {
+        fn despawn_on_add(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+            world.commands().entity(entity).despawn();
+        }
+
+        let mut world = World::new();
+        world
+            .register_component_hooks::<Marker>()
+            .on_add(despawn_on_add);
+
+        world.spawn(Name("a")).insert(Marker).get::<Name>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Component already has an on_add hook")]
+    fn hooks_are_unique() {
+        fn hook(_: DeferredWorld, _: Entity, _: ComponentId) {}
+
+        let mut world = World::new();
+        world.register_component_hooks::<Marker>().on_add(hook);
+        world.register_component_hooks::<Marker>().on_add(hook);
+    }
+
+    #[test]
+    fn observers_receive_the_entity() {
+        fn log_add(In(entity): In<Entity>, mut log: ResMut<Log>) {
+            log.0.push(("add", entity));
+        }
+
+        fn log_remove(In(entity): In<Entity>, mut log: ResMut<Log>, names: Query<&Name>) {
+            assert!(names.get(entity).is_err());
+            log.0.push(("remove", entity));
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Log>();
+        world.observe::<Name, _>(LifecycleEvent::OnAdd, log_add);
+        let observer = world.observe::<Name, _>(LifecycleEvent::OnRemove, log_remove);
+
+        let a = world.spawn(Name("a")).id();
+        world.entity_mut(a).insert(Name("b"));
+        world.despawn(a);
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let b = commands.spawn(Name("c")).id();
+        queue.apply(&mut world);
+
+        world.despawn(observer);
+        world.entity_mut(b).remove::<Name>();
+
+        assert_eq!(
+            world.resource::<Log>().0,
+            vec![("add", a), ("remove", a), ("add", b)]
+        );
+    }
+
+    #[test]
+    fn observers_can_change_the_entity() {
+        fn despawn(In(entity): In<Entity>, mut commands: Commands) {
+            commands.entity(entity).despawn();
+        }
+
+        let mut world = World::new();
+        world.observe::<Marker, _>(LifecycleEvent::OnAdd, despawn);
+
+        let entity = world.spawn(Marker).id();
+        assert!(world.get_entity(entity).is_none());
+
+        world.spawn_batch([Marker, Marker]);
+        assert_eq!(world.query::<&Marker>().iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn despawned_observers_are_unregistered() {
+        fn despawn_self(In(_): In<Entity>, observer: Res<SelfObserver>, mut commands: Commands) {
+            commands.entity(observer.0).despawn();
+        }
+
+        #[derive(Resource)]
+        struct SelfObserver(Entity);
+
+        let mut world = World::new();
+        let observer = world.observe::<Marker, _>(LifecycleEvent::OnAdd, |_: In<Entity>| {});
+        world.despawn(observer);
+        assert!(world.observers.is_empty());
+
+        let observer = world.observe::<Marker, _>(LifecycleEvent::OnAdd, despawn_self);
+        world.insert_resource(SelfObserver(observer));
+        world.spawn(Marker);
+        assert!(world.get_entity(observer).is_none());
+        assert!(world.observers.is_empty());
+    }
+
+    #[test]
+    fn spawn_batch_iter_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut world = World::new();
+        let batch = world.spawn_batch([Marker, Marker]);
+        assert_send(&batch);
+    }
+}
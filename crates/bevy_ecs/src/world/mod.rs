@@ -1,9 +1,13 @@
+mod deferred_world;
 mod entity_ref;
+mod lifecycle;
 mod spawn_batch;
 mod world_cell;
 
 pub use crate::change_detection::Mut;
+pub use deferred_world::*;
 pub use entity_ref::*;
+pub use lifecycle::*;
 pub use spawn_batch::*;
 pub use world_cell::*;
 
@@ -14,10 +18,10 @@ use crate::{
     component::{
         Component, ComponentDescriptor, ComponentId, ComponentInfo, ComponentTicks, Components,
     },
-    entity::{AllocAtWithoutReplacement, Entities, Entity},
-    query::{QueryState, ReadOnlyWorldQuery, WorldQuery},
+    entity::{AllocAtWithoutReplacement, Entities, Entity, EntityLocation},
+    query::{Disabled, QueryState, ReadOnlyWorldQuery, WorldQuery},
     storage::{ResourceData, SparseSet, Storages},
-    system::Resource,
+    system::{CommandQueue, Resource},
 };
 use bevy_ptr::{OwningPtr, Ptr, UnsafeCellDeref};
 use bevy_utils::tracing::warn;
@@ -63,11 +67,14 @@ pub struct World {
     main_thread_validator: MainThreadValidator,
     pub(crate) change_tick: AtomicU32,
     pub(crate) last_change_tick: u32,
+    /// Commands queued by component hooks, applied by [`World::flush_commands`].
+    pub(crate) command_queue: CommandQueue,
+    pub(crate) observers: Observers,
 }
 
 impl Default for World {
     fn default() -> Self {
-        Self {
+        let mut world = Self {
             id: WorldId::new().expect("More `bevy` `World`s have been created than is supported"),
             entities: Default::default(),
             components: Default::default(),
@@ -81,7 +88,13 @@ impl Default for World {
             // are detected on first system runs and for direct world queries.
             change_tick: AtomicU32::new(1),
             last_change_tick: 0,
-        }
+            command_queue: Default::default(),
+            observers: Default::default(),
+        };
+        // Registered up front so that every query, including dynamic ones, can skip disabled
+        // entities.
+        world.init_component::<Disabled>();
+        world
     }
 }
 
@@ -451,7 +464,7 @@ impl World {
     pub fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityMut {
         self.flush();
         let entity = self.entities.alloc();
-        let entity_location = {
+        let mut entity_location = {
             let bundle_info = self
                 .bundles
                 .init_info::<B>(&mut self.components, &mut self.storages);
@@ -466,8 +479,17 @@ impl World {
             // SAFETY: bundle's type matches `bundle_info`, entity is allocated but non-existent
             unsafe { spawner.spawn_non_existent(entity, bundle) }
         };
+        if self.has_lifecycle_listeners() {
+            let bundle_info = self
+                .bundles
+                .init_info::<B>(&mut self.components, &mut self.storages);
+            let component_ids = bundle_info.component_ids.clone();
+            self.trigger_on_add_and_insert(entity, None, &component_ids);
+            self.flush_commands();
+            entity_location = self.entities.get(entity).unwrap_or(EntityLocation::INVALID);
+        }
 
-        // SAFETY: entity and location are valid, as they were just created above
+        // SAFETY: entity and location are valid, as they were just created or updated above
         unsafe { EntityMut::new(self, entity, entity_location) }
     }
 
@@ -494,6 +516,9 @@ impl World {
     /// but it is limited to spawning entities with the same [Bundle] type, whereas spawning
     /// individually is more flexible.
     ///
+    /// [Component hooks](crate::component::ComponentHooks) and observers run once the returned
+    /// iterator is dropped.
+    ///
     /// ```
     /// use bevy_ecs::{component::Component, entity::Entity, world::World};
     ///
@@ -1055,9 +1080,14 @@ impl World {
         let iter = iter.into_iter();
         let change_tick = *self.change_tick.get_mut();
 
+        let has_lifecycle_listeners = self.has_lifecycle_listeners();
         let bundle_info = self
             .bundles
             .init_info::<B>(&mut self.components, &mut self.storages);
+        // The entities and their previous archetype, to trigger lifecycle events once the whole
+        // batch is inserted.
+        let mut lifecycle =
+            has_lifecycle_listeners.then(|| (bundle_info.component_ids.clone(), Vec::new()));
         enum SpawnOrInsert<'a, 'b> {
             Spawn(BundleSpawner<'a, 'b>),
             Insert(BundleInserter<'a, 'b>, ArchetypeId),
@@ -1086,6 +1116,9 @@ impl World {
                 .alloc_at_without_replacement(entity)
             {
                 AllocAtWithoutReplacement::Exists(location) => {
+                    if let Some((_, changed)) = &mut lifecycle {
+                        changed.push((entity, Some(location.archetype_id)));
+                    }
                     match spawn_or_insert {
                         SpawnOrInsert::Insert(ref mut inserter, archetype)
                             if location.archetype_id == archetype =>
@@ -1110,6 +1143,9 @@ impl World {
                     };
                 }
                 AllocAtWithoutReplacement::DidNotExist => {
+                    if let Some((_, changed)) = &mut lifecycle {
+                        changed.push((entity, None));
+                    }
                     if let SpawnOrInsert::Spawn(ref mut spawner) = spawn_or_insert {
                         // SAFETY: `entity` is allocated (but non existent), bundle matches inserter
                         unsafe { spawner.spawn_non_existent(entity, bundle) };
@@ -1132,6 +1168,13 @@ impl World {
             }
         }
 
+        if let Some((component_ids, changed)) = lifecycle {
+            for (entity, old_archetype_id) in changed {
+                self.trigger_on_add_and_insert(entity, old_archetype_id, &component_ids);
+            }
+            self.flush_commands();
+        }
+
         if invalid_entities.is_empty() {
             Ok(())
         } else {
@@ -1423,6 +1466,7 @@ impl World {
         self.storages.sparse_sets.clear();
         self.archetypes.clear_entities();
         self.entities.clear();
+        self.observers = Observers::default();
     }
 }
 
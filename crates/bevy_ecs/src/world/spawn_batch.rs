@@ -1,5 +1,6 @@
 use crate::{
-    bundle::{Bundle, BundleSpawner},
+    bundle::{Bundle, BundleId},
+    component::ComponentId,
     entity::Entity,
     world::World,
 };
@@ -11,7 +12,11 @@ where
     I::Item: Bundle,
 {
     inner: I,
-    spawner: BundleSpawner<'w, 'w>,
+    world: &'w mut World,
+    bundle_id: BundleId,
+    /// The bundle components and the spawned entities, when lifecycle events must be triggered
+    /// once the whole batch is spawned.
+    lifecycle: Option<(Vec<ComponentId>, Vec<Entity>)>,
 }
 
 impl<'w, I> SpawnBatchIter<'w, I>
@@ -28,9 +33,16 @@ where
         let (lower, upper) = iter.size_hint();
         let length = upper.unwrap_or(lower);
 
+        let has_lifecycle_listeners = world.has_lifecycle_listeners();
         let bundle_info = world
             .bundles
             .init_info::<I::Item>(&mut world.components, &mut world.storages);
+        let lifecycle = has_lifecycle_listeners.then(|| {
+            (
+                bundle_info.component_ids.clone(),
+                Vec::with_capacity(length),
+            )
+        });
         world.entities.reserve(length as u32);
         let mut spawner = bundle_info.get_bundle_spawner(
             &mut world.entities,
@@ -40,10 +52,13 @@ where
             *world.change_tick.get_mut(),
         );
         spawner.reserve_storage(length);
+        let bundle_id = bundle_info.id();
 
         Self {
             inner: iter,
-            spawner,
+            world,
+            bundle_id,
+            lifecycle,
         }
     }
 }
@@ -54,7 +69,15 @@ where
     I::Item: Bundle,
 {
     fn drop(&mut self) {
-        for _ in self {}
+        for _ in &mut *self {}
+
+        if let Some((component_ids, entities)) = self.lifecycle.take() {
+            for entity in entities {
+                self.world
+                    .trigger_on_add_and_insert(entity, None, &component_ids);
+            }
+            self.world.flush_commands();
+        }
     }
 }
 
@@ -67,8 +90,22 @@ where
 
     fn next(&mut self) -> Option<Entity> {
         let bundle = self.inner.next()?;
+        let world = &mut *self.world;
+        // The bundle was initialized in `new`.
+        let bundle_info = world.bundles.get(self.bundle_id).unwrap();
+        let mut spawner = bundle_info.get_bundle_spawner(
+            &mut world.entities,
+            &mut world.archetypes,
+            &mut world.components,
+            &mut world.storages,
+            *world.change_tick.get_mut(),
+        );
         // SAFETY: bundle matches spawner type
-        unsafe { Some(self.spawner.spawn(bundle)) }
+        let entity = unsafe { spawner.spawn(bundle) };
+        if let Some((_, entities)) = &mut self.lifecycle {
+            entities.push(entity);
+        }
+        Some(entity)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
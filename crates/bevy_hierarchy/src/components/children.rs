@@ -1,6 +1,7 @@
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    inspector::{EntityChildren, ReflectEntityChildren},
     prelude::FromWorld,
     reflect::{ReflectComponent, ReflectMapEntities},
     world::World,
@@ -17,7 +18,7 @@ use std::ops::Deref;
 /// [`HierarchyQueryExt`]: crate::query_extension::HierarchyQueryExt
 /// [`Query`]: bevy_ecs::system::Query
 #[derive(Component, Debug, Reflect)]
-#[reflect(Component, MapEntities)]
+#[reflect(Component, MapEntities, EntityChildren)]
 pub struct Children(pub(crate) SmallVec<[Entity; 8]>);
 
 impl MapEntities for Children {
@@ -30,6 +31,12 @@ impl MapEntities for Children {
     }
 }
 
+impl EntityChildren for Children {
+    fn child_entities(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
 // TODO: We need to impl either FromWorld or Default so Children can be registered as Reflect.
 // This is because Reflect deserialize by creating an instance and apply a patch on top.
 // However Children should only ever be set with a real user-defined entities. Its worth looking
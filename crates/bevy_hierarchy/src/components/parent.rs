@@ -1,6 +1,7 @@
 use bevy_ecs::{
     component::Component,
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    inspector::{EntityParent, ReflectEntityParent},
     reflect::{ReflectComponent, ReflectMapEntities},
     world::{FromWorld, World},
 };
@@ -15,7 +16,7 @@ use std::ops::Deref;
 /// [`HierarchyQueryExt`]: crate::query_extension::HierarchyQueryExt
 /// [`Query`]: bevy_ecs::system::Query
 #[derive(Component, Debug, Eq, PartialEq, Reflect)]
-#[reflect(Component, MapEntities, PartialEq)]
+#[reflect(Component, MapEntities, PartialEq, EntityParent)]
 pub struct Parent(pub(crate) Entity);
 
 impl Parent {
@@ -25,6 +26,12 @@ impl Parent {
     }
 }
 
+impl EntityParent for Parent {
+    fn parent_entity(&self) -> Entity {
+        self.0
+    }
+}
+
 // TODO: We need to impl either FromWorld or Default so Parent can be registered as Reflect.
 // This is because Reflect deserialize by creating an instance and apply a patch on top.
 // However Parent should only ever be set with a real user-defined entity.  Its worth looking into
@@ -0,0 +1,183 @@
+use crate::components::Children;
+use bevy_ecs::{
+    entity::Entity,
+    query::Disabled,
+    system::{Command, EntityCommands},
+    world::{EntityMut, World},
+};
+use bevy_utils::tracing::debug;
+
+/// Disables the given entity and all its descendants, by inserting [`Disabled`]
+#[derive(Debug)]
+pub struct DisableRecursive {
+    /// Target entity
+    pub entity: Entity,
+}
+
+/// Enables the given entity and all its descendants, by removing [`Disabled`]
+#[derive(Debug)]
+pub struct EnableRecursive {
+    /// Target entity
+    pub entity: Entity,
+}
+
+/// Function for disabling an entity and all its descendants
+pub fn disable_with_children_recursive(world: &mut World, entity: Entity) {
+    set_disabled_recursive(world, entity, true);
+}
+
+/// Function for enabling an entity and all its descendants
+pub fn enable_with_children_recursive(world: &mut World, entity: Entity) {
+    set_disabled_recursive(world, entity, false);
+}
+
+fn set_disabled_recursive(world: &mut World, entity: Entity, disabled: bool) {
+    let mut entity_mut = match world.get_entity_mut(entity) {
+        Some(entity_mut) => entity_mut,
+        None => {
+            debug!("Failed to set the Disabled state of entity {:?}", entity);
+            return;
+        }
+    };
+    if disabled {
+        entity_mut.insert(Disabled);
+    } else {
+        entity_mut.remove::<Disabled>();
+    }
+    let children = match entity_mut.get::<Children>() {
+        Some(children) => children.to_vec(),
+        None => return,
+    };
+    for child in children {
+        set_disabled_recursive(world, child, disabled);
+    }
+}
+
+impl Command for DisableRecursive {
+    fn write(self, world: &mut World) {
+        #[cfg(feature = "trace")]
+        let _span = bevy_utils::tracing::info_span!(
+            "command",
+            name = "DisableRecursive",
+            entity = bevy_utils::tracing::field::debug(self.entity)
+        )
+        .entered();
+        disable_with_children_recursive(world, self.entity);
+    }
+}
+
+impl Command for EnableRecursive {
+    fn write(self, world: &mut World) {
+        #[cfg(feature = "trace")]
+        let _span = bevy_utils::tracing::info_span!(
+            "command",
+            name = "EnableRecursive",
+            entity = bevy_utils::tracing::field::debug(self.entity)
+        )
+        .entered();
+        enable_with_children_recursive(world, self.entity);
+    }
+}
+
+/// Trait that holds functions for disabling and enabling an entity and its descendants.
+///
+/// Disabled entities keep their components, but are skipped by queries, see [`Disabled`].
+/// Enabling an entity also enables the descendants that were disabled on their own.
+pub trait DisableRecursiveExt {
+    /// Disables the provided entity alongside all descendants.
+    fn disable_recursive(&mut self) -> &mut Self;
+
+    /// Enables the provided entity alongside all descendants.
+    fn enable_recursive(&mut self) -> &mut Self;
+}
+
+impl<'w, 's, 'a> DisableRecursiveExt for EntityCommands<'w, 's, 'a> {
+    fn disable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(DisableRecursive { entity });
+        self
+    }
+
+    fn enable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        self.commands().add(EnableRecursive { entity });
+        self
+    }
+}
+
+impl<'w> DisableRecursiveExt for EntityMut<'w> {
+    fn disable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        // SAFETY: The location is updated.
+        unsafe {
+            disable_with_children_recursive(self.world_mut(), entity);
+            self.update_location();
+        }
+        self
+    }
+
+    fn enable_recursive(&mut self) -> &mut Self {
+        let entity = self.id();
+        // SAFETY: The location is updated.
+        unsafe {
+            enable_with_children_recursive(self.world_mut(), entity);
+            self.update_location();
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{
+        component::Component,
+        query::{Disabled, IncludeDisabled},
+        system::{CommandQueue, Commands},
+        world::World,
+    };
+
+    use super::DisableRecursiveExt;
+    use crate::child_builder::BuildChildren;
+
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Debug)]
+    struct Idx(u32);
+
+    fn enabled(world: &mut World) -> Vec<u32> {
+        let mut results: Vec<_> = world.query::<&Idx>().iter(world).map(|i| i.0).collect();
+        results.sort_unstable();
+        results
+    }
+
+    #[test]
+    fn disable_and_enable_recursive() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let parent;
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            parent = commands
+                .spawn(Idx(0))
+                .with_children(|parent| {
+                    parent.spawn(Idx(1)).with_children(|parent| {
+                        parent.spawn(Idx(2));
+                    });
+                })
+                .id();
+            commands.spawn(Idx(3));
+        }
+        queue.apply(&mut world);
+
+        Commands::new(&mut queue, &world)
+            .entity(parent)
+            .disable_recursive();
+        queue.apply(&mut world);
+        assert_eq!(enabled(&mut world), [3]);
+        // The disabled entities keep their components.
+        let mut all = world.query_filtered::<&Idx, IncludeDisabled>();
+        assert_eq!(all.iter(&world).count(), 4);
+
+        world.entity_mut(parent).enable_recursive();
+        assert_eq!(enabled(&mut world), [0, 1, 2, 3]);
+        assert!(world.query::<&Disabled>().iter(&world).next().is_none());
+    }
+}
@@ -10,6 +10,9 @@ pub use components::*;
 mod hierarchy;
 pub use hierarchy::*;
 
+mod disable;
+pub use disable::*;
+
 mod child_builder;
 pub use child_builder::*;
 
@@ -26,8 +29,8 @@ pub use query_extension::*;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        child_builder::*, components::*, hierarchy::*, query_extension::*, HierarchyPlugin,
-        ValidParentCheckPlugin,
+        child_builder::*, components::*, disable::*, hierarchy::*, query_extension::*,
+        HierarchyPlugin, ValidParentCheckPlugin,
     };
 }
 
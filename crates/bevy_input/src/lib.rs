@@ -35,7 +35,7 @@ use touch::{touch_screen_input_system, ForceTouch, TouchInput, TouchPhase, Touch
 use gamepad::{
     gamepad_connection_system, gamepad_event_system, AxisSettings, ButtonAxisSettings,
     ButtonSettings, Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType,
-    GamepadEvent, GamepadEventRaw, GamepadEventType, GamepadSettings, Gamepads,
+    GamepadEvent, GamepadEventRaw, GamepadEventType, GamepadInfo, GamepadSettings, Gamepads,
 };
 
 #[cfg(feature = "serialize")]
@@ -98,6 +98,7 @@ impl Plugin for InputPlugin {
         // Register keyboard types
         app.register_type::<KeyboardInput>()
             .register_type::<KeyCode>()
+            .register_type::<Option<KeyCode>>()
             .register_type::<ScanCode>();
 
         // Register mouse types
@@ -114,6 +115,7 @@ impl Plugin for InputPlugin {
 
         // Register gamepad types
         app.register_type::<Gamepad>()
+            .register_type::<GamepadInfo>()
             .register_type::<GamepadEventType>()
             .register_type::<GamepadEvent>()
             .register_type::<GamepadEventRaw>()
@@ -125,6 +127,15 @@ impl Plugin for InputPlugin {
             .register_type::<ButtonSettings>()
             .register_type::<AxisSettings>()
             .register_type::<ButtonAxisSettings>();
+
+        // Allow CI testing to inject input events
+        #[cfg(feature = "bevy_ci_testing")]
+        app.register_ci_testing_event::<KeyboardInput>()
+            .register_ci_testing_event::<MouseButtonInput>()
+            .register_ci_testing_event::<MouseMotion>()
+            .register_ci_testing_event::<MouseWheel>()
+            .register_ci_testing_event::<TouchInput>()
+            .register_ci_testing_event::<GamepadEventRaw>();
     }
 }
 
@@ -185,5 +185,11 @@ pub mod dynamic_plugin {
     pub use bevy_dynamic_plugin::*;
 }
 
+#[cfg(feature = "bevy_remote")]
+pub mod remote {
+    //! Remote debugging protocol
+    pub use bevy_remote::*;
+}
+
 #[cfg(target_os = "android")]
 pub use ndk_glue;
@@ -1,22 +1,24 @@
 use bevy_core_pipeline::{core_3d, prelude::Camera3d};
+use bevy_diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin};
 use bevy_ecs::{
-    prelude::{Bundle, Component, ReflectComponent, Without},
-    system::{Query, Res, ResMut, Resource},
-    world::EntityMut,
+    prelude::{Added, Bundle, Component, Entity, ReflectComponent, With, Without},
+    system::{Commands, Query, Res, ResMut, Resource},
+    world::{EntityMut, World},
 };
 use bevy_hierarchy::BuildWorldChildren;
-use bevy_math::{Mat4, Quat, Vec3};
+use bevy_math::{Mat4, Quat, UVec2, Vec3};
 use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, Uuid};
 use bevy_render::{
-    camera::{Camera, CameraProjection, CameraRenderGraph, RenderTarget},
-    prelude::VisibilityBundle,
+    camera::{
+        Camera, CameraProjection, CameraRenderGraph, ManualTextureViews, RenderTarget, Viewport,
+    },
+    prelude::SpatialBundle,
     primitives::Frustum,
     view::VisibleEntities,
 };
-use bevy_transform::{
-    components::{GlobalTransform, Transform},
-    TransformBundle,
-};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_utils::Duration;
+use bevy_xr::{XrResolutionScale, XrSystem};
 //  mostly copied from https://github.com/blaind/bevy_openxr/tree/main/crates/bevy_openxr/src/render_graph/camera
 use openxr::{Fovf, Quaternionf, Vector3f, View};
 
@@ -195,94 +197,243 @@ impl CameraProjection for XRProjection {
 #[derive(Resource)]
 pub struct XrViews(pub Vec<View>);
 
+/// Ids of the [`ManualTextureViews`](bevy_render::camera::ManualTextureViews) the eye cameras
+/// render to. The OpenXR runner fills them with the acquired swapchain images every frame.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct XrEyeTargets {
+    pub left: Uuid,
+    pub right: Uuid,
+}
+
+impl Default for XrEyeTargets {
+    fn default() -> Self {
+        Self {
+            left: Uuid::new_v4(),
+            right: Uuid::new_v4(),
+        }
+    }
+}
+
+/// Updates the XR camera rig from the located views. The rig is organized as follows:
+///
+/// ```text
+/// XrPawn -- transform set by the developer, moves the whole rig (see `bevy_xr::locomotion`)
+/// |
+/// V
+/// XrCameras -- transform set as midpoint/midrotation of the views, in the tracking space. Can be
+/// |            used to know the head position relative to the pawn
+/// |
+/// V
+/// [Eye::Left, Eye::Right] -- transform set as the individual views relative to the head, used
+///                            for rendering
+/// ```
 pub fn update_xrcamera_view(
     mut cam: Query<(&mut XRProjection, &mut Transform, &Eye)>,
-    mut xr_cam: Query<(&mut Transform, &XrCameras), Without<Eye>>,
-    views: Option<ResMut<XrViews>>,
+    mut xr_cam: Query<&mut Transform, (With<XrCameras>, Without<Eye>)>,
+    views: Option<Res<XrViews>>,
 ) {
     let views = match &views {
-        Some(views) => &views.0,
-        None => return,
+        Some(views) if views.0.len() >= 2 => &views.0,
+        _ => return,
     };
-    let midpoint = (views.get(0).unwrap().pose.position.to_vec3()
-        + views.get(1).unwrap().pose.position.to_vec3())
-        / 2.;
-    xr_cam.single_mut().0.translation = midpoint;
 
-    let left_rot = views.get(0).unwrap().pose.orientation.to_quat();
-    let right_rot = views.get(1).unwrap().pose.orientation.to_quat();
+    let midpoint = (views[0].pose.position.to_vec3() + views[1].pose.position.to_vec3()) / 2.;
+    let left_rot = views[0].pose.orientation.to_quat();
+    let right_rot = views[1].pose.orientation.to_quat();
     let mid_rot = if left_rot.dot(right_rot) >= 0. {
         left_rot.slerp(right_rot, 0.5)
     } else {
         right_rot.slerp(left_rot, 0.5)
     };
-    xr_cam.single_mut().0.rotation = mid_rot;
-
-    /*
-        TODO: figure out transform hierarchy for XrCameras
-        maybe have a parent object:
-        XrPlayer -- transform set by developer
-        |
-        V
-        XrCameras -- transform set as midpoint/midrotation of two openXR views,
-        |           used for developer to understand relative head position
-        |
-        V
-        [XrCamera::left, XrCamera::right] -- transform set as individual views, used for rendering
-
-    */
+    let head = Transform::from_translation(midpoint).with_rotation(mid_rot);
+    let inverse_head_rot = mid_rot.inverse();
+
+    for mut transform in xr_cam.iter_mut() {
+        *transform = head;
+    }
+
     for (mut projection, mut transform, eye) in cam.iter_mut() {
         let view_idx = match eye {
             Eye::Left => 0,
             Eye::Right => 1,
         };
-        let view = views.get(view_idx).unwrap();
+        let view = &views[view_idx];
 
         projection.fov = view.fov;
 
-        transform.rotation = view.pose.orientation.to_quat();
-        let pos = view.pose.position;
-        transform.translation = pos.to_vec3();
+        transform.rotation = inverse_head_rot * view.pose.orientation.to_quat();
+        transform.translation = inverse_head_rot * (view.pose.position.to_vec3() - midpoint);
     }
 }
 
-#[derive(Component)]
+/// Root of the XR camera rig. Its transform is owned by the developer: moving or rotating the
+/// pawn moves the head and hands tracking space through the world. The [`XrCameras`] and
+/// [`Eye`] cameras are spawned as descendants when the component is added.
+#[derive(Component, Default)]
 pub struct XrPawn {}
 
+#[derive(Bundle, Default)]
+pub struct XrPawnBundle {
+    pub pawn: XrPawn,
+    #[bundle]
+    pub spatial: SpatialBundle,
+}
+
 impl XrPawn {
-    pub fn spawn(mut e: EntityMut, left_id: Uuid, right_id: Uuid) {
-        e.with_children(|pawn| {
-            pawn.spawn(XrCameras {}).insert(TransformBundle::default());
-            pawn.spawn(XRCameraBundle {
-                camera: Camera {
-                    target: RenderTarget::TextureView(left_id),
-                    is_active: true,
-                    ..Default::default()
-                },
-                marker: XrCameraLeftMarker,
-                ..Default::default()
-            })
-            .insert(Eye::Left);
-            pawn.spawn(XRCameraBundle {
-                camera: Camera {
-                    target: RenderTarget::TextureView(right_id),
-                    is_active: true,
-                    ..Default::default()
-                },
-                marker: XrCameraRightMarker,
-                ..Default::default()
-            })
-            .insert(Eye::Right);
-        })
-        .insert(Self {})
-        .insert(TransformBundle::default())
-        .insert(VisibilityBundle::default());
+    /// Spawns the camera rig as children of `pawn`, rendering to `targets`.
+    pub fn spawn_rig(mut pawn: EntityMut, targets: XrEyeTargets) {
+        pawn.with_children(|pawn| {
+            pawn.spawn((XrCameras {}, SpatialBundle::default()))
+                .with_children(|head| {
+                    head.spawn(XRCameraBundle {
+                        camera: Camera {
+                            target: RenderTarget::TextureView(targets.left),
+                            is_active: true,
+                            ..Default::default()
+                        },
+                        marker: XrCameraLeftMarker,
+                        ..Default::default()
+                    })
+                    .insert(Eye::Left);
+                    head.spawn(XRCameraBundle {
+                        camera: Camera {
+                            target: RenderTarget::TextureView(targets.right),
+                            is_active: true,
+                            ..Default::default()
+                        },
+                        marker: XrCameraRightMarker,
+                        ..Default::default()
+                    })
+                    .insert(Eye::Right);
+                });
+        });
+    }
+}
+
+/// Spawns the camera rig under every newly added [`XrPawn`].
+pub fn spawn_xr_camera_rig(
+    mut commands: Commands,
+    targets: Option<Res<XrEyeTargets>>,
+    pawns: Query<Entity, Added<XrPawn>>,
+) {
+    let targets = match targets {
+        Some(targets) => *targets,
+        None => return,
+    };
+    for pawn in pawns.iter() {
+        commands.add(move |world: &mut World| {
+            if let Some(pawn) = world.get_entity_mut(pawn) {
+                XrPawn::spawn_rig(pawn, targets);
+            }
+        });
     }
 }
 
 #[derive(Component)]
 pub struct XrCameras {}
 
+/// Adjusts [`XrResolutionScale`] from the frame time measured by [`FrameTimeDiagnosticsPlugin`],
+/// when a target frame time is set.
+pub fn adjust_xr_resolution_scale(
+    diagnostics: Option<Res<Diagnostics>>,
+    resolution_scale: Option<ResMut<XrResolutionScale>>,
+) {
+    let (diagnostics, mut resolution_scale) = match (diagnostics, resolution_scale) {
+        (Some(diagnostics), Some(resolution_scale)) => (diagnostics, resolution_scale),
+        _ => return,
+    };
+    if resolution_scale.target_frame_time.is_none() {
+        return;
+    }
+
+    if let Some(frame_time_ms) = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+    {
+        resolution_scale.update(Duration::from_secs_f64(frame_time_ms / 1000.0));
+    }
+}
+
+/// Renders the [`Eye`] cameras into the top-left corner of their swapchain image, scaled by
+/// [`XrResolutionScale`]. The runner submits the matching sub-image rect to the compositor.
+pub fn update_xrcamera_viewports(
+    resolution_scale: Option<Res<XrResolutionScale>>,
+    manual_texture_views: Res<ManualTextureViews>,
+    mut cam: Query<&mut Camera, With<Eye>>,
+) {
+    let resolution_scale = resolution_scale.map(|scale| *scale).unwrap_or_default();
+
+    for mut camera in cam.iter_mut() {
+        let full_size = match &camera.target {
+            RenderTarget::TextureView(id) => match manual_texture_views.get(id) {
+                Some((_, size)) => *size,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let physical_size = resolution_scale.apply(full_size);
+
+        let up_to_date = camera
+            .viewport
+            .as_ref()
+            .map_or(false, |viewport| viewport.physical_size == physical_size);
+        if !up_to_date {
+            camera.viewport = Some(Viewport {
+                physical_size,
+                ..Default::default()
+            });
+        }
+    }
+}
+
+/// Returns the rendered size of the left and right [`Eye`] cameras, if their viewport is set.
+pub(crate) fn eye_viewport_sizes(world: &mut World) -> [Option<UVec2>; 2] {
+    let mut sizes = [None, None];
+    for (eye, camera) in world.query::<(&Eye, &Camera)>().iter(world) {
+        let idx = match eye {
+            Eye::Left => 0,
+            Eye::Right => 1,
+        };
+        sizes[idx] = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| viewport.physical_size);
+    }
+
+    sizes
+}
+
+/// Marks flat-screen cameras deactivated while the XR session is running.
+#[derive(Component)]
+pub struct XrSuspendedCamera;
+
+/// Activates the [`Eye`] cameras and deactivates every other camera while the XR session is
+/// running, and restores the flat-screen cameras when the session ends.
+pub fn swap_flat_and_xr_cameras(
+    mut commands: Commands,
+    xr_system: Option<Res<XrSystem>>,
+    mut eyes: Query<&mut Camera, With<Eye>>,
+    mut flat_cameras: Query<(Entity, &mut Camera, Option<&XrSuspendedCamera>), Without<Eye>>,
+) {
+    let running = xr_system.map_or(false, |xr_system| xr_system.is_session_running());
+
+    for mut camera in eyes.iter_mut() {
+        if camera.is_active != running {
+            camera.is_active = running;
+        }
+    }
+
+    for (entity, mut camera, suspended) in flat_cameras.iter_mut() {
+        if running && camera.is_active {
+            camera.is_active = false;
+            commands.entity(entity).insert(XrSuspendedCamera);
+        } else if !running && suspended.is_some() {
+            camera.is_active = true;
+            commands.entity(entity).remove::<XrSuspendedCamera>();
+        }
+    }
+}
+
 #[derive(Component, Debug)]
 pub enum Eye {
     Left,
@@ -1,4 +1,4 @@
-use bevy_app::{App, CoreStage, Plugin};
+use bevy_app::{App, CoreStage, Plugin, PluginDependency};
 
 use bevy_core_pipeline::core_3d::{AlphaMask3d, Opaque3d, Transparent3d};
 use bevy_ecs::{
@@ -17,7 +17,10 @@ use bevy_render::{
 use bevy_transform::TransformSystem;
 use bevy_window::ModifiesWindows;
 
-use super::{update_xrcamera_view, XRProjection};
+use super::{
+    adjust_xr_resolution_scale, spawn_xr_camera_rig, update_xrcamera_view,
+    update_xrcamera_viewports, XRProjection,
+};
 
 #[derive(Component, Default)]
 pub struct XrCameraLeftMarker;
@@ -60,7 +63,20 @@ impl Plugin for XrCameraPlugin {
                 .before(VisibilitySystems::UpdatePerspectiveFrusta),
         );
 
-        app.add_system_to_stage(CoreStage::PreUpdate, update_xrcamera_view);
+        app.add_system_to_stage(CoreStage::PreUpdate, spawn_xr_camera_rig)
+            .add_system_to_stage(CoreStage::PreUpdate, update_xrcamera_view)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                adjust_xr_resolution_scale.before(update_xrcamera_viewports),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, update_xrcamera_viewports);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            PluginDependency::required::<bevy_render::RenderPlugin>(),
+            PluginDependency::required::<bevy_core_pipeline::CorePipelinePlugin>(),
+        ]
     }
 }
 
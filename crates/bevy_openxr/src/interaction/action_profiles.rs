@@ -93,6 +93,20 @@ pub fn setup_interaction(system: &mut XrSystem) {
                     },
                     "/user/hand/right/input/system".into(), //   might be unavailable for app use
                 ),
+                (
+                    XrActionDescriptor {
+                        name: "left_thumbstick".into(),
+                        action_type: XrActionType::Vec2D,
+                    },
+                    "/user/hand/left/input/thumbstick".into(),
+                ),
+                (
+                    XrActionDescriptor {
+                        name: "right_thumbstick".into(),
+                        action_type: XrActionType::Vec2D,
+                    },
+                    "/user/hand/right/input/thumbstick".into(),
+                ),
             ],
             tracked: true,
             has_haptics: true,
@@ -169,6 +183,20 @@ pub fn setup_interaction(system: &mut XrSystem) {
                     },
                     "/user/hand/right/input/a".into(),
                 ),
+                (
+                    XrActionDescriptor {
+                        name: "left_thumbstick".into(),
+                        action_type: XrActionType::Vec2D,
+                    },
+                    "/user/hand/left/input/thumbstick".into(),
+                ),
+                (
+                    XrActionDescriptor {
+                        name: "right_thumbstick".into(),
+                        action_type: XrActionType::Vec2D,
+                    },
+                    "/user/hand/right/input/thumbstick".into(),
+                ),
             ],
             tracked: true,
             has_haptics: true,
@@ -24,20 +24,22 @@ use bevy_render::{
     settings::WgpuSettings,
 };
 
-use bevy_utils::Uuid;
 pub use interaction::*;
 
 #[cfg(feature = "winit_loop")]
 use ::winit::event_loop::EventLoop;
-use bevy_app::{App, AppExit, Plugin};
+use bevy_app::{App, AppExit, CoreStage, Plugin, PluginDependency};
 use bevy_ecs::{
     event::{Events, ManualEventReader},
     system::Resource,
 };
+use bevy_time::TimeUpdateStrategy;
+use bevy_utils::Instant;
 use bevy_xr::{
     presentation::{XrEnvironmentBlendMode, XrGraphicsContext, XrInteractionMode},
-    XrActionDescriptor, XrActionSet, XrActionType, XrProfileDescriptor, XrProfiles, XrSessionMode,
-    XrSystem, XrTrackingSource, XrVibrationEvent, XrVisibilityState,
+    XrActionDescriptor, XrActionSet, XrActionType, XrFrameTiming, XrProfileDescriptor, XrProfiles,
+    XrSessionEvent, XrSessionMode, XrSessionRequest, XrSystem, XrTrackingSource, XrVibrationEvent,
+    XrVisibilityState,
 };
 use openxr::{self as xr, sys};
 use parking_lot::RwLock;
@@ -56,8 +58,8 @@ use std::{
 use wgpu::{Backends, TextureUsages, TextureViewDescriptor};
 use wgpu_hal::TextureUses;
 
-pub use crate::camera::XrPawn;
 use crate::camera::XrViews;
+pub use crate::camera::{XrEyeTargets, XrPawn, XrPawnBundle};
 
 // The form-factor is selected at plugin-creation-time and cannot be changed anymore for the entire
 // lifetime of the app. This will restrict which XrSessionMode can be selected.
@@ -299,61 +301,202 @@ fn get_system_info(
     Some((view_type, blend_mode))
 }
 
+/// Controls when the OpenXR session is created.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum OpenXrSessionStart {
+    /// The session is created when the plugin is built and the app exits when the session ends.
+    /// Panics if no OpenXR runtime is available.
+    Immediate,
+    /// The app starts in flat-screen mode and the session is created when a
+    /// [`XrSessionRequest::Start`] event is sent. When the session ends, the app goes back to
+    /// flat-screen mode. XR and flat cameras are activated and deactivated accordingly. If no
+    /// OpenXR runtime is available, the app keeps running in flat-screen mode.
+    ///
+    /// Requires the `winit_loop` feature, which runs the winit event loop in flat-screen mode:
+    /// the plugin panics without it.
+    OnRequest,
+}
+
+impl Default for OpenXrSessionStart {
+    fn default() -> Self {
+        Self::Immediate
+    }
+}
+
 #[derive(Default)]
-pub struct OpenXrPlugin;
+pub struct OpenXrPlugin {
+    pub session_start: OpenXrSessionStart,
+    /// When true, [`Time`](bevy_time::Time) is advanced with the display time predicted by the
+    /// runtime instead of the wall clock while the session is running, so that animations are
+    /// phase-locked with the display. The predicted display time and period are always available
+    /// in [`XrFrameTiming`].
+    pub use_predicted_display_time: bool,
+}
+
+#[derive(Clone, Copy, Resource)]
+struct RunnerSettings {
+    session_start: OpenXrSessionStart,
+    use_predicted_display_time: bool,
+}
 
 impl Plugin for OpenXrPlugin {
     fn build(&self, app: &mut App) {
-        setup::setup_xrcontext_and_graphics(app);
-        //  Populate this state before the runner so that plugins that run
-        //  app.update() will have the expected resources (such as
-        //  bevy_editor_pls).
-        let runner_state = setup::setup_other_xr(app);
-        app.insert_resource(runner_state);
+        // Without a window event loop, flat-screen mode would be a busy loop of app updates.
+        #[cfg(not(feature = "winit_loop"))]
+        assert!(
+            self.session_start != OpenXrSessionStart::OnRequest,
+            "OpenXR: `OpenXrSessionStart::OnRequest` requires the `winit_loop` feature"
+        );
+
+        match self.session_start {
+            OpenXrSessionStart::Immediate => {
+                setup::setup_xrcontext_and_graphics(app);
+                setup::setup_xr_system(app);
+                //  Populate this state before the runner so that plugins that run
+                //  app.update() will have the expected resources (such as
+                //  bevy_editor_pls).
+                let runner_state = setup::create_session(app);
+                app.insert_resource(runner_state);
+            }
+            OpenXrSessionStart::OnRequest => {
+                match setup::try_setup_xrcontext_and_graphics(app) {
+                    Ok(()) => setup::setup_xr_system(app),
+                    Err(e) => bevy_log::warn!("OpenXR: Running in flat-screen mode: {:?}", e),
+                }
+                app.add_system_to_stage(CoreStage::PreUpdate, camera::swap_flat_and_xr_cameras);
+            }
+        }
+
+        app.insert_resource(RunnerSettings {
+            session_start: self.session_start,
+            use_predicted_display_time: self.use_predicted_display_time,
+        })
+        .set_runner(runner);
+    }
+
+    fn dependencies(&self) -> Vec<PluginDependency> {
+        vec![
+            // The runner reads the session requests and sends the session events of `XrPlugin`
+            PluginDependency::required::<bevy_xr::XrPlugin>(),
+            #[cfg(feature = "winit_loop")]
+            PluginDependency::optional::<bevy_winit::WinitPlugin>(),
+        ]
     }
 }
 
+/// State that persists across sessions.
+#[derive(Default)]
+struct RunnerContext {
+    app_exit_event_reader: ManualEventReader<AppExit>,
+    session_request_reader: ManualEventReader<XrSessionRequest>,
+    #[cfg(feature = "winit_loop")]
+    winit_state: crate::winit::State,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SessionLoopExit {
+    SessionEnded,
+    AppExit,
+}
 
-// Currently, only the session loop is implemented. If the session is destroyed or fails to
-// create, the app will exit.
-// todo: Implement the instance loop when the the lifecycle API is implemented.
 fn runner(mut app: App) {
+    let settings = app.world.remove_resource::<RunnerSettings>().unwrap();
+    let mut runner_state = app.world.remove_resource::<setup::XrRunnerState>();
+    let mut runner_context = RunnerContext::default();
+
+    #[cfg(feature = "winit_loop")]
+    {
+        crate::winit::init_window(&mut app);
+    }
+
+    loop {
+        if let Some(mut state) = runner_state.take() {
+            let exit = session_loop(&mut app, &mut state, &mut runner_context, settings);
+            if settings.use_predicted_display_time {
+                app.world.insert_resource(TimeUpdateStrategy::Automatic);
+            }
+            if exit == SessionLoopExit::AppExit
+                || settings.session_start == OpenXrSessionStart::Immediate
+            {
+                break;
+            }
+            setup::destroy_session(&mut app, state);
+            continue;
+        }
+
+        // Flat-screen mode
+        #[cfg(feature = "winit_loop")]
+        {
+            runner_context.winit_state = crate::winit::run_event_loop(
+                std::mem::take(&mut runner_context.winit_state),
+                &mut app,
+            );
+        }
+
+        app.update();
+
+        if runner_context
+            .app_exit_event_reader
+            .iter(app.world.resource::<Events<AppExit>>())
+            .next_back()
+            .is_some()
+        {
+            break;
+        }
+
+        let start_requested = runner_context
+            .session_request_reader
+            .iter(app.world.resource::<Events<XrSessionRequest>>())
+            .any(|request| *request == XrSessionRequest::Start);
+        if start_requested {
+            if app.world.contains_resource::<OpenXrContext>() {
+                runner_state = Some(setup::create_session(&mut app));
+            } else {
+                bevy_log::warn!("OpenXR: Cannot start the session, no runtime is available");
+            }
+        }
+    }
+}
+
+// Runs the session until it is destroyed. If the session fails to create, the app will exit.
+// todo: Implement the instance loop when the the lifecycle API is implemented.
+fn session_loop(
+    app: &mut App,
+    state: &mut setup::XrRunnerState,
+    runner_context: &mut RunnerContext,
+    settings: RunnerSettings,
+) -> SessionLoopExit {
     let setup::XrRunnerState {
-        tracking_context,
+        ref tracking_context,
         view_type,
-        mut app_exit_event_reader,
-        interaction_context,
-        mut frame_waiter,
-        mut frame_stream,
+        ref interaction_context,
+        ref mut frame_waiter,
+        ref mut frame_stream,
         blend_mode,
-        next_vsync_time,
-        stage,
-        vk_session,
-        left_id,
-        right_id,
-        xr_context: ctx,
-    }: setup::XrRunnerState = app.world.remove_resource().unwrap();
+        ref next_vsync_time,
+        ref stage,
+        ref vk_session,
+        xr_context: ref ctx,
+    } = *state;
     let mut vibration_event_reader = ManualEventReader::default();
 
     let mut event_storage = xr::EventDataBuffer::new();
 
     let mut swapchain = None;
     let mut running = false;
-
-    #[cfg(feature = "winit_loop")]
-    let mut winit_state = crate::winit::State::new();
-    #[cfg(feature = "winit_loop")]
-    {
-        crate::winit::init_window(&mut app);
-    }
+    let mut exit = SessionLoopExit::SessionEnded;
 
     let session = app.world.resource::<OpenXrSession>().clone();
 
+    // Maps the runtime clock to `Instant`s, anchored at the first rendered frame.
+    let mut time_anchor: Option<(Instant, xr::Time)> = None;
+
     let mut frame_count = 0usize;
     'session_loop: loop {
         #[cfg(feature = "winit_loop")]
         {
-            winit_state = crate::winit::run_event_loop(winit_state, &mut app);
+            runner_context.winit_state =
+                crate::winit::run_event_loop(std::mem::take(&mut runner_context.winit_state), app);
         }
 
         frame_count += 1;
@@ -364,6 +507,7 @@ fn runner(mut app: App) {
                 }
                 xr::Event::InstanceLossPending(_) => {
                     bevy_log::info!("OpenXR: Shutting down for runtime request");
+                    end_session(app, &mut running);
                     break 'session_loop;
                 }
                 xr::Event::SessionStateChanged(e) => {
@@ -374,6 +518,11 @@ fn runner(mut app: App) {
                         xr::SessionState::READY => {
                             session.begin(view_type).unwrap();
                             running = true;
+
+                            let mut xr_system = app.world.resource_mut::<XrSystem>();
+                            xr_system.set_session_running(true);
+                            let mode = xr_system.selected_session_mode();
+                            app.world.send_event(XrSessionEvent::Started(mode));
                         }
                         xr::SessionState::SYNCHRONIZED => {
                             app.world.insert_resource(XrVisibilityState::Hidden)
@@ -386,10 +535,13 @@ fn runner(mut app: App) {
                         }
                         xr::SessionState::STOPPING => {
                             session.end().unwrap();
-                            running = false;
+                            end_session(app, &mut running);
                         }
                         xr::SessionState::EXITING | xr::SessionState::LOSS_PENDING => {
                             println!("Exiting | Loss Pending");
+                            // The runtime may skip STOPPING, for example when the session is lost
+                            // while running.
+                            end_session(app, &mut running);
                             break 'session_loop;
                         }
                         _ => unreachable!(),
@@ -501,20 +653,38 @@ fn runner(mut app: App) {
             continue;
         }
 
-        //  TODO: override bevy time with predicted frame time?
         *next_vsync_time.write() = frame_state.predicted_display_time;
 
+        app.world.insert_resource(XrFrameTiming {
+            predicted_display_time: Duration::from_nanos(
+                frame_state.predicted_display_time.as_nanos() as u64,
+            ),
+            predicted_display_period: Duration::from_nanos(
+                frame_state.predicted_display_period.as_nanos() as u64,
+            ),
+        });
+
+        if settings.use_predicted_display_time {
+            let (anchor_instant, anchor_time) = *time_anchor
+                .get_or_insert_with(|| (Instant::now(), frame_state.predicted_display_time));
+            let elapsed_nanos =
+                frame_state.predicted_display_time.as_nanos() - anchor_time.as_nanos();
+            let instant = anchor_instant + Duration::from_nanos(elapsed_nanos.max(0) as u64);
+            app.world
+                .insert_resource(TimeUpdateStrategy::ManualInstant(instant));
+        }
+
         {
             let _world_cell = app.world.cell();
             handle_input(
-                &interaction_context,
+                interaction_context,
                 &session,
                 &mut _world_cell.get_resource_mut::<XrActionSet>().unwrap(),
             );
         }
 
         let (view_state_flags, views) = session
-            .locate_views(view_type, frame_state.predicted_display_time, &stage)
+            .locate_views(view_type, frame_state.predicted_display_time, stage)
             .unwrap();
 
         let view_cfgs = session
@@ -532,14 +702,15 @@ fn runner(mut app: App) {
             .collect();
         let device = ctx.wgpu_device.clone();
         let swapchains = swapchain
-            .get_or_insert_with(|| EyeSwapchains::new(&vk_session, resolutions, device).unwrap());
+            .get_or_insert_with(|| EyeSwapchains::new(vk_session, resolutions, device).unwrap());
 
         let left_tex = swapchains.left.acquire_texture_view().unwrap();
         let right_tex = swapchains.right.acquire_texture_view().unwrap();
 
+        let targets = *app.world.resource::<XrEyeTargets>();
         let mut manual_texture_views = app.world.get_resource_mut::<ManualTextureViews>().unwrap();
-        manual_texture_views.insert(left_id, (left_tex.into(), resolutions[0].bevy()));
-        manual_texture_views.insert(right_id, (right_tex.into(), resolutions[1].bevy()));
+        manual_texture_views.insert(targets.left, (left_tex.into(), resolutions[0].bevy()));
+        manual_texture_views.insert(targets.right, (right_tex.into(), resolutions[1].bevy()));
 
         app.world.insert_resource(XrViews(views.clone()));
 
@@ -548,6 +719,13 @@ fn runner(mut app: App) {
         swapchains.left.release().unwrap();
         swapchains.right.release().unwrap();
 
+        // Only the part of the swapchain images covered by the eye viewports has been rendered.
+        let [left_size, right_size] = camera::eye_viewport_sizes(&mut app.world);
+        let image_rects = [
+            left_size.unwrap_or_else(|| resolutions[0].bevy()).xr(),
+            right_size.unwrap_or_else(|| resolutions[1].bevy()).xr(),
+        ];
+
         if view_state_flags
             .contains(ViewStateFlags::POSITION_VALID | ViewStateFlags::ORIENTATION_VALID)
         {
@@ -555,26 +733,24 @@ fn runner(mut app: App) {
                 .end(
                     frame_state.predicted_display_time,
                     blend_mode,
-                    &[
-                        &xr::CompositionLayerProjection::new().space(&stage).views(&[
-                            xr::CompositionLayerProjectionView::new()
-                                .pose(views[0].pose)
-                                .fov(views[0].fov)
-                                .sub_image(
-                                    xr::SwapchainSubImage::new()
-                                        .swapchain(&swapchains.left.handle)
-                                        .image_rect(resolutions[0].xr()),
-                                ),
-                            xr::CompositionLayerProjectionView::new()
-                                .pose(views[1].pose)
-                                .fov(views[1].fov)
-                                .sub_image(
-                                    xr::SwapchainSubImage::new()
-                                        .swapchain(&swapchains.right.handle)
-                                        .image_rect(resolutions[1].xr()),
-                                ),
-                        ]),
-                    ],
+                    &[&xr::CompositionLayerProjection::new().space(stage).views(&[
+                        xr::CompositionLayerProjectionView::new()
+                            .pose(views[0].pose)
+                            .fov(views[0].fov)
+                            .sub_image(
+                                xr::SwapchainSubImage::new()
+                                    .swapchain(&swapchains.left.handle)
+                                    .image_rect(image_rects[0]),
+                            ),
+                        xr::CompositionLayerProjectionView::new()
+                            .pose(views[1].pose)
+                            .fov(views[1].fov)
+                            .sub_image(
+                                xr::SwapchainSubImage::new()
+                                    .swapchain(&swapchains.right.handle)
+                                    .image_rect(image_rects[1]),
+                            ),
+                    ])],
                 )
                 .unwrap()
         } else {
@@ -584,7 +760,7 @@ fn runner(mut app: App) {
         }
 
         handle_output(
-            &interaction_context,
+            interaction_context,
             &session,
             &mut vibration_event_reader,
             &mut app
@@ -593,14 +769,36 @@ fn runner(mut app: App) {
                 .unwrap(),
         );
 
-        if app_exit_event_reader
+        if runner_context
+            .app_exit_event_reader
             .iter(&app.world.get_resource_mut::<Events<AppExit>>().unwrap())
             .next_back()
             .is_some()
         {
             println!("app exit event");
+            exit = SessionLoopExit::AppExit;
+            session.request_exit().unwrap();
+        } else if runner_context
+            .session_request_reader
+            .iter(app.world.resource::<Events<XrSessionRequest>>())
+            .any(|request| *request == XrSessionRequest::End)
+        {
             session.request_exit().unwrap();
         }
     }
-    println!("runner loop done");
+
+    exit
+}
+
+// Marks the session as stopped and sends `XrSessionEvent::Ended`, unless it was already stopped.
+fn end_session(app: &mut App, running: &mut bool) {
+    if !*running {
+        return;
+    }
+    *running = false;
+
+    app.world
+        .resource_mut::<XrSystem>()
+        .set_session_running(false);
+    app.world.send_event(XrSessionEvent::Ended);
 }
@@ -3,16 +3,6 @@ use xr::{EnvironmentBlendMode, FrameWaiter, ViewConfigurationType};
 use crate::*;
 
 pub fn setup_xrcontext_and_graphics(app: &mut App) {
-    #[cfg(feature = "simulator")]
-    {
-        let mut event_loop = app
-            .world
-            .remove_non_send_resource::<EventLoop<()>>()
-            .unwrap();
-        bevy_openxr_simulator::simulator::pre_graphics_init(&mut event_loop);
-        app.insert_non_send_resource(event_loop);
-    }
-
     if !app.world.contains_resource::<OpenXrContext>() {
         let context =
             OpenXrContext::new(OpenXrFormFactor::HeadMountedDisplay).unwrap_or_else(|_| {
@@ -46,9 +36,37 @@ pub fn setup_xrcontext_and_graphics(app: &mut App) {
         app.world.insert_resource(context);
     }
 
+    insert_graphics_context(app);
+}
+
+/// Same as [`setup_xrcontext_and_graphics`], but returns an error instead of panicking when no
+/// OpenXR runtime or device is available. In that case the app is left untouched and the renderer
+/// will create its own graphics context.
+pub fn try_setup_xrcontext_and_graphics(app: &mut App) -> Result<(), OpenXrError> {
+    if !app.world.contains_resource::<OpenXrContext>() {
+        let context = OpenXrContext::new(OpenXrFormFactor::HeadMountedDisplay)
+            .or_else(|_| OpenXrContext::new(OpenXrFormFactor::Handheld))?;
+        app.world.insert_resource(context);
+    }
+
+    insert_graphics_context(app);
+
+    Ok(())
+}
+
+fn insert_graphics_context(app: &mut App) {
+    #[cfg(feature = "simulator")]
+    {
+        let mut event_loop = app
+            .world
+            .remove_non_send_resource::<EventLoop<()>>()
+            .unwrap();
+        bevy_openxr_simulator::simulator::pre_graphics_init(&mut event_loop);
+        app.insert_non_send_resource(event_loop);
+    }
+
     let mut context = app.world.get_resource_mut::<OpenXrContext>().unwrap();
     let mut graphics_context = context.graphics_context.take().unwrap();
-    println!("got graphics context");
 
     let instance = RenderInstance(graphics_context.instance.take().unwrap());
     let dev = renderer::RenderDevice::from(graphics_context.device.clone());
@@ -64,8 +82,7 @@ pub fn setup_xrcontext_and_graphics(app: &mut App) {
             .insert_resource(instance)
     };
 
-    app.insert_resource::<XrGraphicsContext>(graphics_context)
-        .set_runner(runner);
+    app.insert_resource::<XrGraphicsContext>(graphics_context);
 
     app.insert_resource(Msaa { samples: 1 });
 }
@@ -74,7 +91,6 @@ pub fn setup_xrcontext_and_graphics(app: &mut App) {
 pub struct XrRunnerState {
     pub(crate) tracking_context: Arc<OpenXrTrackingContext>,
     pub(crate) view_type: ViewConfigurationType,
-    pub(crate) app_exit_event_reader: ManualEventReader<AppExit>,
     pub(crate) interaction_context: InteractionContext,
     pub(crate) frame_waiter: FrameWaiter,
     pub(crate) frame_stream: xr::FrameStream<xr::Vulkan>,
@@ -82,13 +98,12 @@ pub struct XrRunnerState {
     pub(crate) next_vsync_time: Arc<RwLock<xr::Time>>,
     pub(crate) stage: xr::Space,
     pub(crate) vk_session: xr::Session<xr::Vulkan>,
-    pub(crate) left_id: Uuid,
-    pub(crate) right_id: Uuid,
     pub(crate) xr_context: OpenXrContext,
 }
 
-pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
-    let ctx = app.world.remove_resource::<OpenXrContext>().unwrap();
+/// Inserts the resources that only depend on the OpenXR instance (such as [`XrSystem`]) and
+/// spawns the [`XrPawn`]. The session itself is created by [`create_session`].
+pub fn setup_xr_system(app: &mut App) {
     #[cfg(feature = "winit_loop")]
     {
         app.world.init_resource::<WinitSettings>();
@@ -104,12 +119,13 @@ pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
         app.insert_non_send_resource(event_loop);
     }
 
-    app.world
-        .insert_resource(XrInstanceRes(ctx.instance.clone()));
-
-    let mut app_exit_event_reader = ManualEventReader::default();
+    let ctx = app.world.resource::<OpenXrContext>();
+    let instance = ctx.instance.clone();
+    let form_factor = ctx.form_factor;
+    let system = ctx.system;
+    app.world.insert_resource(XrInstanceRes(instance.clone()));
 
-    let interaction_mode = if ctx.form_factor == xr::FormFactor::HEAD_MOUNTED_DISPLAY {
+    let interaction_mode = if form_factor == xr::FormFactor::HEAD_MOUNTED_DISPLAY {
         XrInteractionMode::WorldSpace
     } else {
         XrInteractionMode::ScreenSpace
@@ -124,27 +140,36 @@ pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
         XrSessionMode::InlineAR,
     ]
     .iter()
-    .filter_map(|mode| get_system_info(&ctx.instance, ctx.system, *mode).map(|_| *mode))
+    .filter_map(|mode| get_system_info(&instance, system, *mode).map(|_| *mode))
     .collect();
 
     app.world
         .insert_resource(XrSystem::new(available_session_modes));
-    println!("inserted XrSystem");
 
     let mut xr_system = app.world.get_resource_mut::<XrSystem>().unwrap();
     action_profiles::setup_interaction(&mut xr_system);
 
+    app.world.init_resource::<XrActionSet>();
+    app.world.init_resource::<XrEyeTargets>();
+    app.world.spawn(XrPawnBundle::default());
+}
+
+/// Creates the OpenXR session using the session mode and action set selected in [`XrSystem`].
+/// The [`OpenXrContext`] is moved into the returned state, and given back to the world by
+/// [`destroy_session`] when the session ends.
+pub fn create_session(app: &mut App) -> XrRunnerState {
+    let ctx = app.world.remove_resource::<OpenXrContext>().unwrap();
+    let xr_system = app.world.resource::<XrSystem>();
+
     let mode = xr_system.selected_session_mode();
     let bindings = xr_system.action_set();
-    dbg!(bindings.iter().map(|b| &b.profile).collect::<Vec<_>>());
+    bevy_log::debug!(
+        "OpenXR: Interaction profiles: {:?}",
+        bindings.iter().map(|b| &b.profile).collect::<Vec<_>>()
+    );
 
     let interaction_context = InteractionContext::new(&ctx.instance, bindings);
 
-    // Remove XrSystem. The user cannot make any more changes to the session mode.
-    // todo: when the lifecycle API is implemented, allow the user to change the session mode at any
-    // moment.
-    // app.world.remove_resource::<XrSystem>();
-
     let (view_type, blend_mode) = get_system_info(&ctx.instance, ctx.system, mode).unwrap();
 
     let environment_blend_mode = match blend_mode {
@@ -218,7 +243,6 @@ pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
         next_vsync_time: next_vsync_time.clone(),
     };
 
-    app.world.init_resource::<XrActionSet>();
     app.world
         .insert_resource(OpenXrTrackingContextRes(tracking_context.clone()));
     app.world
@@ -234,14 +258,9 @@ pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
         .create_reference_space(xr::ReferenceSpaceType::STAGE, xr::Posef::IDENTITY)
         .unwrap();
 
-    let left_id = Uuid::new_v4();
-    let right_id = Uuid::new_v4();
-    XrPawn::spawn(app.world.spawn_empty(), left_id, right_id);
-
     XrRunnerState {
         tracking_context,
         view_type,
-        app_exit_event_reader,
         interaction_context,
         frame_stream,
         frame_waiter,
@@ -249,8 +268,25 @@ pub fn setup_other_xr(app: &mut App) -> XrRunnerState {
         next_vsync_time,
         stage,
         vk_session,
-        left_id,
-        right_id,
         xr_context: ctx,
     }
 }
+
+/// Removes the session-related resources and gives the [`OpenXrContext`] back to the world so that
+/// a new session can be created later.
+pub fn destroy_session(app: &mut App, state: XrRunnerState) {
+    app.world.remove_resource::<XrTrackingSource>();
+    app.world.remove_resource::<OpenXrTrackingContextRes>();
+    app.world.remove_resource::<OpenXrSession>();
+    app.world.remove_resource::<XrViews>();
+    app.world.resource_mut::<XrActionSet>().clear();
+
+    // The swapchain images are destroyed with the session.
+    let targets = *app.world.resource::<XrEyeTargets>();
+    let mut manual_texture_views = app.world.resource_mut::<ManualTextureViews>();
+    manual_texture_views.remove(&targets.left);
+    manual_texture_views.remove(&targets.right);
+
+    let XrRunnerState { xr_context, .. } = state;
+    app.world.insert_resource(xr_context);
+}
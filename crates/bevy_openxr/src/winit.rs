@@ -18,14 +18,6 @@ pub struct State {
     persistent: WinitPersistentState,
 }
 
-impl State {
-    pub fn new() -> State {
-        State {
-            ..Default::default()
-        }
-    }
-}
-
 pub fn init_window(app: &mut App) {
     let event_loop = app
         .world
@@ -10,7 +10,7 @@ use crate::{
 
 use crate::utility::{GenericTypeInfoCell, NonGenericTypeInfoCell};
 use bevy_reflect_derive::{impl_from_reflect_value, impl_reflect_value};
-use bevy_utils::{Duration, Instant};
+use bevy_utils::{Duration, Instant, Uuid};
 use bevy_utils::{HashMap, HashSet};
 use std::{
     any::Any,
@@ -116,6 +116,14 @@ impl_reflect_value!(Duration(
     Default
 ));
 impl_reflect_value!(Instant(Debug, Hash, PartialEq));
+impl_reflect_value!(Uuid(
+    Debug,
+    Hash,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Default
+));
 impl_reflect_value!(NonZeroI128(Debug, Hash, PartialEq, Serialize, Deserialize));
 impl_reflect_value!(NonZeroU128(Debug, Hash, PartialEq, Serialize, Deserialize));
 impl_reflect_value!(NonZeroIsize(Debug, Hash, PartialEq, Serialize, Deserialize));
@@ -164,6 +172,7 @@ impl_from_reflect_value!(RangeToInclusive<T: Clone + Send + Sync + 'static>);
 impl_from_reflect_value!(RangeFull);
 impl_from_reflect_value!(Duration);
 impl_from_reflect_value!(Instant);
+impl_from_reflect_value!(Uuid);
 impl_from_reflect_value!(NonZeroI128);
 impl_from_reflect_value!(NonZeroU128);
 impl_from_reflect_value!(NonZeroIsize);
@@ -108,6 +108,18 @@ impl TypeRegistry {
             .insert(registration.type_id(), registration);
     }
 
+    /// Removes the registration of the type with the given [`TypeId`], and returns it.
+    ///
+    /// The short name of the type stays ambiguous if it was.
+    pub fn remove(&mut self, type_id: TypeId) -> Option<TypeRegistration> {
+        let registration = self.registrations.remove(&type_id)?;
+        if self.short_name_to_id.get(registration.short_name()) == Some(&type_id) {
+            self.short_name_to_id.remove(registration.short_name());
+        }
+        self.full_name_to_id.remove(registration.type_name());
+        Some(registration)
+    }
+
     /// Registers the type data `D` for type `T`.
     ///
     /// Most of the time [`TypeRegistry::register`] can be used instead to register a type you derived [`Reflect`] for.
@@ -536,7 +548,9 @@ impl<T: Reflect> FromType<T> for ReflectFromPtr {
 mod test {
     use std::ptr::NonNull;
 
-    use crate::{GetTypeRegistration, ReflectFromPtr, TypeRegistration};
+    use std::any::TypeId;
+
+    use crate::{GetTypeRegistration, ReflectFromPtr, TypeRegistration, TypeRegistry};
     use bevy_ptr::{Ptr, PtrMut};
     use bevy_utils::HashMap;
 
@@ -587,6 +601,26 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_remove() {
+        #[derive(Reflect)]
+        struct Foo;
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Foo>();
+        assert!(registry.get_with_short_name("Foo").is_some());
+
+        let registration = registry.remove(TypeId::of::<Foo>()).unwrap();
+        assert_eq!(registration.type_id(), TypeId::of::<Foo>());
+        assert!(registry.get(TypeId::of::<Foo>()).is_none());
+        assert!(registry.get_with_short_name("Foo").is_none());
+        assert!(registry.get_with_name(registration.type_name()).is_none());
+        assert!(registry.remove(TypeId::of::<Foo>()).is_none());
+
+        registry.register::<Foo>();
+        assert!(registry.get_with_short_name("Foo").is_some());
+    }
+
     #[test]
     fn test_property_type_registration() {
         assert_eq!(
@@ -0,0 +1,382 @@
+//! A remote debugging protocol for Bevy apps, to inspect and edit a running [`App`] from other
+//! processes, such as external inspectors or tests.
+//!
+//! The [`RemotePlugin`] serves [JSON-RPC 2.0](https://www.jsonrpc.org/specification) on a local
+//! TCP port. Each request and response is a single line of JSON:
+//!
+//! ```text
+//! --> {"jsonrpc": "2.0", "id": 1, "method": "bevy/get", "params": {"entity": 4294967296, "components": ["Transform.translation"]}}
+//! <-- {"jsonrpc": "2.0", "id": 1, "result": {"Transform.translation": [0.0, 1.0, 0.0]}}
+//! ```
+//!
+//! Entities are identified by their [`Entity::to_bits`](bevy_ecs::entity::Entity::to_bits).
+//! Components and resources are named by their full or short type name, optionally followed by
+//! a path in them like `"Transform.translation.x"`, as used by [`WorldInspector`]. Only types
+//! registered in the [`AppTypeRegistry`](bevy_app::AppTypeRegistry) with `#[reflect(Component)]`
+//! or `#[reflect(Resource)]` are available, and their values are written in the same format as
+//! in a serialized `DynamicScene`.
+//!
+//! | Method | Params | Result |
+//! |---|---|---|
+//! | `bevy/list` | | The entities, with their name, parent, children and component types |
+//! | `bevy/query` | `components`: paths | The entities with all the components, and their values |
+//! | `bevy/get` | `entity`, `components` (optional): paths | The values of the components of `entity`, or all of them |
+//! | `bevy/set` | `entity`, `path`, `value` | `null` |
+//! | `bevy/insert` | `entity`, `components`: values by type | `null` |
+//! | `bevy/remove` | `entity`, `components`: types | `null` |
+//! | `bevy/spawn` | `components` (optional): values by type | `{"entity": entity}` |
+//! | `bevy/despawn` | `entity` | `null` |
+//! | `bevy/get_resource` | `path` | The value |
+//! | `bevy/set_resource` | `path`, `value` | `null` |
+//! | `bevy/schedules` | | The [`ScheduleGraph`](bevy_ecs::schedule::ScheduleGraph) of each schedule, as `{"name", "graph"}` |
+//! | `bevy/subscribe_diagnostics` | | `true` |
+//! | `bevy/unsubscribe_diagnostics` | | Whether the client was subscribed |
+//!
+//! Clients subscribed to diagnostics receive a `bevy/diagnostics` notification each frame, with
+//! the `name`, `suffix`, `value` and `smoothed` value of each enabled diagnostic.
+//!
+//! Requests are handled at the end of each frame, in [`CoreStage::Last`].
+//!
+//! [`App`]: bevy_app::App
+//! [`WorldInspector`]: bevy_ecs::inspector::WorldInspector
+
+mod methods;
+mod server;
+
+pub use methods::*;
+pub use server::*;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use bevy_app::{App, CoreStage, Plugin, ScheduleGraphs};
+use bevy_ecs::schedule::IntoSystemDescriptor;
+use bevy_utils::tracing::{error, info};
+
+/// The port the [`RemotePlugin`] listens on by default.
+pub const DEFAULT_PORT: u16 = 15702;
+
+/// Serves the remote debugging protocol described in the [crate documentation](crate).
+///
+/// Anyone who can connect to the address can edit the app, so it should stay a local address.
+#[derive(Debug, Clone)]
+pub struct RemotePlugin {
+    /// The address to listen on. Defaults to `127.0.0.1:15702`. With port 0, the OS picks a free
+    /// port, available from [`RemoteServer::local_addr`].
+    pub address: SocketAddr,
+}
+
+impl Default for RemotePlugin {
+    fn default() -> Self {
+        Self {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_PORT)),
+        }
+    }
+}
+
+impl Plugin for RemotePlugin {
+    fn build(&self, app: &mut App) {
+        let server = match RemoteServer::bind(self.address) {
+            Ok(server) => server,
+            Err(err) => {
+                error!(
+                    "Failed to start the remote server on {}: {}",
+                    self.address, err
+                );
+                return;
+            }
+        };
+        info!("Remote server listening on {}", server.local_addr());
+        app.insert_resource(server)
+            .init_resource::<ScheduleGraphs>()
+            .add_system_to_stage(CoreStage::Last, process_remote_requests.at_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{Ipv4Addr, SocketAddr, TcpStream},
+        time::Duration,
+    };
+
+    use bevy_app::App;
+    use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+    use bevy_ecs::{prelude::*, reflect::ReflectResource};
+    use bevy_reflect::Reflect;
+    use serde_json::{json, Value};
+
+    use crate::{RemoteError, RemotePlugin, RemoteServer};
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(f32);
+
+    #[derive(Resource, Reflect, Default)]
+    #[reflect(Resource)]
+    struct Gravity(f32);
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+        next_id: u64,
+    }
+
+    impl Client {
+        fn connect(app: &App) -> Self {
+            let address = app.world.resource::<RemoteServer>().local_addr();
+            let stream = TcpStream::connect(address).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_millis(5)))
+                .unwrap();
+            Self {
+                reader: BufReader::new(stream.try_clone().unwrap()),
+                writer: stream,
+                next_id: 0,
+            }
+        }
+
+        /// Updates `app` until a message is received.
+        fn receive(&mut self, app: &mut App) -> Value {
+            let mut line = String::new();
+            for _ in 0..1000 {
+                app.update();
+                if self.reader.read_line(&mut line).is_ok() && line.ends_with('\n') {
+                    return serde_json::from_str(&line).unwrap();
+                }
+            }
+            panic!("no message received");
+        }
+
+        fn send(&mut self, message: &str) {
+            self.writer.write_all(message.as_bytes()).unwrap();
+            self.writer.write_all(b"\n").unwrap();
+        }
+
+        fn call(
+            &mut self,
+            app: &mut App,
+            method: &str,
+            params: Value,
+        ) -> Result<Value, RemoteError> {
+            self.next_id += 1;
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": self.next_id,
+                "method": method,
+                "params": params,
+            });
+            self.send(&request.to_string());
+            let mut response = self.receive(app);
+            assert_eq!(response["id"], self.next_id);
+            match response.get_mut("error") {
+                Some(error) => Err(RemoteError::new(
+                    error["code"].as_i64().unwrap(),
+                    error["message"].as_str().unwrap(),
+                )),
+                None => Ok(response["result"].take()),
+            }
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.register_type::<Position>()
+            .register_type::<Health>()
+            .register_type::<Gravity>()
+            .add_plugin(RemotePlugin {
+                address: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            });
+        app
+    }
+
+    #[test]
+    fn entities_and_components() {
+        let mut app = app();
+        let entity = app.world.spawn(Position { x: 1.0, y: 2.0 }).id().to_bits();
+        let mut client = Client::connect(&app);
+
+        let entities = client.call(&mut app, "bevy/list", Value::Null).unwrap();
+        assert_eq!(entities[0]["entity"], entity);
+        assert!(entities[0]["components"][0]
+            .as_str()
+            .unwrap()
+            .ends_with("Position"));
+
+        client
+            .call(
+                &mut app,
+                "bevy/set",
+                json!({ "entity": entity, "path": "Position.x", "value": 3.0 }),
+            )
+            .unwrap();
+        let components = client
+            .call(&mut app, "bevy/get", json!({ "entity": entity }))
+            .unwrap();
+        let (_, position) = components.as_object().unwrap().iter().next().unwrap();
+        assert_eq!(position, &json!({ "x": 3.0, "y": 2.0 }));
+
+        let spawned = client
+            .call(
+                &mut app,
+                "bevy/spawn",
+                json!({ "components": { "Position": { "x": 0.0, "y": 0.0 }, "Health": [10.0] } }),
+            )
+            .unwrap()["entity"]
+            .clone();
+        client
+            .call(
+                &mut app,
+                "bevy/insert",
+                json!({ "entity": entity, "components": { "Health": [5.0] } }),
+            )
+            .unwrap();
+        let healths = client
+            .call(
+                &mut app,
+                "bevy/query",
+                json!({ "components": ["Health.0", "Position.x"] }),
+            )
+            .unwrap();
+        assert_eq!(
+            healths,
+            json!([
+                { "entity": entity, "components": { "Health.0": 5.0, "Position.x": 3.0 } },
+                { "entity": spawned, "components": { "Health.0": 10.0, "Position.x": 0.0 } },
+            ])
+        );
+
+        client
+            .call(
+                &mut app,
+                "bevy/remove",
+                json!({ "entity": entity, "components": ["Health"] }),
+            )
+            .unwrap();
+        client
+            .call(&mut app, "bevy/despawn", json!({ "entity": spawned }))
+            .unwrap();
+        let error = client
+            .call(&mut app, "bevy/despawn", json!({ "entity": spawned }))
+            .unwrap_err();
+        assert_eq!(error.code, RemoteError::WORLD_ERROR);
+        let entity = Entity::from_bits(entity);
+        assert!(app.world.get::<Health>(entity).is_none());
+        assert_eq!(app.world.entities().len(), 1);
+    }
+
+    #[test]
+    fn resources_and_errors() {
+        let mut app = app();
+        app.insert_resource(Gravity(-9.8));
+        let mut client = Client::connect(&app);
+
+        client
+            .call(
+                &mut app,
+                "bevy/set_resource",
+                json!({ "path": "Gravity.0", "value": -1.0 }),
+            )
+            .unwrap();
+        let gravity = client
+            .call(&mut app, "bevy/get_resource", json!({ "path": "Gravity" }))
+            .unwrap();
+        assert_eq!(gravity, json!([-1.0]));
+        assert_eq!(app.world.resource::<Gravity>().0, -1.0);
+
+        let error = client
+            .call(
+                &mut app,
+                "bevy/set_resource",
+                json!({ "path": "Gravity.0" }),
+            )
+            .unwrap_err();
+        assert_eq!(error.code, RemoteError::INVALID_PARAMS);
+        let error = client
+            .call(
+                &mut app,
+                "bevy/set_resource",
+                json!({ "path": "Gravity.0", "value": "down" }),
+            )
+            .unwrap_err();
+        assert_eq!(error.code, RemoteError::INVALID_PARAMS);
+        let error = client
+            .call(&mut app, "bevy/get_resource", json!({ "path": "Wind" }))
+            .unwrap_err();
+        assert_eq!(error.code, RemoteError::WORLD_ERROR);
+        let error = client.call(&mut app, "bevy/fly", Value::Null).unwrap_err();
+        assert_eq!(error.code, RemoteError::METHOD_NOT_FOUND);
+
+        client.send("{");
+        assert_eq!(
+            client.receive(&mut app)["error"]["code"],
+            RemoteError::PARSE_ERROR
+        );
+    }
+
+    #[test]
+    fn release_port_on_drop() {
+        let server = RemoteServer::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = server.local_addr();
+        drop(server);
+        // The listener is released by its thread, once woken up.
+        let rebound = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            std::net::TcpListener::bind(address).is_ok()
+        });
+        assert!(rebound);
+    }
+
+    #[test]
+    fn disconnect_clients_on_drop() {
+        let server = RemoteServer::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        let accepted = (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            !server.clients().is_empty()
+        });
+        assert!(accepted);
+        drop(server);
+        stream
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        assert_eq!(stream.read(&mut [0; 1]).unwrap(), 0);
+    }
+
+    #[test]
+    fn schedules_and_diagnostics() {
+        fn some_system() {}
+
+        let id = DiagnosticId::default();
+        let mut diagnostics = Diagnostics::default();
+        diagnostics.add(Diagnostic::new(id, "frames", 10));
+        let mut app = app();
+        app.insert_resource(diagnostics).add_system(some_system);
+        let mut client = Client::connect(&app);
+
+        let schedules = client
+            .call(&mut app, "bevy/schedules", Value::Null)
+            .unwrap();
+        assert_eq!(schedules[0]["name"], "main");
+        assert!(schedules[0]["graph"].to_string().contains("some_system"));
+
+        app.world
+            .resource_mut::<Diagnostics>()
+            .add_measurement(id, || 60.0);
+        let subscribed = client
+            .call(&mut app, "bevy/subscribe_diagnostics", Value::Null)
+            .unwrap();
+        assert_eq!(subscribed, true);
+        let notification = client.receive(&mut app);
+        assert_eq!(notification["method"], "bevy/diagnostics");
+        assert_eq!(notification["params"][0]["name"], "frames");
+        assert_eq!(notification["params"][0]["value"], 60.0);
+    }
+}
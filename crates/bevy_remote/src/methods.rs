@@ -0,0 +1,495 @@
+use std::fmt;
+
+use bevy_app::{AppTypeRegistry, ScheduleGraphs};
+use bevy_diagnostic::Diagnostics;
+use bevy_ecs::{
+    entity::Entity,
+    inspector::{self, InspectorError, WorldInspector},
+    reflect::ReflectComponent,
+    world::{Mut, World},
+};
+use bevy_reflect::{
+    serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    Reflect, TypeRegistration, TypeRegistry,
+};
+use serde::{de::DeserializeSeed, Deserialize};
+use serde_json::{json, Map, Value};
+
+use crate::{ClientId, RemoteServer};
+
+/// An error answered to a request, with its JSON-RPC error code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RemoteError {
+    /// The message isn't valid JSON.
+    pub const PARSE_ERROR: i64 = -32700;
+    /// The message isn't a JSON-RPC 2.0 request.
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The request couldn't be applied to the world, for example because an entity doesn't exist.
+    pub const WORLD_ERROR: i64 = -32000;
+
+    pub fn new(code: i64, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<InspectorError> for RemoteError {
+    fn from(err: InspectorError) -> Self {
+        RemoteError::new(RemoteError::WORLD_ERROR, err)
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Handles the messages received by the [`RemoteServer`], and sends the diagnostics to the clients
+/// that subscribed to them.
+pub fn process_remote_requests(world: &mut World) {
+    world.resource_scope(|world, mut server: Mut<RemoteServer>| {
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        if !server.schedule_requests.is_empty() {
+            if let Some(graphs) = world.resource_mut::<ScheduleGraphs>().take() {
+                let graphs = schedules(graphs);
+                for (client, id) in std::mem::take(&mut server.schedule_requests) {
+                    server.send(client, response(id, Ok(graphs.clone())).to_string());
+                }
+            }
+        }
+
+        while let Some((client, message)) = server.try_recv() {
+            if let Some(response) = handle_message(world, &registry, &mut server, client, &message)
+            {
+                server.send(client, response.to_string());
+            }
+        }
+
+        if !server.diagnostic_subscribers.is_empty() {
+            if let Some(diagnostics) = world.get_resource::<Diagnostics>() {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "bevy/diagnostics",
+                    "params": diagnostics_json(diagnostics),
+                })
+                .to_string();
+                let subscribers = std::mem::take(&mut server.diagnostic_subscribers);
+                server.diagnostic_subscribers = subscribers
+                    .into_iter()
+                    .filter(|client| server.send(*client, notification.clone()))
+                    .collect();
+            }
+        }
+    });
+}
+
+/// Handles a message from `client`, returning the response to send back, if any.
+fn handle_message(
+    world: &mut World,
+    registry: &TypeRegistry,
+    server: &mut RemoteServer,
+    client: ClientId,
+    message: &str,
+) -> Option<Value> {
+    let request = match serde_json::from_str::<Value>(message) {
+        Ok(request) => request,
+        Err(err) => {
+            let error = RemoteError::new(RemoteError::PARSE_ERROR, err);
+            return Some(response(Value::Null, Err(error)));
+        }
+    };
+    let request = match serde_json::from_value::<Request>(request) {
+        Ok(request) if request.jsonrpc == "2.0" => request,
+        Ok(_) => {
+            let error = RemoteError::new(RemoteError::INVALID_REQUEST, "expected JSON-RPC 2.0");
+            return Some(response(Value::Null, Err(error)));
+        }
+        Err(err) => {
+            let error = RemoteError::new(RemoteError::INVALID_REQUEST, err);
+            return Some(response(Value::Null, Err(error)));
+        }
+    };
+
+    let params = request.params;
+    let result = match request.method.as_str() {
+        "bevy/list" => Ok(list(world, registry)),
+        "bevy/query" => parse(params).and_then(|params| query(world, registry, params)),
+        "bevy/get" => parse(params).and_then(|params| get(world, registry, params)),
+        "bevy/set" => parse(params).and_then(|params| set(world, registry, params)),
+        "bevy/insert" => parse(params).and_then(|params| insert(world, registry, params)),
+        "bevy/remove" => parse(params).and_then(|params| remove(world, registry, params)),
+        "bevy/spawn" => parse(params).and_then(|params| spawn(world, registry, params)),
+        "bevy/despawn" => parse(params).and_then(|params| despawn(world, params)),
+        "bevy/get_resource" => {
+            parse(params).and_then(|params| get_resource(world, registry, params))
+        }
+        "bevy/set_resource" => {
+            parse(params).and_then(|params| set_resource(world, registry, params))
+        }
+        "bevy/schedules" => {
+            // The graphs are written by the app at the end of the update, and sent on the next one.
+            if let Some(id) = request.id {
+                world.resource_mut::<ScheduleGraphs>().request();
+                server.schedule_requests.push((client, id));
+            }
+            return None;
+        }
+        "bevy/subscribe_diagnostics" => {
+            server.diagnostic_subscribers.insert(client);
+            Ok(Value::Bool(true))
+        }
+        "bevy/unsubscribe_diagnostics" => {
+            Ok(Value::Bool(server.diagnostic_subscribers.remove(&client)))
+        }
+        method => Err(RemoteError::new(
+            RemoteError::METHOD_NOT_FOUND,
+            format!("unknown method `{}`", method),
+        )),
+    };
+    // Notifications, requests without an id, aren't answered.
+    request.id.map(|id| response(id, result))
+}
+
+fn response(id: Value, result: Result<Value, RemoteError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+fn parse<'de, P: Deserialize<'de>>(params: Value) -> Result<P, RemoteError> {
+    // Omitted params are parsed as an empty object, for methods whose params are all optional.
+    let params = match params {
+        Value::Null => Value::Object(Map::new()),
+        params => params,
+    };
+    P::deserialize(params).map_err(|err| RemoteError::new(RemoteError::INVALID_PARAMS, err))
+}
+
+#[derive(Deserialize)]
+struct QueryParams {
+    components: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GetParams {
+    entity: u64,
+    components: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct SetParams {
+    entity: u64,
+    path: String,
+    value: Value,
+}
+
+#[derive(Deserialize)]
+struct InsertParams {
+    entity: u64,
+    components: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RemoveParams {
+    entity: u64,
+    components: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SpawnParams {
+    #[serde(default)]
+    components: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct DespawnParams {
+    entity: u64,
+}
+
+#[derive(Deserialize)]
+struct GetResourceParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct SetResourceParams {
+    path: String,
+    value: Value,
+}
+
+fn list(world: &World, registry: &TypeRegistry) -> Value {
+    let inspector = WorldInspector::new(world, registry);
+    let entities = inspector
+        .entities()
+        .into_iter()
+        .map(|entity| {
+            let components: Vec<_> = entity
+                .components
+                .iter()
+                .map(|component| &component.type_name)
+                .collect();
+            json!({
+                "entity": entity.entity.to_bits(),
+                "name": entity.name,
+                "parent": entity.parent.map(Entity::to_bits),
+                "children": entity.children.iter().map(|child| child.to_bits()).collect::<Vec<_>>(),
+                "components": components,
+            })
+        })
+        .collect();
+    Value::Array(entities)
+}
+
+fn query(
+    world: &World,
+    registry: &TypeRegistry,
+    params: QueryParams,
+) -> Result<Value, RemoteError> {
+    let inspector = WorldInspector::new(world, registry);
+    let mut entities = Vec::new();
+    'entities: for entity in inspector.entity_ids() {
+        let mut components = Map::new();
+        for path in &params.components {
+            match inspector.component(entity, path) {
+                Ok(value) => {
+                    components.insert(path.clone(), serialize(value, registry)?);
+                }
+                Err(InspectorError::MissingComponent { .. }) => continue 'entities,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        entities.push(json!({ "entity": entity.to_bits(), "components": components }));
+    }
+    Ok(Value::Array(entities))
+}
+
+fn get(world: &World, registry: &TypeRegistry, params: GetParams) -> Result<Value, RemoteError> {
+    let entity = Entity::from_bits(params.entity);
+    let inspector = WorldInspector::new(world, registry);
+    let mut components = Map::new();
+    match params.components {
+        Some(paths) => {
+            for path in paths {
+                let value = inspector.component(entity, &path)?;
+                components.insert(path, serialize(value, registry)?);
+            }
+        }
+        None => {
+            let inspected = inspector
+                .entity(entity)
+                .ok_or(InspectorError::NoSuchEntity(entity))?;
+            for component in inspected.components {
+                if let Some(value) = component.value {
+                    components.insert(component.type_name.into(), serialize(value, registry)?);
+                }
+            }
+        }
+    }
+    Ok(Value::Object(components))
+}
+
+fn set(
+    world: &mut World,
+    registry: &TypeRegistry,
+    params: SetParams,
+) -> Result<Value, RemoteError> {
+    let entity = Entity::from_bits(params.entity);
+    let field = WorldInspector::new(world, registry).component(entity, &params.path)?;
+    let value = deserialize(
+        registration(registry, field.type_name())?,
+        registry,
+        params.value,
+    )?;
+    inspector::edit_component(world, registry, entity, &params.path, &*value)?;
+    Ok(Value::Null)
+}
+
+fn insert(
+    world: &mut World,
+    registry: &TypeRegistry,
+    params: InsertParams,
+) -> Result<Value, RemoteError> {
+    let entity = Entity::from_bits(params.entity);
+    if !world.entities().contains(entity) {
+        return Err(InspectorError::NoSuchEntity(entity).into());
+    }
+    insert_components(world, registry, entity, params.components)?;
+    Ok(Value::Null)
+}
+
+fn remove(
+    world: &mut World,
+    registry: &TypeRegistry,
+    params: RemoveParams,
+) -> Result<Value, RemoteError> {
+    let entity = Entity::from_bits(params.entity);
+    if !world.entities().contains(entity) {
+        return Err(InspectorError::NoSuchEntity(entity).into());
+    }
+    let components = params
+        .components
+        .iter()
+        .map(|name| reflect_component(registration(registry, name)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    for component in components {
+        component.remove(world, entity);
+    }
+    Ok(Value::Null)
+}
+
+fn spawn(
+    world: &mut World,
+    registry: &TypeRegistry,
+    params: SpawnParams,
+) -> Result<Value, RemoteError> {
+    let entity = world.spawn_empty().id();
+    if let Err(err) = insert_components(world, registry, entity, params.components) {
+        world.despawn(entity);
+        return Err(err);
+    }
+    Ok(json!({ "entity": entity.to_bits() }))
+}
+
+fn despawn(world: &mut World, params: DespawnParams) -> Result<Value, RemoteError> {
+    let entity = Entity::from_bits(params.entity);
+    if !world.despawn(entity) {
+        return Err(InspectorError::NoSuchEntity(entity).into());
+    }
+    Ok(Value::Null)
+}
+
+fn get_resource(
+    world: &World,
+    registry: &TypeRegistry,
+    params: GetResourceParams,
+) -> Result<Value, RemoteError> {
+    let value = WorldInspector::new(world, registry).resource(&params.path)?;
+    serialize(value, registry)
+}
+
+fn set_resource(
+    world: &mut World,
+    registry: &TypeRegistry,
+    params: SetResourceParams,
+) -> Result<Value, RemoteError> {
+    let field = WorldInspector::new(world, registry).resource(&params.path)?;
+    let value = deserialize(
+        registration(registry, field.type_name())?,
+        registry,
+        params.value,
+    )?;
+    inspector::edit_resource(world, registry, &params.path, &*value)?;
+    Ok(Value::Null)
+}
+
+fn schedules(graphs: Vec<(String, bevy_ecs::schedule::ScheduleGraph)>) -> Value {
+    let schedules = graphs
+        .into_iter()
+        .map(|(name, graph)| {
+            // The graph is written in JSON by `ScheduleGraph::to_json`, which always succeeds.
+            let graph: Value = serde_json::from_str(&graph.to_json()).unwrap_or_default();
+            json!({ "name": name, "graph": graph })
+        })
+        .collect();
+    Value::Array(schedules)
+}
+
+fn diagnostics_json(diagnostics: &Diagnostics) -> Value {
+    let diagnostics = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_enabled)
+        .map(|diagnostic| {
+            json!({
+                "name": diagnostic.name,
+                "suffix": diagnostic.suffix,
+                "value": diagnostic.value(),
+                "smoothed": diagnostic.smoothed(),
+            })
+        })
+        .collect();
+    Value::Array(diagnostics)
+}
+
+/// Inserts the components of `components`, by type name, into `entity`. Nothing is inserted if one
+/// of them can't be deserialized.
+fn insert_components(
+    world: &mut World,
+    registry: &TypeRegistry,
+    entity: Entity,
+    components: Map<String, Value>,
+) -> Result<(), RemoteError> {
+    let components = components
+        .into_iter()
+        .map(|(name, value)| {
+            let registration = registration(registry, &name)?;
+            let reflect_component = reflect_component(registration)?;
+            Ok((
+                reflect_component,
+                deserialize(registration, registry, value)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, RemoteError>>()?;
+    for (reflect_component, component) in components {
+        reflect_component.apply_or_insert(world, entity, &*component);
+    }
+    Ok(())
+}
+
+fn registration<'r>(
+    registry: &'r TypeRegistry,
+    name: &str,
+) -> Result<&'r TypeRegistration, RemoteError> {
+    registry
+        .get_with_name(name)
+        .or_else(|| registry.get_with_short_name(name))
+        .ok_or_else(|| InspectorError::UnknownType(name.to_string()).into())
+}
+
+fn reflect_component(registration: &TypeRegistration) -> Result<&ReflectComponent, RemoteError> {
+    registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| InspectorError::NotAComponent(registration.type_name()).into())
+}
+
+fn serialize(value: &dyn Reflect, registry: &TypeRegistry) -> Result<Value, RemoteError> {
+    serde_json::to_value(TypedReflectSerializer::new(value, registry))
+        .map_err(|err| RemoteError::new(RemoteError::WORLD_ERROR, err))
+}
+
+fn deserialize(
+    registration: &TypeRegistration,
+    registry: &TypeRegistry,
+    value: Value,
+) -> Result<Box<dyn Reflect>, RemoteError> {
+    TypedReflectDeserializer::new(registration, registry)
+        .deserialize(value)
+        .map_err(|err| RemoteError::new(RemoteError::INVALID_PARAMS, err))
+}
@@ -0,0 +1,197 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    thread,
+};
+
+use bevy_ecs::system::Resource;
+use bevy_utils::{tracing::debug, HashMap, HashSet};
+use crossbeam_channel::{Receiver, Sender};
+use serde_json::Value;
+
+/// Identifies a client connected to a [`RemoteServer`].
+pub type ClientId = u64;
+
+/// The connected clients.
+type Clients = Arc<Mutex<HashMap<ClientId, Client>>>;
+
+struct Client {
+    /// Sends the messages written to the client.
+    sender: Sender<String>,
+    /// The socket of the client, shut down when the server is dropped to stop its threads.
+    stream: TcpStream,
+}
+
+/// The connections of the [`RemotePlugin`](crate::RemotePlugin) server.
+///
+/// Clients are accepted, read and written on background threads: each line they send is a
+/// message, queued until the [`App`](bevy_app::App) handles it.
+#[derive(Resource)]
+pub struct RemoteServer {
+    local_addr: SocketAddr,
+    messages: Receiver<(ClientId, String)>,
+    clients: Clients,
+    /// Set when the server is dropped, to stop accepting clients. Checked with the clients locked.
+    shutdown: Arc<AtomicBool>,
+    /// The `bevy/schedules` requests waiting for the app to write its [`ScheduleGraphs`].
+    ///
+    /// [`ScheduleGraphs`]: bevy_app::ScheduleGraphs
+    pub(crate) schedule_requests: Vec<(ClientId, Value)>,
+    pub(crate) diagnostic_subscribers: HashSet<ClientId>,
+}
+
+impl RemoteServer {
+    /// Starts listening for clients on `address`.
+    pub fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let local_addr = listener.local_addr()?;
+        let (message_sender, messages) = crossbeam_channel::unbounded();
+        let clients = Clients::default();
+
+        // The client threads only hold weak references to the clients, to stop once the server is
+        // dropped. The listener thread is woken up by a connection when the server is dropped.
+        let weak_clients = Arc::downgrade(&clients);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let listener_shutdown = shutdown.clone();
+        thread::Builder::new()
+            .name("remote server".to_string())
+            .spawn(move || {
+                for (id, stream) in (0..).zip(listener.incoming()) {
+                    if listener_shutdown.load(Ordering::Acquire) {
+                        break;
+                    }
+                    let result = stream.and_then(|stream| {
+                        accept(
+                            stream,
+                            id,
+                            message_sender.clone(),
+                            &weak_clients,
+                            &listener_shutdown,
+                        )
+                    });
+                    if let Err(err) = result {
+                        debug!("failed to accept remote client: {}", err);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            local_addr,
+            messages,
+            clients,
+            shutdown,
+            schedule_requests: Vec::new(),
+            diagnostic_subscribers: HashSet::default(),
+        })
+    }
+
+    /// The address the server listens on, with the port chosen by the OS when binding to port 0.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The clients currently connected.
+    pub fn clients(&self) -> Vec<ClientId> {
+        self.clients.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Takes the next message received from a client, if any.
+    pub fn try_recv(&self) -> Option<(ClientId, String)> {
+        self.messages.try_recv().ok()
+    }
+
+    /// Queues `message` to be written to `client` as a line. Returns `false` if the client is
+    /// disconnected.
+    pub fn send(&self, client: ClientId, message: String) -> bool {
+        match self.clients.lock().unwrap().get(&client) {
+            Some(client) => client.sender.send(message).is_ok(),
+            None => false,
+        }
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        // Wake up the listener thread, so that it stops and releases the port.
+        let mut address = self.local_addr;
+        if address.ip().is_unspecified() {
+            address.set_ip(match address {
+                SocketAddr::V4(_) => Ipv4Addr::LOCALHOST.into(),
+                SocketAddr::V6(_) => Ipv6Addr::LOCALHOST.into(),
+            });
+        }
+        if let Err(err) = TcpStream::connect(address) {
+            debug!("failed to stop the remote server listener: {}", err);
+        }
+
+        // Stop the client threads: readers see the end of their stream, and writers the end of
+        // their messages.
+        for (_, client) in self.clients.lock().unwrap().drain() {
+            // The client may already have disconnected.
+            let _ = client.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+fn accept(
+    stream: TcpStream,
+    id: ClientId,
+    messages: Sender<(ClientId, String)>,
+    clients: &Weak<Mutex<HashMap<ClientId, Client>>>,
+    shutdown: &AtomicBool,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let client_stream = stream.try_clone()?;
+    let (sender, outgoing) = crossbeam_channel::unbounded::<String>();
+    match clients.upgrade() {
+        Some(clients) => {
+            let mut clients = clients.lock().unwrap();
+            if shutdown.load(Ordering::Acquire) {
+                return Ok(());
+            }
+            clients.insert(
+                id,
+                Client {
+                    sender,
+                    stream: client_stream,
+                },
+            );
+        }
+        None => return Ok(()),
+    };
+
+    thread::Builder::new()
+        .name(format!("remote client {} writer", id))
+        .spawn(move || {
+            for mut message in outgoing {
+                message.push('\n');
+                if writer.write_all(message.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+    let clients = clients.clone();
+    thread::Builder::new()
+        .name(format!("remote client {} reader", id))
+        .spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if messages.send((id, line)).is_err() {
+                    break;
+                }
+            }
+            // Dropping the sender stops the writer thread.
+            if let Some(clients) = clients.upgrade() {
+                clients.lock().unwrap().remove(&id);
+            }
+        })?;
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use crate::{
+    camera::{Camera, RenderTarget},
+    render_asset::RenderAssets,
+    render_resource::{
+        BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+        ImageDataLayout, MapMode, TextureDimension, TextureFormat, TextureUsages,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    texture::{BevyDefault, Image},
+    Extract, RenderApp, RenderStage,
+};
+use bevy_app::{ci_testing::CiTestingAction, App, Plugin};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_utils::tracing::{error, info, warn};
+use bevy_window::{WindowId, Windows};
+use std::num::NonZeroU32;
+
+/// Handles [`CiTestingAction::Screenshot`] by rendering the cameras that target the primary
+/// window to an offscreen [`Image`] for one frame, and saving it once rendered.
+pub struct CiTestingScreenshotPlugin;
+
+impl Plugin for CiTestingScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Events<CiTestingAction>>() {
+            return;
+        }
+
+        app.init_resource::<CiTestingScreenshots>()
+            .add_system_to_stage(bevy_app::CoreStage::First, request_screenshots);
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ExtractedCiTestingScreenshots>()
+                .add_system_to_stage(RenderStage::Extract, extract_screenshots)
+                .add_system_to_stage(RenderStage::Cleanup, save_screenshots);
+        }
+    }
+}
+
+/// The screenshots of the current frame.
+#[derive(Resource, Default)]
+struct CiTestingScreenshots {
+    /// The offscreen image the screenshots are rendered to.
+    image: Option<Handle<Image>>,
+    /// The paths to save the screenshots at.
+    paths: Vec<String>,
+    /// The cameras rendering to the image, with their original targets.
+    redirected: Vec<(Entity, RenderTarget)>,
+}
+
+#[derive(Resource, Default)]
+struct ExtractedCiTestingScreenshots {
+    image: Option<Handle<Image>>,
+    paths: Vec<String>,
+}
+
+fn request_screenshots(
+    mut actions: EventReader<CiTestingAction>,
+    mut screenshots: ResMut<CiTestingScreenshots>,
+    mut cameras: Query<(Entity, &mut Camera)>,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    // Restore the cameras redirected during the previous frame.
+    let screenshots = &mut *screenshots;
+    for (entity, target) in screenshots.redirected.drain(..) {
+        if let Ok((_, mut camera)) = cameras.get_mut(entity) {
+            camera.target = target;
+        }
+    }
+    if let Some(image) = screenshots.image.take() {
+        images.remove(image);
+    }
+    screenshots.paths.clear();
+
+    for action in actions.iter() {
+        if let CiTestingAction::Screenshot(path) = action {
+            screenshots.paths.push(path.clone());
+        }
+    }
+    if screenshots.paths.is_empty() {
+        return;
+    }
+
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => {
+            warn!("Cannot take a CI testing screenshot without a primary window");
+            screenshots.paths.clear();
+            return;
+        }
+    };
+    let size = Extent3d {
+        width: window.physical_width(),
+        height: window.physical_height(),
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+    );
+    image.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+    let image = images.add(image);
+
+    let window_target = RenderTarget::Window(WindowId::primary());
+    for (entity, mut camera) in &mut cameras {
+        if camera.target == window_target {
+            let target = std::mem::replace(&mut camera.target, RenderTarget::Image(image.clone()));
+            screenshots.redirected.push((entity, target));
+        }
+    }
+    if screenshots.redirected.is_empty() {
+        warn!("Cannot take a CI testing screenshot without a camera targeting the primary window");
+        screenshots.paths.clear();
+        images.remove(image);
+        return;
+    }
+    screenshots.image = Some(image);
+}
+
+fn extract_screenshots(
+    mut extracted: ResMut<ExtractedCiTestingScreenshots>,
+    screenshots: Extract<Res<CiTestingScreenshots>>,
+) {
+    extracted.image = screenshots.image.as_ref().map(Handle::clone_weak);
+    extracted.paths.clone_from(&screenshots.paths);
+}
+
+fn save_screenshots(
+    screenshots: Res<ExtractedCiTestingScreenshots>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let image = match &screenshots.image {
+        Some(image) => image,
+        None => return,
+    };
+    let gpu_image = match images.get(image) {
+        Some(gpu_image) => gpu_image,
+        None => {
+            warn!(
+                "CI testing screenshots {:?} were skipped, because their image wasn't ready",
+                screenshots.paths
+            );
+            return;
+        }
+    };
+
+    let width = gpu_image.size.x as u32;
+    let height = gpu_image.size.y as u32;
+    let row_bytes = width as usize * 4;
+    let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("ci_testing_screenshot_buffer"),
+        size: (padded_row_bytes * height as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("ci_testing_screenshot_encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_row_bytes as u32),
+                rows_per_image: None,
+            },
+        },
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    render_queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    render_device.map_buffer(&slice, MapMode::Read, |_| {});
+    render_device.poll(wgpu::Maintain::Wait);
+    let mut pixels = Vec::with_capacity(row_bytes * height as usize);
+    for row in slice.get_mapped_range().chunks(padded_row_bytes) {
+        pixels.extend_from_slice(&row[..row_bytes]);
+    }
+    buffer.unmap();
+
+    for path in &screenshots.paths {
+        match image::save_buffer(path, &pixels, width, height, image::ColorType::Rgba8) {
+            Ok(()) => info!("Saved CI testing screenshot to {}", path),
+            Err(err) => error!("Could not save CI testing screenshot to {}: {}", path, err),
+        }
+    }
+}
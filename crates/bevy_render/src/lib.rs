@@ -1,6 +1,8 @@
 extern crate core;
 
 pub mod camera;
+#[cfg(feature = "bevy_ci_testing")]
+pub mod ci_testing;
 pub mod color;
 pub mod extract_component;
 mod extract_param;
@@ -370,6 +372,9 @@ impl Plugin for RenderPlugin {
             .add_plugin(GlobalsPlugin)
             .add_plugin(FrameCountPlugin);
 
+        #[cfg(feature = "bevy_ci_testing")]
+        app.add_plugin(ci_testing::CiTestingScreenshotPlugin);
+
         app.register_type::<color::Color>()
             .register_type::<primitives::Aabb>()
             .register_type::<primitives::CubemapFrusta>()
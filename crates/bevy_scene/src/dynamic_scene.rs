@@ -1,12 +1,18 @@
-use crate::{serde::SceneSerializer, DynamicSceneBuilder, Scene, SceneSpawnError};
+use std::collections::BTreeMap;
+
+use crate::{
+    find_persistent_entity, serde::SceneSerializer, DynamicSceneBuilder, PersistentId, Scene,
+    SceneSpawnError,
+};
 use anyhow::Result;
 use bevy_app::AppTypeRegistry;
 use bevy_ecs::{
-    entity::EntityMap,
+    entity::{Entity, EntityMap, MapEntitiesError},
     reflect::{ReflectComponent, ReflectMapEntities},
     world::World,
 };
-use bevy_reflect::{Reflect, TypeRegistryArc, TypeUuid};
+use bevy_reflect::{Reflect, TypeRegistry, TypeRegistryArc, TypeUuid};
+use bevy_utils::HashSet;
 use serde::Serialize;
 
 /// A collection of serializable dynamic entities, each with its own run-time defined set of components.
@@ -16,16 +22,48 @@ use serde::Serialize;
 /// * adding the [`Handle<DynamicScene>`](bevy_asset::Handle) to an entity (the scene will only be
 /// visible if the entity already has [`Transform`](bevy_transform::components::Transform) and
 /// [`GlobalTransform`](bevy_transform::components::GlobalTransform) components)
+///
+/// # Persistent ids
+///
+/// The entities of the scene are identified by the [`Entity`] they were extracted from, and so are
+/// the entities referenced by their components. These identifiers are only meaningful for the
+/// world the scene was extracted from: when the scene is written to a world, they are mapped to
+/// the entities spawned for the scene.
+///
+/// Entities with a [`PersistentId`] are also identified by it in [`persistent_ids`]. When the
+/// scene is written to a world that already has an entity with the same persistent id, the
+/// scene entity is written to it instead of spawning a new one, and references to entities outside
+/// of the scene are mapped to the entities with their persistent id.
+///
+/// Components keep referencing entities by [`Entity`], so that their own
+/// [`MapEntities`](bevy_ecs::entity::MapEntities) implementation can map them, and the side table
+/// gives the persistent id behind each of these identifiers. This is equivalent to writing the
+/// persistent ids in the components: an identifier is only looked up in the table, never in the
+/// world, and each one stands for a single entity of the saved world, since [`Entity::to_bits`]
+/// includes the generation.
+///
+/// [`DynamicEntity::entity`] used to be the `u32` [`Entity::index`] of the entity, and is now the
+/// `u64` [`Entity::to_bits`]. Scenes saved with indices still load as before, since the bits of an
+/// index alone are those of its entity with generation 0.
+///
+/// [`persistent_ids`]: DynamicScene::persistent_ids
 #[derive(Default, TypeUuid)]
 #[uuid = "749479b1-fb8c-4ff8-a775-623aa76014f5"]
 pub struct DynamicScene {
     pub entities: Vec<DynamicEntity>,
+    /// The [`PersistentId`]s of the entities of the scene, and of the entities outside of the
+    /// scene that it references, by their [`DynamicEntity::entity`] identifier.
+    pub persistent_ids: BTreeMap<u64, PersistentId>,
 }
 
+/// A component of a scene mapped to the world, with the entity it is written to.
+type MappedComponent<'r> = (Entity, &'r ReflectComponent, Box<dyn Reflect>);
+
 /// A reflection-powered serializable representation of an entity and its components.
 pub struct DynamicEntity {
-    /// The transiently unique identifier of a corresponding `Entity`.
-    pub entity: u32,
+    /// The identifier of the entity in the scene, which is the [`Entity::to_bits`] of the entity
+    /// it was extracted from.
+    pub entity: u64,
     /// A vector of boxed components that belong to the given entity and
     /// implement the `Reflect` trait.
     pub components: Vec<Box<dyn Reflect>>,
@@ -51,7 +89,14 @@ impl DynamicScene {
     ///
     /// This method will return a [`SceneSpawnError`] if a type either is not registered
     /// in the provided [`AppTypeRegistry`] resource, or doesn't reflect the
-    /// [`Component`](bevy_ecs::component::Component) trait.
+    /// [`Component`](bevy_ecs::component::Component) trait, or if the scene references an entity
+    /// that is neither in the scene nor in the world by its [`PersistentId`]. Nothing is written
+    /// to the world if an error is returned.
+    ///
+    /// The entities referenced by the components that reflect
+    /// [`MapEntities`](bevy_ecs::entity::MapEntities) are mapped to the entities of the world by
+    /// their implementation, which decides what happens to the entities that can't be mapped.
+    /// Components already in the world are only mapped where the scene overwrites them.
     pub fn write_to_world_with(
         &self,
         world: &mut World,
@@ -60,15 +105,76 @@ impl DynamicScene {
     ) -> Result<(), SceneSpawnError> {
         let type_registry = type_registry.read();
 
+        // References to entities outside of the scene are only mapped, and not added to
+        // `entity_map`, which only contains the entities written by the scene.
+        let scene_entities: HashSet<u64> =
+            self.entities.iter().map(|entity| entity.entity).collect();
+        let mut references = EntityMap::default();
+        for (&scene_entity, &id) in &self.persistent_ids {
+            if !scene_entities.contains(&scene_entity) {
+                let entity = find_persistent_entity(world, id)
+                    .ok_or(SceneSpawnError::UnresolvedPersistentId { id })?;
+                references.insert(Entity::from_bits(scene_entity), entity);
+            }
+        }
+
+        let mut entities = Vec::with_capacity(self.entities.len());
+        let mut spawned_entities = Vec::new();
         for scene_entity in &self.entities {
-            // Fetch the entity with the given entity id from the `entity_map`
-            // or spawn a new entity with a transiently unique id if there is
-            // no corresponding entry.
-            let entity = *entity_map
-                .entry(bevy_ecs::entity::Entity::from_raw(scene_entity.entity))
-                .or_insert_with(|| world.spawn_empty().id());
-
-            // Apply/ add each component to the given entity.
+            // Fetch the entity with the given entity id from the `entity_map`, or the entity with
+            // the same persistent id, or spawn a new entity with a transiently unique id if there
+            // is none.
+            let key = Entity::from_bits(scene_entity.entity);
+            let entity = entity_map
+                .get(key)
+                .ok()
+                .or_else(|| {
+                    let id = self.persistent_ids.get(&scene_entity.entity)?;
+                    find_persistent_entity(world, *id)
+                })
+                .unwrap_or_else(|| {
+                    let entity = world.spawn_empty().id();
+                    spawned_entities.push(entity);
+                    entity
+                });
+            references.insert(key, entity);
+            entities.push(entity);
+        }
+
+        let components = match self.map_components(world, &entities, &references, &type_registry) {
+            Ok(components) => components,
+            Err(err) => {
+                for entity in spawned_entities {
+                    world.despawn(entity);
+                }
+                return Err(err);
+            }
+        };
+
+        for (scene_entity, &entity) in self.entities.iter().zip(&entities) {
+            entity_map.insert(Entity::from_bits(scene_entity.entity), entity);
+        }
+        for (entity, reflect_component, component) in components {
+            // If the entity already has the given component attached,
+            // just apply the (possibly) new value, otherwise add the
+            // component to the entity.
+            reflect_component.apply_or_insert(world, entity, &*component);
+        }
+
+        Ok(())
+    }
+
+    /// Clones the components of the scene, mapping the entities they reference with `references`
+    /// through their [`ReflectMapEntities`], and pairs them with the entity they are written to.
+    fn map_components<'r>(
+        &self,
+        world: &mut World,
+        entities: &[Entity],
+        references: &EntityMap,
+        type_registry: &'r TypeRegistry,
+    ) -> Result<Vec<MappedComponent<'r>>, SceneSpawnError> {
+        let mut components = Vec::new();
+        for (scene_entity, &entity) in self.entities.iter().zip(entities) {
             for component in &scene_entity.components {
                 let registration = type_registry
                     .get_with_name(component.type_name())
@@ -82,22 +188,18 @@ impl DynamicScene {
                         }
                     })?;
 
-                // If the entity already has the given component attached,
-                // just apply the (possibly) new value, otherwise add the
-                // component to the entity.
-                reflect_component.apply_or_insert(world, entity, &**component);
-            }
-        }
-
-        for registration in type_registry.iter() {
-            if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                map_entities_reflect
-                    .map_entities(world, entity_map)
-                    .unwrap();
+                let component = match registration.data::<ReflectMapEntities>() {
+                    Some(map_entities) => map_entities
+                        .map_value(world, &**component, references)
+                        .map_err(|MapEntitiesError::EntityNotFound(entity)| {
+                        SceneSpawnError::UnresolvedEntity { entity }
+                    })?,
+                    None => component.clone_value(),
+                };
+                components.push((entity, reflect_component, component));
             }
         }
-
-        Ok(())
+        Ok(components)
     }
 
     /// Write the dynamic entities and their corresponding components to the given world.
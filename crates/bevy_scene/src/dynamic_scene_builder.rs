@@ -1,6 +1,10 @@
-use crate::{DynamicEntity, DynamicScene};
+use crate::{visit_entities, DynamicEntity, DynamicScene, PersistentId};
 use bevy_app::AppTypeRegistry;
-use bevy_ecs::{prelude::Entity, reflect::ReflectComponent, world::World};
+use bevy_ecs::{
+    prelude::Entity,
+    reflect::{ReflectComponent, ReflectMapEntities},
+    world::World,
+};
 use bevy_utils::default;
 use std::collections::BTreeMap;
 
@@ -12,6 +16,11 @@ use std::collections::BTreeMap;
 /// This means that inserting `Entity(1v0)` then `Entity(0v0)` will always result in the entities
 /// being ordered as `[Entity(0v0), Entity(1v0)]`.
 ///
+/// # Persistent ids
+///
+/// The [`PersistentId`]s of the extracted entities, and of the entities their components
+/// reference, are stored in [`DynamicScene::persistent_ids`].
+///
 /// # Example
 /// ```
 /// # use bevy_scene::DynamicSceneBuilder;
@@ -32,6 +41,7 @@ use std::collections::BTreeMap;
 /// ```
 pub struct DynamicSceneBuilder<'w> {
     entities: BTreeMap<u32, DynamicEntity>,
+    persistent_ids: BTreeMap<u64, PersistentId>,
     type_registry: AppTypeRegistry,
     world: &'w World,
 }
@@ -42,6 +52,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn from_world(world: &'w World) -> Self {
         Self {
             entities: default(),
+            persistent_ids: default(),
             type_registry: world.resource::<AppTypeRegistry>().clone(),
             world,
         }
@@ -52,6 +63,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn from_world_with_type_registry(world: &'w World, type_registry: AppTypeRegistry) -> Self {
         Self {
             entities: default(),
+            persistent_ids: default(),
             type_registry,
             world,
         }
@@ -61,6 +73,7 @@ impl<'w> DynamicSceneBuilder<'w> {
     pub fn build(self) -> DynamicScene {
         DynamicScene {
             entities: self.entities.into_values().collect(),
+            persistent_ids: self.persistent_ids,
         }
     }
 
@@ -107,24 +120,40 @@ impl<'w> DynamicSceneBuilder<'w> {
             }
 
             let mut entry = DynamicEntity {
-                entity: index,
+                entity: entity.to_bits(),
                 components: Vec::new(),
             };
 
             for component_id in self.world.entity(entity).archetype().components() {
-                let reflect_component = self
+                let registration = self
                     .world
                     .components()
                     .get_info(component_id)
-                    .and_then(|info| type_registry.get(info.type_id().unwrap()))
-                    .and_then(|registration| registration.data::<ReflectComponent>());
+                    .and_then(|info| type_registry.get(info.type_id().unwrap()));
+                let reflect_component =
+                    registration.and_then(|registration| registration.data::<ReflectComponent>());
+                // Only the references of components that map their entities are resolved when
+                // the scene is written to a world.
+                let maps_entities = registration
+                    .and_then(|registration| registration.data::<ReflectMapEntities>())
+                    .is_some();
 
                 if let Some(reflect_component) = reflect_component {
                     if let Some(component) = reflect_component.reflect(self.world, entity) {
+                        if maps_entities {
+                            visit_entities(component, &mut |referenced| {
+                                if let Some(id) = self.world.get::<PersistentId>(referenced) {
+                                    self.persistent_ids.insert(referenced.to_bits(), *id);
+                                }
+                            });
+                        }
                         entry.components.push(component.clone_value());
                     }
                 }
             }
+            if let Some(id) = self.world.get::<PersistentId>(entity) {
+                self.persistent_ids.insert(entity.to_bits(), *id);
+            }
             self.entities.insert(index, entry);
         }
 
@@ -166,7 +195,7 @@ mod tests {
         let scene = builder.build();
 
         assert_eq!(scene.entities.len(), 1);
-        assert_eq!(scene.entities[0].entity, entity.index());
+        assert_eq!(scene.entities[0].entity, entity.to_bits());
         assert_eq!(scene.entities[0].components.len(), 1);
         assert!(scene.entities[0].components[0].represents::<ComponentA>());
     }
@@ -187,7 +216,7 @@ mod tests {
         let scene = builder.build();
 
         assert_eq!(scene.entities.len(), 1);
-        assert_eq!(scene.entities[0].entity, entity.index());
+        assert_eq!(scene.entities[0].entity, entity.to_bits());
         assert_eq!(scene.entities[0].components.len(), 1);
         assert!(scene.entities[0].components[0].represents::<ComponentA>());
     }
@@ -211,7 +240,7 @@ mod tests {
         let scene = builder.build();
 
         assert_eq!(scene.entities.len(), 1);
-        assert_eq!(scene.entities[0].entity, entity.index());
+        assert_eq!(scene.entities[0].entity, entity.to_bits());
         assert_eq!(scene.entities[0].components.len(), 2);
         assert!(scene.entities[0].components[0].represents::<ComponentA>());
         assert!(scene.entities[0].components[1].represents::<ComponentB>());
@@ -238,10 +267,22 @@ mod tests {
         let mut entities = builder.build().entities.into_iter();
 
         // Assert entities are ordered
-        assert_eq!(entity_a.index(), entities.next().map(|e| e.entity).unwrap());
-        assert_eq!(entity_b.index(), entities.next().map(|e| e.entity).unwrap());
-        assert_eq!(entity_c.index(), entities.next().map(|e| e.entity).unwrap());
-        assert_eq!(entity_d.index(), entities.next().map(|e| e.entity).unwrap());
+        assert_eq!(
+            entity_a.to_bits(),
+            entities.next().map(|e| e.entity).unwrap()
+        );
+        assert_eq!(
+            entity_b.to_bits(),
+            entities.next().map(|e| e.entity).unwrap()
+        );
+        assert_eq!(
+            entity_c.to_bits(),
+            entities.next().map(|e| e.entity).unwrap()
+        );
+        assert_eq!(
+            entity_d.to_bits(),
+            entities.next().map(|e| e.entity).unwrap()
+        );
     }
 
     #[test]
@@ -268,6 +309,6 @@ mod tests {
         assert_eq!(scene.entities.len(), 2);
         let mut scene_entities = vec![scene.entities[0].entity, scene.entities[1].entity];
         scene_entities.sort();
-        assert_eq!(scene_entities, [entity_a_b.index(), entity_a.index()]);
+        assert_eq!(scene_entities, [entity_a_b.to_bits(), entity_a.to_bits()]);
     }
 }
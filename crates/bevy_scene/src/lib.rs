@@ -1,6 +1,7 @@
 mod bundle;
 mod dynamic_scene;
 mod dynamic_scene_builder;
+mod persistent_id;
 mod scene;
 mod scene_loader;
 mod scene_spawner;
@@ -9,6 +10,7 @@ pub mod serde;
 pub use bundle::*;
 pub use dynamic_scene::*;
 pub use dynamic_scene_builder::*;
+pub use persistent_id::*;
 pub use scene::*;
 pub use scene_loader::*;
 pub use scene_spawner::*;
@@ -16,23 +18,28 @@ pub use scene_spawner::*;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        DynamicScene, DynamicSceneBuilder, DynamicSceneBundle, Scene, SceneBundle, SceneSpawner,
+        DynamicScene, DynamicSceneBuilder, DynamicSceneBundle, PersistentId, Scene, SceneBundle,
+        SceneSpawner,
     };
 }
 
 use bevy_app::prelude::*;
 use bevy_asset::AddAsset;
 use bevy_ecs::prelude::*;
+use bevy_utils::Uuid;
 
 #[derive(Default)]
 pub struct ScenePlugin;
 
 impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
+        PersistentIds::init(&mut app.world);
         app.add_asset::<DynamicScene>()
             .add_asset::<Scene>()
             .init_asset_loader::<SceneLoader>()
             .init_resource::<SceneSpawner>()
+            .register_type::<Uuid>()
+            .register_type::<PersistentId>()
             .add_system_to_stage(CoreStage::PreUpdate, scene_spawner_system.at_end())
             // Systems `*_bundle_spawner` must run before `scene_spawner_system`
             .add_system_to_stage(CoreStage::PreUpdate, scene_spawner);
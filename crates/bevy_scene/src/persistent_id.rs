@@ -0,0 +1,399 @@
+use bevy_ecs::{
+    component::{Component, ComponentId},
+    entity::Entity,
+    reflect::ReflectComponent,
+    system::Resource,
+    world::{DeferredWorld, World},
+};
+use bevy_reflect::{FromReflect, Reflect, ReflectRef};
+use bevy_utils::{tracing::warn, HashMap, Uuid};
+use serde::{Deserialize, Serialize};
+
+/// A stable identifier of an entity, which stays the same when the entity is saved in a
+/// [`DynamicScene`](crate::DynamicScene) and loaded again, unlike its [`Entity`].
+///
+/// When a dynamic scene is written to a world, its entities whose persistent id already exists in
+/// the world update that entity instead of spawning a new one, and the references of the scene to
+/// entities outside of it are resolved by their persistent id. This lets save files be loaded into
+/// a world whose entities were spawned in a different order.
+///
+/// Inserting a new id updates the [`PersistentIds`] index, but mutating an id in place doesn't.
+#[derive(
+    Component, Reflect, FromReflect, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+// `Serialize` and `Deserialize` are not reflected: scenes store dynamic clones of components,
+// which are serialized field by field, so they must be deserialized field by field too.
+#[reflect(Component, PartialEq, Hash)]
+#[serde(transparent)]
+pub struct PersistentId(Uuid);
+
+impl PersistentId {
+    /// Creates a new random id.
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    pub const fn from_uuid(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+
+    pub const fn uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for PersistentId {
+    /// Creates a new random id.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The index of the entities of a world by their [`PersistentId`].
+///
+/// It is kept up to date by hooks of [`PersistentId`], registered with [`PersistentIds::init`].
+/// The [`ScenePlugin`](crate::ScenePlugin) initializes it in the app world.
+#[derive(Resource, Debug, Default)]
+pub struct PersistentIds {
+    entities: HashMap<PersistentId, Entity>,
+    ids: HashMap<Entity, PersistentId>,
+}
+
+impl PersistentIds {
+    /// Inserts the index in `world`, indexing the entities that already have a [`PersistentId`],
+    /// and registers the hooks that keep it up to date.
+    pub fn init(world: &mut World) {
+        if world.contains_resource::<PersistentIds>() {
+            return;
+        }
+        let mut persistent_ids = PersistentIds::default();
+        for (entity, id) in world.query::<(Entity, &PersistentId)>().iter(world) {
+            persistent_ids.insert(*id, entity);
+        }
+        world.insert_resource(persistent_ids);
+        let hooks = world.register_component_hooks::<PersistentId>();
+        // The hooks are already registered if the index was removed and initialized again.
+        hooks.try_on_insert(index_persistent_id);
+        hooks.try_on_remove(unindex_persistent_id);
+    }
+
+    /// Returns the entity with the persistent `id`.
+    pub fn get(&self, id: PersistentId) -> Option<Entity> {
+        self.entities.get(&id).copied()
+    }
+
+    /// Returns the persistent id of `entity`.
+    pub fn id(&self, entity: Entity) -> Option<PersistentId> {
+        self.ids.get(&entity).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PersistentId, Entity)> + '_ {
+        self.entities.iter().map(|(id, entity)| (*id, *entity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn insert(&mut self, id: PersistentId, entity: Entity) {
+        self.remove(entity);
+        if let Some(previous) = self.entities.insert(id, entity) {
+            warn!(
+                "{:?} has the same persistent id as {:?}, which won't be found by it anymore",
+                entity, previous
+            );
+        }
+        self.ids.insert(entity, id);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(id) = self.ids.remove(&entity) {
+            // Another entity may have been given the same id since.
+            if self.entities.get(&id) == Some(&entity) {
+                self.entities.remove(&id);
+            }
+        }
+    }
+}
+
+fn index_persistent_id(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let id = *world.get::<PersistentId>(entity).unwrap();
+    if let Some(mut persistent_ids) = world.get_resource_mut::<PersistentIds>() {
+        persistent_ids.insert(id, entity);
+    }
+}
+
+fn unindex_persistent_id(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    if let Some(mut persistent_ids) = world.get_resource_mut::<PersistentIds>() {
+        persistent_ids.remove(entity);
+    }
+}
+
+/// Returns the entity of `world` with the persistent `id`, from the [`PersistentIds`] index if the
+/// world has one.
+pub(crate) fn find_persistent_entity(world: &mut World, id: PersistentId) -> Option<Entity> {
+    if let Some(persistent_ids) = world.get_resource::<PersistentIds>() {
+        return persistent_ids.get(id);
+    }
+    world
+        .query::<(Entity, &PersistentId)>()
+        .iter(world)
+        .find(|(_, entity_id)| **entity_id == id)
+        .map(|(entity, _)| entity)
+}
+
+/// Calls `f` with each [`Entity`] referenced by the reflected `value`, in its fields or elements.
+///
+/// This is used to find the persistent ids of the entities a component references, which are then
+/// mapped by its [`MapEntities`](bevy_ecs::entity::MapEntities) implementation. Entities that
+/// reflection can't see, like those in opaque [`ReflectRef::Value`]s other than [`Entity`], are
+/// not found: if the component maps them to entities outside of the scene, writing the scene
+/// fails with [`SceneSpawnError::UnresolvedEntity`](crate::SceneSpawnError::UnresolvedEntity).
+pub(crate) fn visit_entities(value: &dyn Reflect, f: &mut impl FnMut(Entity)) {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value
+            .iter_fields()
+            .for_each(|field| visit_entities(field, f)),
+        ReflectRef::TupleStruct(value) => {
+            value
+                .iter_fields()
+                .for_each(|field| visit_entities(field, f));
+        }
+        ReflectRef::Tuple(value) => value
+            .iter_fields()
+            .for_each(|field| visit_entities(field, f)),
+        ReflectRef::List(value) => value.iter().for_each(|item| visit_entities(item, f)),
+        ReflectRef::Array(value) => value.iter().for_each(|item| visit_entities(item, f)),
+        ReflectRef::Map(value) => value.iter().for_each(|(key, value)| {
+            visit_entities(key, f);
+            visit_entities(value, f);
+        }),
+        ReflectRef::Enum(value) => value
+            .iter_fields()
+            .for_each(|field| visit_entities(field.value(), f)),
+        ReflectRef::Value(value) => {
+            if let Some(entity) = value.downcast_ref::<Entity>() {
+                f(*entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::AppTypeRegistry;
+    use bevy_ecs::{
+        entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+        prelude::{Component, ReflectComponent, World},
+        reflect::ReflectMapEntities,
+    };
+    use bevy_hierarchy::{BuildWorldChildren, Parent};
+    use bevy_reflect::Reflect;
+
+    use crate::{DynamicSceneBuilder, PersistentId, PersistentIds, SceneSpawnError};
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(i32);
+
+    /// Maps `target` but keeps `spawner` as it was in the saved world.
+    #[derive(Component, Reflect)]
+    #[reflect(Component, MapEntities)]
+    struct Target {
+        target: Entity,
+        spawner: Entity,
+    }
+
+    impl Default for Target {
+        fn default() -> Self {
+            Self {
+                target: Entity::from_raw(u32::MAX),
+                spawner: Entity::from_raw(u32::MAX),
+            }
+        }
+    }
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+            self.target = entity_map.get(self.target)?;
+            Ok(())
+        }
+    }
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        let registry = AppTypeRegistry::default();
+        {
+            let mut registry = registry.write();
+            registry.register::<PersistentId>();
+            registry.register::<Parent>();
+            registry.register::<Health>();
+            registry.register::<Target>();
+        }
+        world.insert_resource(registry);
+        PersistentIds::init(&mut world);
+        world
+    }
+
+    #[test]
+    fn index_follows_components() {
+        let mut world = create_world();
+        let id = PersistentId::new();
+        let entity = world.spawn(id).id();
+        assert_eq!(world.resource::<PersistentIds>().get(id), Some(entity));
+        assert_eq!(world.resource::<PersistentIds>().id(entity), Some(id));
+
+        let new_id = PersistentId::new();
+        world.entity_mut(entity).insert(new_id);
+        assert_eq!(world.resource::<PersistentIds>().get(id), None);
+        assert_eq!(world.resource::<PersistentIds>().get(new_id), Some(entity));
+
+        world.despawn(entity);
+        assert!(world.resource::<PersistentIds>().is_empty());
+    }
+
+    #[test]
+    fn resolve_references_by_persistent_id() {
+        let mut world = create_world();
+        let player_id = PersistentId::new();
+        let sword_id = PersistentId::new();
+        let player = world.spawn(player_id).id();
+        let sword = world.spawn((sword_id, Health(3))).id();
+        world.entity_mut(player).push_children(&[sword]);
+
+        // The player is not part of the save, but is referenced by the sword.
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(sword);
+        let scene = builder.build();
+        assert_eq!(
+            scene.persistent_ids.get(&player.to_bits()),
+            Some(&player_id)
+        );
+        assert_eq!(scene.persistent_ids.get(&sword.to_bits()), Some(&sword_id));
+
+        // The player is spawned with another entity in the loading world.
+        let mut dst_world = create_world();
+        dst_world.spawn_batch((0..4).map(|_| Health(0)));
+        let dst_player = dst_world.spawn(player_id).id();
+        assert_ne!(dst_player, player);
+
+        scene
+            .write_to_world(&mut dst_world, &mut EntityMap::default())
+            .unwrap();
+        let dst_sword = dst_world.resource::<PersistentIds>().get(sword_id).unwrap();
+        assert_eq!(
+            dst_world.get::<Parent>(dst_sword).unwrap().get(),
+            dst_player
+        );
+        assert_eq!(dst_world.get::<Health>(dst_sword).unwrap().0, 3);
+
+        // Loading the save again updates the sword instead of spawning another one.
+        dst_world.get_mut::<Health>(dst_sword).unwrap().0 = 1;
+        scene
+            .write_to_world(&mut dst_world, &mut EntityMap::default())
+            .unwrap();
+        assert_eq!(dst_world.resource::<PersistentIds>().len(), 2);
+        assert_eq!(dst_world.get::<Health>(dst_sword).unwrap().0, 3);
+    }
+
+    #[test]
+    fn keep_existing_references() {
+        let mut world = create_world();
+        let player_id = PersistentId::new();
+        let player = world.spawn((player_id, Health(3))).id();
+
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(player);
+        let scene = builder.build();
+
+        // The existing player references an entity that is not part of the save.
+        let mut dst_world = create_world();
+        let home = dst_world.spawn_empty().id();
+        let dst_player = dst_world.spawn(player_id).id();
+        dst_world.entity_mut(home).push_children(&[dst_player]);
+
+        scene
+            .write_to_world(&mut dst_world, &mut EntityMap::default())
+            .unwrap();
+        assert_eq!(dst_world.get::<Health>(dst_player).unwrap().0, 3);
+        assert_eq!(dst_world.get::<Parent>(dst_player).unwrap().get(), home);
+    }
+
+    #[test]
+    fn map_entities_with_the_component_implementation() {
+        let mut world = create_world();
+        let player_id = PersistentId::new();
+        let player = world.spawn(player_id).id();
+        let spawner = world.spawn_empty().id();
+        let arrow = world
+            .spawn(Target {
+                target: player,
+                spawner,
+            })
+            .id();
+
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(arrow);
+        let scene = builder.build();
+
+        let mut dst_world = create_world();
+        let dst_player = dst_world.spawn(player_id).id();
+        let mut entity_map = EntityMap::default();
+        scene
+            .write_to_world(&mut dst_world, &mut entity_map)
+            .unwrap();
+
+        let dst_arrow = entity_map.get(arrow).unwrap();
+        let target = dst_world.get::<Target>(dst_arrow).unwrap();
+        assert_eq!(target.target, dst_player);
+        assert_eq!(target.spawner, spawner);
+    }
+
+    #[test]
+    fn unresolved_entity() {
+        let mut world = create_world();
+        let player = world.spawn_empty().id();
+        let arrow = world
+            .spawn(Target {
+                target: player,
+                spawner: player,
+            })
+            .id();
+
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(arrow);
+        let scene = builder.build();
+
+        let mut dst_world = create_world();
+        let result = scene.write_to_world(&mut dst_world, &mut EntityMap::default());
+        assert!(matches!(
+            result,
+            Err(SceneSpawnError::UnresolvedEntity { entity }) if entity == player
+        ));
+        assert!(dst_world.entities().is_empty());
+    }
+
+    #[test]
+    fn unresolved_persistent_id() {
+        let mut world = create_world();
+        let player = world.spawn(PersistentId::new()).id();
+        let sword = world.spawn(Health(3)).id();
+        world.entity_mut(player).push_children(&[sword]);
+
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(sword);
+        let scene = builder.build();
+
+        let mut dst_world = create_world();
+        let result = scene.write_to_world(&mut dst_world, &mut EntityMap::default());
+        assert!(matches!(
+            result,
+            Err(SceneSpawnError::UnresolvedPersistentId { .. })
+        ));
+        assert!(dst_world.entities().is_empty());
+    }
+}
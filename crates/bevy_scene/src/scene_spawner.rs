@@ -1,4 +1,4 @@
-use crate::{DynamicScene, Scene};
+use crate::{DynamicScene, PersistentId, Scene};
 use bevy_app::AppTypeRegistry;
 use bevy_asset::{AssetEvent, Assets, Handle};
 use bevy_ecs::{
@@ -51,6 +51,10 @@ pub enum SceneSpawnError {
     NonExistentScene { handle: Handle<DynamicScene> },
     #[error("scene does not exist")]
     NonExistentRealScene { handle: Handle<Scene> },
+    #[error("scene references the entity with the persistent id {id:?}, which does not exist")]
+    UnresolvedPersistentId { id: PersistentId },
+    #[error("scene references the entity {entity:?}, which is neither in the scene nor identified by a persistent id")]
+    UnresolvedEntity { entity: Entity },
 }
 
 impl SceneSpawner {
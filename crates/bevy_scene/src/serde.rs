@@ -1,4 +1,4 @@
-use crate::{DynamicEntity, DynamicScene};
+use crate::{DynamicEntity, DynamicScene, PersistentId};
 use anyhow::Result;
 use bevy_reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
 use bevy_reflect::{serde::UntypedReflectDeserializer, Reflect, TypeRegistry, TypeRegistryArc};
@@ -9,10 +9,11 @@ use serde::{
     ser::SerializeStruct,
     Deserialize, Deserializer, Serialize, Serializer,
 };
-use std::fmt::Formatter;
+use std::{collections::BTreeMap, fmt::Formatter};
 
 pub const SCENE_STRUCT: &str = "Scene";
 pub const SCENE_ENTITIES: &str = "entities";
+pub const SCENE_PERSISTENT_IDS: &str = "persistent_ids";
 
 pub const ENTITY_STRUCT: &str = "Entity";
 pub const ENTITY_FIELD_COMPONENTS: &str = "components";
@@ -33,7 +34,8 @@ impl<'a> Serialize for SceneSerializer<'a> {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct(SCENE_STRUCT, 1)?;
+        let human_readable = serializer.is_human_readable();
+        let mut state = serializer.serialize_struct(SCENE_STRUCT, 2)?;
         state.serialize_field(
             SCENE_ENTITIES,
             &EntitiesSerializer {
@@ -41,6 +43,12 @@ impl<'a> Serialize for SceneSerializer<'a> {
                 registry: self.registry,
             },
         )?;
+        // Non-self-describing formats can't skip fields.
+        if human_readable && self.scene.persistent_ids.is_empty() {
+            state.skip_field(SCENE_PERSISTENT_IDS)?;
+        } else {
+            state.serialize_field(SCENE_PERSISTENT_IDS, &self.scene.persistent_ids)?;
+        }
         state.end()
     }
 }
@@ -116,6 +124,8 @@ impl<'a> Serialize for ComponentsSerializer<'a> {
 #[serde(field_identifier, rename_all = "lowercase")]
 enum SceneField {
     Entities,
+    #[serde(rename = "persistent_ids")]
+    PersistentIds,
 }
 
 #[derive(Deserialize)]
@@ -137,7 +147,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneDeserializer<'a> {
     {
         deserializer.deserialize_struct(
             SCENE_STRUCT,
-            &[SCENE_ENTITIES],
+            &[SCENE_ENTITIES, SCENE_PERSISTENT_IDS],
             SceneVisitor {
                 type_registry: self.type_registry,
             },
@@ -161,6 +171,7 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
         A: MapAccess<'de>,
     {
         let mut entities = None;
+        let mut persistent_ids = None;
         while let Some(key) = map.next_key()? {
             match key {
                 SceneField::Entities => {
@@ -171,12 +182,22 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
                         type_registry: self.type_registry,
                     })?);
                 }
+                SceneField::PersistentIds => {
+                    if persistent_ids.is_some() {
+                        return Err(Error::duplicate_field(SCENE_PERSISTENT_IDS));
+                    }
+                    persistent_ids = Some(map.next_value::<BTreeMap<u64, PersistentId>>()?);
+                }
             }
         }
 
         let entities = entities.ok_or_else(|| Error::missing_field(SCENE_ENTITIES))?;
+        let persistent_ids = persistent_ids.unwrap_or_default();
 
-        Ok(DynamicScene { entities })
+        Ok(DynamicScene {
+            entities,
+            persistent_ids,
+        })
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -188,8 +209,14 @@ impl<'a, 'de> Visitor<'de> for SceneVisitor<'a> {
                 type_registry: self.type_registry,
             })?
             .ok_or_else(|| Error::missing_field(SCENE_ENTITIES))?;
+        let persistent_ids = seq
+            .next_element::<BTreeMap<u64, PersistentId>>()?
+            .ok_or_else(|| Error::missing_field(SCENE_PERSISTENT_IDS))?;
 
-        Ok(DynamicScene { entities })
+        Ok(DynamicScene {
+            entities,
+            persistent_ids,
+        })
     }
 }
 
@@ -226,7 +253,7 @@ impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
         A: MapAccess<'de>,
     {
         let mut entities = Vec::new();
-        while let Some(id) = map.next_key::<u32>()? {
+        while let Some(id) = map.next_key::<u64>()? {
             let entity = map.next_value_seed(SceneEntityDeserializer {
                 id,
                 type_registry: self.type_registry,
@@ -239,7 +266,7 @@ impl<'a, 'de> Visitor<'de> for SceneEntitiesVisitor<'a> {
 }
 
 pub struct SceneEntityDeserializer<'a> {
-    pub id: u32,
+    pub id: u64,
     pub type_registry: &'a TypeRegistry,
 }
 
@@ -262,7 +289,7 @@ impl<'a, 'de> DeserializeSeed<'de> for SceneEntityDeserializer<'a> {
 }
 
 struct SceneEntityVisitor<'a> {
-    pub id: u32,
+    pub id: u64,
     pub registry: &'a TypeRegistry,
 }
 
@@ -387,11 +414,13 @@ impl<'a, 'de> Visitor<'de> for ComponentVisitor<'a> {
 #[cfg(test)]
 mod tests {
     use crate::serde::{SceneDeserializer, SceneSerializer};
-    use crate::{DynamicScene, DynamicSceneBuilder};
+    use crate::{DynamicScene, DynamicSceneBuilder, PersistentId};
     use bevy_app::AppTypeRegistry;
-    use bevy_ecs::entity::EntityMap;
+    use bevy_ecs::entity::{Entity, EntityMap};
     use bevy_ecs::prelude::{Component, ReflectComponent, World};
+    use bevy_hierarchy::{BuildWorldChildren, Parent};
     use bevy_reflect::{FromReflect, Reflect, ReflectSerialize};
+    use bevy_utils::Uuid;
     use bincode::Options;
     use serde::de::DeserializeSeed;
 
@@ -437,11 +466,56 @@ mod tests {
             registry.register_type_data::<String, ReflectSerialize>();
             registry.register::<[usize; 3]>();
             registry.register::<(f32, f32)>();
+            registry.register::<Entity>();
+            registry.register::<Parent>();
+            registry.register::<PersistentId>();
+            registry.register::<Uuid>();
         }
         world.insert_resource(registry);
         world
     }
 
+    /// Extracts a sword with a [`PersistentId`] whose parent, the player, is referenced by its
+    /// persistent id but is not part of the scene.
+    fn create_persistent_scene() -> (DynamicScene, PersistentId, PersistentId) {
+        let mut world = create_world();
+        let player_id = PersistentId::new();
+        let sword_id = PersistentId::new();
+        let player = world.spawn(player_id).id();
+        let sword = world.spawn((sword_id, Foo(3))).id();
+        world.entity_mut(player).push_children(&[sword]);
+
+        let mut builder = DynamicSceneBuilder::from_world(&world);
+        builder.extract_entity(sword);
+        (builder.build(), player_id, sword_id)
+    }
+
+    /// Writes `scene` to a world where the player is spawned after other entities, so that it
+    /// and the entity with the sword's original index have different entities than when saved.
+    fn assert_persistent_scene_loads(
+        scene: &DynamicScene,
+        player_id: PersistentId,
+        sword_id: PersistentId,
+    ) {
+        let mut world = create_world();
+        world.spawn_batch((0..3).map(|_| Bar(0)));
+        let player = world.spawn(player_id).id();
+
+        scene
+            .write_to_world(&mut world, &mut EntityMap::default())
+            .unwrap();
+
+        let (sword, _) = world
+            .query::<(Entity, &PersistentId)>()
+            .iter(&world)
+            .find(|(_, id)| **id == sword_id)
+            .unwrap();
+        assert_eq!(world.get::<Parent>(sword).unwrap().get(), player);
+        assert_eq!(world.get::<Foo>(sword).unwrap().0, 3);
+        assert_eq!(world.query::<&Bar>().iter(&world).count(), 3);
+        assert_eq!(world.query::<&Foo>().iter(&world).count(), 1);
+    }
+
     #[test]
     fn should_serialize() {
         let mut world = create_world();
@@ -551,7 +625,7 @@ mod tests {
                 1, 0, 1, 37, 98, 101, 118, 121, 95, 115, 99, 101, 110, 101, 58, 58, 115, 101, 114,
                 100, 101, 58, 58, 116, 101, 115, 116, 115, 58, 58, 77, 121, 67, 111, 109, 112, 111,
                 110, 101, 110, 116, 1, 2, 3, 102, 102, 166, 63, 205, 204, 108, 64, 1, 12, 72, 101,
-                108, 108, 111, 32, 87, 111, 114, 108, 100, 33
+                108, 108, 111, 32, 87, 111, 114, 108, 100, 33, 0
             ],
             serialized_scene
         );
@@ -586,12 +660,13 @@ mod tests {
 
         assert_eq!(
             vec![
-                1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 37, 0, 0, 0, 0, 0, 0,
-                0, 98, 101, 118, 121, 95, 115, 99, 101, 110, 101, 58, 58, 115, 101, 114, 100, 101,
-                58, 58, 116, 101, 115, 116, 115, 58, 58, 77, 121, 67, 111, 109, 112, 111, 110, 101,
-                110, 116, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0,
-                102, 102, 166, 63, 205, 204, 108, 64, 1, 0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 72, 101,
-                108, 108, 111, 32, 87, 111, 114, 108, 100, 33
+                1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 37, 0, 0,
+                0, 0, 0, 0, 0, 98, 101, 118, 121, 95, 115, 99, 101, 110, 101, 58, 58, 115, 101,
+                114, 100, 101, 58, 58, 116, 101, 115, 116, 115, 58, 58, 77, 121, 67, 111, 109, 112,
+                111, 110, 101, 110, 116, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0,
+                0, 0, 0, 0, 0, 102, 102, 166, 63, 205, 204, 108, 64, 1, 0, 0, 0, 12, 0, 0, 0, 0, 0,
+                0, 0, 72, 101, 108, 108, 111, 32, 87, 111, 114, 108, 100, 33, 0, 0, 0, 0, 0, 0, 0,
+                0
             ],
             serialized_scene
         );
@@ -609,6 +684,47 @@ mod tests {
         assert_scene_eq(&scene, &deserialized_scene);
     }
 
+    #[test]
+    fn should_roundtrip_persistent_ids_ron() {
+        let world = create_world();
+        let registry = world.resource::<AppTypeRegistry>();
+        let (scene, player_id, sword_id) = create_persistent_scene();
+
+        let serialized_scene = scene.serialize_ron(&registry.0).unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&serialized_scene).unwrap();
+        let scene_deserializer = SceneDeserializer {
+            type_registry: &registry.read(),
+        };
+        let deserialized_scene = scene_deserializer.deserialize(&mut deserializer).unwrap();
+
+        assert_eq!(2, deserialized_scene.persistent_ids.len());
+        assert_eq!(scene.persistent_ids, deserialized_scene.persistent_ids);
+        assert_scene_eq(&scene, &deserialized_scene);
+        assert_persistent_scene_loads(&deserialized_scene, player_id, sword_id);
+    }
+
+    #[test]
+    fn should_roundtrip_persistent_ids_bincode() {
+        let world = create_world();
+        let registry = world.resource::<AppTypeRegistry>();
+        let (scene, player_id, sword_id) = create_persistent_scene();
+
+        let scene_serializer = SceneSerializer::new(&scene, &registry.0);
+        let serialized_scene = bincode::serialize(&scene_serializer).unwrap();
+        let scene_deserializer = SceneDeserializer {
+            type_registry: &registry.read(),
+        };
+        let deserialized_scene = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .deserialize_seed(scene_deserializer, &serialized_scene)
+            .unwrap();
+
+        assert_eq!(2, deserialized_scene.persistent_ids.len());
+        assert_eq!(scene.persistent_ids, deserialized_scene.persistent_ids);
+        assert_scene_eq(&scene, &deserialized_scene);
+        assert_persistent_scene_loads(&deserialized_scene, player_id, sword_id);
+    }
+
     /// A crude equality checker for [`DynamicScene`], used solely for testing purposes.
     fn assert_scene_eq(expected: &DynamicScene, received: &DynamicScene) {
         assert_eq!(
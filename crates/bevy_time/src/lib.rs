@@ -19,7 +19,7 @@ pub mod prelude {
     pub use crate::{Time, Timer, TimerMode};
 }
 
-use bevy_app::prelude::*;
+use bevy_app::{prelude::*, ScheduleRunnerSettings};
 use bevy_ecs::prelude::*;
 
 /// Adds time functionality to Apps.
@@ -42,6 +42,20 @@ impl Plugin for TimePlugin {
             // time system is added as an "exclusive system" to ensure it runs before other systems
             // in CoreStage::First
             .add_system_to_stage(CoreStage::First, time_system.at_start().label(TimeSystem));
+
+        #[cfg(feature = "bevy_ci_testing")]
+        if app
+            .world
+            .contains_resource::<Events<bevy_app::ci_testing::CiTestingAction>>()
+        {
+            app.add_system_to_stage(
+                CoreStage::First,
+                ci_testing_set_time
+                    .at_start()
+                    .after(bevy_app::ci_testing::CiTestingSystem)
+                    .before(TimeSystem),
+            );
+        }
     }
 }
 
@@ -51,6 +65,8 @@ impl Plugin for TimePlugin {
 /// you may prefer to set the next [`Time`] value manually.
 #[derive(Resource, Default)]
 pub enum TimeUpdateStrategy {
+    /// Update [`Time`] with the current time, or advance it by a fixed delta per update when
+    /// running with [`RunMode::FixedStep`](bevy_app::RunMode::FixedStep)
     #[default]
     Automatic,
     // Update [`Time`] with an exact `Instant` value
@@ -75,11 +91,29 @@ pub fn create_time_channels() -> (TimeSender, TimeReceiver) {
     (TimeSender(s), TimeReceiver(r))
 }
 
+/// Handles [`CiTestingAction::SetTime`](bevy_app::ci_testing::CiTestingAction::SetTime) by
+/// freezing [`Time`] with [`TimeUpdateStrategy::ManualInstant`].
+#[cfg(feature = "bevy_ci_testing")]
+fn ci_testing_set_time(
+    mut actions: EventReader<bevy_app::ci_testing::CiTestingAction>,
+    time: Res<Time>,
+    mut update_strategy: ResMut<TimeUpdateStrategy>,
+) {
+    for action in actions.iter() {
+        if let bevy_app::ci_testing::CiTestingAction::SetTime(seconds) = action {
+            *update_strategy = TimeUpdateStrategy::ManualInstant(
+                time.startup() + Duration::from_secs_f64(*seconds),
+            );
+        }
+    }
+}
+
 /// The system used to update the [`Time`] used by app logic. If there is a render world the time is sent from
 /// there to this system through channels. Otherwise the time is updated in this system.
 fn time_system(
     mut time: ResMut<Time>,
     update_strategy: Res<TimeUpdateStrategy>,
+    runner_settings: Option<Res<ScheduleRunnerSettings>>,
     time_recv: Option<Res<TimeReceiver>>,
     mut has_received_time: Local<bool>,
 ) {
@@ -98,11 +132,56 @@ fn time_system(
         Instant::now()
     };
 
+    let fixed_step = runner_settings.and_then(|settings| settings.fixed_step());
     match update_strategy.as_ref() {
-        TimeUpdateStrategy::Automatic => time.update_with_instant(new_time),
+        TimeUpdateStrategy::Automatic => match fixed_step {
+            // The first update has no delta, so it happens at startup.
+            Some(delta) => match time.last_update() {
+                Some(last_update) => time.update_with_instant(last_update + delta),
+                None => {
+                    let startup = time.startup();
+                    time.update_with_instant(startup);
+                }
+            },
+            None => time.update_with_instant(new_time),
+        },
         TimeUpdateStrategy::ManualInstant(instant) => time.update_with_instant(*instant),
         TimeUpdateStrategy::ManualDuration(duration) => {
             time.update_with_instant(Instant::now() + *duration);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Time, TimePlugin};
+    use bevy_app::{App, ScheduleRunnerPlugin, ScheduleRunnerSettings};
+    use bevy_ecs::system::Res;
+    use bevy_utils::Duration;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn fixed_step_run_mode() {
+        let elapsed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = elapsed.clone();
+        App::new()
+            .insert_resource(ScheduleRunnerSettings::run_fixed_step(
+                Duration::from_millis(250),
+                Some(4),
+            ))
+            .add_plugin(TimePlugin)
+            .add_plugin(ScheduleRunnerPlugin)
+            .add_system(move |time: Res<Time>| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((time.elapsed().as_millis(), time.delta().as_millis()));
+            })
+            .run();
+
+        assert_eq!(
+            *elapsed.lock().unwrap(),
+            [(0, 0), (250, 250), (500, 250), (750, 250)]
+        );
+    }
+}
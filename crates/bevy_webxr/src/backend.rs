@@ -0,0 +1,95 @@
+//! Boundary between the browser WebXR API and the rest of the crate. Everything crossing
+//! [`WebXrBackend`] is plain data, so the plugin logic can be exercised without a browser.
+
+use bevy_math::Vec3;
+use bevy_xr::{XrHandType, XrPose, XrReferenceSpaceType};
+
+/// Session modes accepted by `navigator.xr.requestSession()`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WebXrSessionMode {
+    Inline,
+    ImmersiveVr,
+    ImmersiveAr,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WebXrSessionState {
+    /// No session is running or being requested.
+    Idle,
+    /// A session has been requested and the browser has not answered yet.
+    Requested,
+    Running(WebXrSessionMode),
+}
+
+/// State of a single `GamepadButton`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct WebXrButton {
+    pub pressed: bool,
+    pub touched: bool,
+    pub value: f32,
+}
+
+/// Buttons and axes of an input source, indexed following the `xr-standard` gamepad mapping.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct WebXrGamepad {
+    pub buttons: Vec<WebXrButton>,
+    pub axes: Vec<f32>,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct WebXrInputSource {
+    /// `None` for input sources not bound to a hand, like gaze or screen input.
+    pub handedness: Option<XrHandType>,
+    /// Input profile names, from the most to the least specific.
+    pub profiles: Vec<String>,
+    pub target_ray: Option<XrPose>,
+    pub grip: Option<XrPose>,
+    pub gamepad: Option<WebXrGamepad>,
+}
+
+/// Tracking and input data of the last `XRFrame`, relative to the current reference space.
+#[derive(Clone, Debug)]
+pub struct WebXrFrame {
+    pub reference_space_type: XrReferenceSpaceType,
+    pub viewer: Option<XrPose>,
+    pub views: Vec<XrPose>,
+    pub input_sources: Vec<WebXrInputSource>,
+    pub bounds_geometry: Option<Vec<Vec3>>,
+}
+
+impl Default for WebXrFrame {
+    fn default() -> Self {
+        Self {
+            reference_space_type: XrReferenceSpaceType::Local,
+            viewer: None,
+            views: vec![],
+            input_sources: vec![],
+            bounds_geometry: None,
+        }
+    }
+}
+
+/// Thin wrapper around the browser WebXR API. Most WebXR calls are asynchronous: methods never
+/// block and results are observed by polling.
+pub trait WebXrBackend: 'static {
+    /// Returns whether `mode` is supported, or `None` while the browser has not answered yet.
+    fn is_session_mode_supported(&self, mode: WebXrSessionMode) -> Option<bool>;
+
+    /// Browsers grant immersive sessions only in response to a user activation, like a click.
+    fn request_session(
+        &mut self,
+        mode: WebXrSessionMode,
+        reference_space_type: XrReferenceSpaceType,
+    );
+
+    fn end_session(&mut self);
+
+    fn session_state(&self) -> WebXrSessionState;
+
+    /// Requests a new reference space. Frames keep using the previous one until the new one is
+    /// available, or forever if it is not supported.
+    fn set_reference_space_type(&mut self, reference_space_type: XrReferenceSpaceType);
+
+    /// Returns the data of the latest `XRFrame` received since the last call, if any.
+    fn poll_frame(&mut self) -> Option<WebXrFrame>;
+}
@@ -0,0 +1,69 @@
+use bevy_math::{Quat, Vec3};
+use bevy_xr::{XrPose, XrReferenceSpaceType, XrRigidTransform};
+
+/// Converts a `DOMPointReadOnly` given as `[x, y, z, w]` into a point in 3D space.
+pub fn to_vec3(point: [f64; 4]) -> Vec3 {
+    let [x, y, z, w] = point;
+    let w = if w != 0.0 { w } else { 1.0 };
+
+    Vec3::new((x / w) as f32, (y / w) as f32, (z / w) as f32)
+}
+
+/// Converts a `DOMPointReadOnly` given as `[x, y, z, w]` into a rotation. WebXR orientations are
+/// always normalized, but the precision is lost when going from `f64` to `f32`.
+pub fn to_quat(orientation: [f64; 4]) -> Quat {
+    let [x, y, z, w] = orientation;
+
+    Quat::from_xyzw(x as f32, y as f32, z as f32, w as f32).normalize()
+}
+
+/// Converts the `position` and `orientation` of an `XRRigidTransform`.
+pub fn to_rigid_transform(position: [f64; 4], orientation: [f64; 4]) -> XrRigidTransform {
+    XrRigidTransform {
+        position: to_vec3(position),
+        orientation: to_quat(orientation),
+    }
+}
+
+/// Converts an `XRPose`. Velocities are not exposed by WebXR.
+pub fn to_pose(position: [f64; 4], orientation: [f64; 4], emulated_position: bool) -> XrPose {
+    XrPose {
+        transform: to_rigid_transform(position, orientation),
+        linear_velocity: None,
+        angular_velocity: None,
+        emulated_position,
+    }
+}
+
+/// Returns the name of the `XRReferenceSpaceType` matching `reference_space_type`.
+pub fn reference_space_name(reference_space_type: XrReferenceSpaceType) -> &'static str {
+    match reference_space_type {
+        XrReferenceSpaceType::Viewer => "viewer",
+        XrReferenceSpaceType::Local => "local",
+        XrReferenceSpaceType::Stage => "bounded-floor",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    #[test]
+    fn pose_conversion() {
+        let orientation = [0.0, 0.5f64.sqrt(), 0.0, 0.5f64.sqrt()];
+        let pose = to_pose([1.0, 1.6, -0.5, 1.0], orientation, true);
+
+        assert_eq!(pose.position, Vec3::new(1.0, 1.6, -0.5));
+        assert!(pose.orientation.abs_diff_eq(
+            Quat::from_xyzw(0.0, FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2),
+            1e-6
+        ));
+        assert!(pose.emulated_position);
+        assert!(pose.linear_velocity.is_none());
+
+        // Homogeneous coordinates
+        assert_eq!(to_vec3([2.0, 4.0, -6.0, 2.0]), Vec3::new(1.0, 2.0, -3.0));
+        assert_eq!(to_vec3([2.0, 4.0, -6.0, 0.0]), Vec3::new(2.0, 4.0, -6.0));
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{WebXrFrame, WebXrGamepad, WebXrInputSource};
+use bevy_math::{Vec2, Vec3};
+use bevy_xr::{
+    implementation::XrTrackingSourceBackend, XrActionState, XrActionType, XrButtonState,
+    XrHandType, XrJointPose, XrPose, XrProfileDescriptor, XrProfiles, XrReferenceSpaceType,
+};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+/// Component of a gamepad using the `xr-standard` mapping, as indexed by the WebXR Gamepads Module.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct StandardComponent {
+    button: usize,
+    axes: Option<(usize, usize)>,
+}
+
+/// Parses OpenXR-style binding paths, like `/user/hand/left/input/thumbstick` or
+/// `/user/hand/right/input/a/click`. WebXR does not expose interaction profile specific paths, so
+/// bindings of all profiles are resolved against the `xr-standard` gamepad layout.
+fn parse_binding_path(path: &str) -> Option<(XrHandType, StandardComponent, Option<&str>)> {
+    let mut segments = path.strip_prefix("/user/hand/")?.split('/');
+
+    let hand = match segments.next()? {
+        "left" => XrHandType::Left,
+        "right" => XrHandType::Right,
+        _ => return None,
+    };
+    if segments.next()? != "input" {
+        return None;
+    }
+    let component = match segments.next()? {
+        "trigger" | "select" => StandardComponent {
+            button: 0,
+            axes: None,
+        },
+        "squeeze" => StandardComponent {
+            button: 1,
+            axes: None,
+        },
+        "trackpad" => StandardComponent {
+            button: 2,
+            axes: Some((0, 1)),
+        },
+        "thumbstick" => StandardComponent {
+            button: 3,
+            axes: Some((2, 3)),
+        },
+        "a" | "x" => StandardComponent {
+            button: 4,
+            axes: None,
+        },
+        "b" | "y" => StandardComponent {
+            button: 5,
+            axes: None,
+        },
+        _ => return None,
+    };
+    let suffix = segments.next();
+
+    Some((hand, component, suffix))
+}
+
+fn gamepad_action_state(
+    gamepad: Option<&WebXrGamepad>,
+    component: StandardComponent,
+    suffix: Option<&str>,
+    action_type: XrActionType,
+) -> XrActionState {
+    let button = gamepad
+        .and_then(|gamepad| gamepad.buttons.get(component.button))
+        .copied()
+        .unwrap_or_default();
+    let axis = |index: usize| {
+        gamepad
+            .and_then(|gamepad| gamepad.axes.get(index))
+            .copied()
+            .unwrap_or(0.0)
+    };
+    // Gamepad axes are +Y down, OpenXR and bevy_xr use +Y up.
+    let axes = component
+        .axes
+        .map(|(x, y)| Vec2::new(axis(x), -axis(y)))
+        .unwrap_or(Vec2::ZERO);
+
+    match action_type {
+        XrActionType::Button { .. } => {
+            let state = if button.pressed {
+                XrButtonState::Pressed
+            } else if button.touched {
+                XrButtonState::Touched
+            } else {
+                XrButtonState::Default
+            };
+
+            XrActionState::Button {
+                state,
+                value: button.value,
+            }
+        }
+        XrActionType::Binary => XrActionState::Binary(match suffix {
+            Some("touch") => button.touched,
+            _ => button.pressed,
+        }),
+        XrActionType::Scalar => XrActionState::Scalar(match suffix {
+            Some("x") => axes.x,
+            Some("y") => axes.y,
+            _ => button.value,
+        }),
+        XrActionType::Vec2D => XrActionState::Vec2D(axes),
+    }
+}
+
+/// Computes the state of the actions declared with [`bevy_xr::XrSystem::set_action_set`] from the
+/// WebXR input sources. When an action is bound in multiple profiles, the first binding is used.
+/// Actions bound to a missing input source are reported in their default state.
+pub fn action_states(
+    action_set: &[XrProfileDescriptor],
+    input_sources: &[WebXrInputSource],
+) -> HashMap<String, XrActionState> {
+    let mut states = HashMap::new();
+
+    for (action, path) in action_set
+        .iter()
+        .flat_map(|profile| profile.bindings.iter())
+    {
+        if states.contains_key(&action.name) {
+            continue;
+        }
+
+        if let Some((hand, component, suffix)) = parse_binding_path(path) {
+            let gamepad = input_sources
+                .iter()
+                .find(|source| source.handedness == Some(hand))
+                .and_then(|source| source.gamepad.as_ref());
+
+            states.insert(
+                action.name.clone(),
+                gamepad_action_state(gamepad, component, suffix, action.action_type),
+            );
+        }
+    }
+
+    states
+}
+
+/// Returns the most specific profile of each hand input source.
+pub fn input_profiles(input_sources: &[WebXrInputSource]) -> XrProfiles {
+    let profile = |hand| {
+        input_sources
+            .iter()
+            .find(|source| source.handedness == Some(hand))
+            .and_then(|source| source.profiles.first().cloned())
+    };
+
+    XrProfiles {
+        left_hand: profile(XrHandType::Left),
+        right_hand: profile(XrHandType::Right),
+    }
+}
+
+fn hands_poses(
+    input_sources: &[WebXrInputSource],
+    pose: impl Fn(&WebXrInputSource) -> Option<XrPose>,
+) -> [Option<XrPose>; 2] {
+    let hand_pose = |hand| {
+        input_sources
+            .iter()
+            .find(|source| source.handedness == Some(hand))
+            .and_then(&pose)
+    };
+
+    [hand_pose(XrHandType::Left), hand_pose(XrHandType::Right)]
+}
+
+#[derive(Default)]
+pub(crate) struct TrackingState {
+    pub frame: WebXrFrame,
+    pub requested_reference_space_type: Option<XrReferenceSpaceType>,
+}
+
+/// Tracking source backed by the data of the last polled WebXR frame.
+pub(crate) struct TrackingSource {
+    pub state: Arc<RwLock<TrackingState>>,
+}
+
+impl XrTrackingSourceBackend for TrackingSource {
+    fn reference_space_type(&self) -> XrReferenceSpaceType {
+        self.state.read().frame.reference_space_type
+    }
+
+    // The reference space is created asynchronously by the browser: the request is always
+    // accepted and the new type is reported once frames use it.
+    fn set_reference_space_type(&self, reference_space_type: XrReferenceSpaceType) -> bool {
+        self.state.write().requested_reference_space_type = Some(reference_space_type);
+
+        true
+    }
+
+    fn bounds_geometry(&self) -> Option<Vec<Vec3>> {
+        self.state.read().frame.bounds_geometry.clone()
+    }
+
+    fn views_poses(&self) -> Vec<XrPose> {
+        self.state.read().frame.views.clone()
+    }
+
+    fn hands_pose(&self) -> [Option<XrPose>; 2] {
+        hands_poses(&self.state.read().frame.input_sources, |source| {
+            source.grip.clone()
+        })
+    }
+
+    fn hands_skeleton_pose(&self) -> [Option<Vec<XrJointPose>>; 2] {
+        [None, None]
+    }
+
+    fn hands_target_ray(&self) -> [Option<XrPose>; 2] {
+        hands_poses(&self.state.read().frame.input_sources, |source| {
+            source.target_ray.clone()
+        })
+    }
+
+    fn viewer_target_ray(&self) -> XrPose {
+        self.state.read().frame.viewer.clone().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WebXrButton;
+    use bevy_xr::XrActionDescriptor;
+
+    fn binding(name: &str, action_type: XrActionType, path: &str) -> (XrActionDescriptor, String) {
+        (
+            XrActionDescriptor {
+                name: name.into(),
+                action_type,
+            },
+            path.into(),
+        )
+    }
+
+    #[test]
+    fn input_sources_to_actions() {
+        let button = XrActionType::Button {
+            touch: true,
+            click: true,
+            value: true,
+        };
+        let action_set = [
+            XrProfileDescriptor {
+                profile: "/interaction_profiles/oculus/touch_controller".into(),
+                bindings: vec![
+                    binding("left_trigger", button, "/user/hand/left/input/trigger"),
+                    binding("right_primary", button, "/user/hand/right/input/a"),
+                    binding(
+                        "left_thumbstick",
+                        XrActionType::Vec2D,
+                        "/user/hand/left/input/thumbstick",
+                    ),
+                    binding(
+                        "left_thumbstick_touched",
+                        XrActionType::Binary,
+                        "/user/hand/left/input/thumbstick/touch",
+                    ),
+                    binding(
+                        "right_squeeze",
+                        XrActionType::Scalar,
+                        "/user/hand/right/input/squeeze/value",
+                    ),
+                    binding(
+                        "left_menu",
+                        XrActionType::Binary,
+                        "/user/hand/left/input/menu",
+                    ),
+                ],
+                tracked: true,
+                has_haptics: false,
+            },
+            XrProfileDescriptor {
+                profile: "/interaction_profiles/khr/simple_controller".into(),
+                bindings: vec![binding(
+                    "left_trigger",
+                    XrActionType::Binary,
+                    "/user/hand/left/input/select/click",
+                )],
+                tracked: true,
+                has_haptics: false,
+            },
+        ];
+        let left = WebXrInputSource {
+            handedness: Some(XrHandType::Left),
+            profiles: vec!["oculus-touch-v3".into(), "generic-trigger".into()],
+            gamepad: Some(WebXrGamepad {
+                buttons: vec![
+                    WebXrButton {
+                        pressed: true,
+                        touched: true,
+                        value: 0.9,
+                    },
+                    WebXrButton::default(),
+                    WebXrButton::default(),
+                    WebXrButton {
+                        pressed: false,
+                        touched: true,
+                        value: 0.0,
+                    },
+                ],
+                axes: vec![0.0, 0.0, 0.25, -0.5],
+            }),
+            ..Default::default()
+        };
+
+        let input_sources = [left];
+        let states = action_states(&action_set, &input_sources);
+
+        assert_eq!(
+            states["left_trigger"],
+            XrActionState::Button {
+                state: XrButtonState::Pressed,
+                value: 0.9
+            }
+        );
+        assert_eq!(
+            states["left_thumbstick"],
+            XrActionState::Vec2D(Vec2::new(0.25, 0.5))
+        );
+        assert_eq!(
+            states["left_thumbstick_touched"],
+            XrActionState::Binary(true)
+        );
+        // The right controller is missing
+        assert_eq!(
+            states["right_primary"],
+            XrActionState::Button {
+                state: XrButtonState::Default,
+                value: 0.0
+            }
+        );
+        assert_eq!(states["right_squeeze"], XrActionState::Scalar(0.0));
+        // WebXR does not expose the menu button
+        assert!(!states.contains_key("left_menu"));
+
+        let profiles = input_profiles(&input_sources);
+        assert_eq!(profiles.left_hand.as_deref(), Some("oculus-touch-v3"));
+        assert_eq!(profiles.right_hand, None);
+    }
+}
@@ -1,99 +1,335 @@
-// use bevy_app::{AppBuilder, Plugin};
-// use bevy_utils::Duration;
-// use bevy_xr::XrMode;
-// use std::{cell::RefCell, rc::Rc, thread};
-// use web_sys::{Closure, XrSession, XrSessionMode};
-
-// #[derive(Clone)]
-// struct WebXrContext {
-//     pub session: XrSession,
-//     pub mode: XrMode,
-// }
-
-// struct WebXrPlugin;
-
-// impl Plugin for WebXrPlugin {
-//     fn build(&self, app: &mut AppBuilder) {
-//         let context = if let Some(context) = app.world().get_resource::<WebXrContext>() {
-//             context.clone()
-//         } else {
-//             let system = web_sys::window().unwrap().navigator().xr();
-
-//             // todo: this needs a better solution
-//             let vr_supported = Rc::new(RefCell::new(None));
-//             system
-//                 .is_session_supported(XrSessionMode::ImmersiveVr)
-//                 .then(Closure::wrap(Box::new({
-//                     let vr_supported = Rc::clone(&vr_supported);
-//                     move |res| {
-//                         *vr_supported.borrow_mut() = Some(res);
-//                     }
-//                 })));
-//             let ar_supported = Rc::new(RefCell::new(None));
-//             system
-//                 .is_session_supported(XrSessionMode::ImmersiveAr)
-//                 .then(Closure::wrap(Box::new({
-//                     let ar_supported = Rc::clone(&ar_supported);
-//                     move |res| {
-//                         *ar_supported.borrow_mut() = Some(res);
-//                     }
-//                 })));
-//             let vr_supported = loop {
-//                 if let Some(res) = vr_supported.borrow_mut().take() {
-//                     break res;
-//                 } else {
-//                     thread::sleep(Duration::from_millis(10));
-//                 }
-//             };
-//             let ar_supported = loop {
-//                 if let Some(res) = vr_supported.borrow_mut().take() {
-//                     break res;
-//                 } else {
-//                     thread::sleep(Duration::from_millis(10));
-//                 }
-//             };
-
-//             let mode = app.world().get_resource::<XrMode>();
-//             let session_mode = match mode {
-//                 Some(XrMode::ImmersiveVR) | None if vr_supported => XrSessionMode::ImmersiveVr,
-//                 Some(XrMode::ImmersiveAR) if ar_supported => XrSessionMode::ImmersiveAr,
-//                 _ => XrSessionMode::Inline,
-//             };
-
-//             let new_mode = match session_mode {
-//                 XrSessionMode::ImmersiveVr => XrMode::ImmersiveVR,
-//                 XrSessionMode::ImmersiveAr => XrMode::ImmersiveAR,
-//                 XrSessionMode::Inline => XrMode::InlineVR,
-//             };
-
-//             if let Some(mode) = mode {
-//                 if new_mode != *mode {
-//                     bevy_log::warn!("XrMode has been changed to {:?}", mode);
-//                 }
-//             }
-
-//             let session = Rc::new(RefCell::new(None));
-//             system
-//                 .request_session(session_mode)
-//                 .then(Closure::wrap(Box::new({
-//                     let session = Rc::clone(&session);
-//                     move |s: XrSession| {
-//                         *session.borrow_mut() = Some(s);
-//                     }
-//                 })));
-//             let session = loop {
-//                 if let Some(session) = session.borrow_mut().take() {
-//                     break session;
-//                 } else {
-//                     thread::sleep(Duration::from_millis(10));
-//                 }
-//             };
-
-//             WebXrContext {
-//                 session,
-//                 mode: new_mode,
-//             };
-//         };
-//         app.insert_resource(context.mode).insert_resource(context);
-//     }
-// }
+mod backend;
+pub mod conversion;
+pub mod interaction;
+pub mod session;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+pub use backend::*;
+#[cfg(target_arch = "wasm32")]
+pub use web::WebSysBackend;
+
+use bevy_app::{App, CoreStage, Plugin};
+use bevy_ecs::{
+    event::{EventReader, EventWriter},
+    schedule::IntoSystemDescriptor,
+    system::{Commands, NonSendMut, Res, ResMut},
+};
+use bevy_xr::{
+    XrActionSet, XrProfiles, XrReferenceSpaceType, XrSessionEvent, XrSessionMode, XrSessionRequest,
+    XrSystem, XrTrackingSource,
+};
+use interaction::{TrackingSource, TrackingState};
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Non-send resource holding the [`WebXrBackend`] used by [`WebXrPlugin`]. Insert it before adding
+/// the plugin to use a custom backend, otherwise [`WebSysBackend`] is used on the web.
+pub struct WebXrContext {
+    backend: Box<dyn WebXrBackend>,
+    session_state: WebXrSessionState,
+    session_mode: XrSessionMode,
+    session_modes_discovered: bool,
+    tracking_state: Arc<RwLock<TrackingState>>,
+}
+
+impl WebXrContext {
+    pub fn new(backend: Box<dyn WebXrBackend>) -> Self {
+        Self {
+            backend,
+            session_state: WebXrSessionState::Idle,
+            session_mode: XrSessionMode::InlineVR,
+            session_modes_discovered: false,
+            tracking_state: Default::default(),
+        }
+    }
+}
+
+/// WebXR backend for `bevy_xr`. Sessions are started with [`XrSessionRequest::Start`], which the
+/// browser only accepts in response to a user activation. Tracking and input data is read from
+/// the most recent `XRFrame`; rendering to the WebXR layer is not implemented yet.
+#[derive(Default)]
+pub struct WebXrPlugin;
+
+impl Plugin for WebXrPlugin {
+    fn build(&self, app: &mut App) {
+        if app.world.get_non_send_resource::<WebXrContext>().is_none() {
+            #[cfg(target_arch = "wasm32")]
+            app.insert_non_send_resource(WebXrContext::new(Box::new(WebSysBackend::new())));
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                bevy_log::warn!("WebXR is only available on the web");
+                return;
+            }
+        }
+
+        // Inline sessions are always supported. Immersive modes are added once the browser
+        // answers.
+        app.insert_resource(XrSystem::new(vec![XrSessionMode::InlineVR]))
+            .init_resource::<XrActionSet>()
+            .add_system_to_stage(CoreStage::PreUpdate, discover_session_modes)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                handle_session_requests.after(discover_session_modes),
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_session_state.after(handle_session_requests),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, poll_frame.after(update_session_state));
+    }
+}
+
+fn discover_session_modes(mut context: NonSendMut<WebXrContext>, mut system: ResMut<XrSystem>) {
+    if context.session_modes_discovered {
+        return;
+    }
+
+    if let Some(supported_modes) = session::supported_session_modes(&*context.backend) {
+        system.set_available_session_modes(session::to_xr_session_modes(&supported_modes));
+        context.session_modes_discovered = true;
+    }
+}
+
+fn handle_session_requests(
+    mut context: NonSendMut<WebXrContext>,
+    system: Res<XrSystem>,
+    mut session_requests: EventReader<XrSessionRequest>,
+) {
+    for request in session_requests.iter() {
+        match (request, context.session_state) {
+            (XrSessionRequest::Start, WebXrSessionState::Idle) => {
+                let supported_modes = session::supported_session_modes(&*context.backend)
+                    .unwrap_or_else(|| vec![WebXrSessionMode::Inline]);
+                let requested_mode = system.selected_session_mode();
+                let (web_mode, mode) =
+                    session::negotiate_session_mode(requested_mode, &supported_modes);
+                if mode != requested_mode {
+                    bevy_log::warn!("{:?} is not supported, using {:?}", requested_mode, mode);
+                }
+
+                let reference_space_type = match web_mode {
+                    WebXrSessionMode::Inline => XrReferenceSpaceType::Viewer,
+                    _ => XrReferenceSpaceType::Local,
+                };
+                context
+                    .backend
+                    .request_session(web_mode, reference_space_type);
+                context.session_mode = mode;
+                context.session_state = WebXrSessionState::Requested;
+            }
+            (XrSessionRequest::End, WebXrSessionState::Running(_)) => context.backend.end_session(),
+            _ => (),
+        }
+    }
+}
+
+fn update_session_state(
+    mut commands: Commands,
+    mut context: NonSendMut<WebXrContext>,
+    mut system: ResMut<XrSystem>,
+    mut action_set: ResMut<XrActionSet>,
+    mut session_events: EventWriter<XrSessionEvent>,
+) {
+    let session_state = context.backend.session_state();
+
+    match (context.session_state, session_state) {
+        (WebXrSessionState::Running(_), WebXrSessionState::Running(_)) => (),
+        (_, WebXrSessionState::Running(_)) => {
+            *context.tracking_state.write() = TrackingState::default();
+            commands.insert_resource(XrTrackingSource::new(Box::new(TrackingSource {
+                state: Arc::clone(&context.tracking_state),
+            })));
+            system.set_session_running(true);
+            session_events.send(XrSessionEvent::Started(context.session_mode));
+        }
+        (WebXrSessionState::Running(_), _) => {
+            commands.remove_resource::<XrTrackingSource>();
+            commands.insert_resource(XrProfiles::default());
+            action_set.clear();
+            system.set_session_running(false);
+            session_events.send(XrSessionEvent::Ended);
+        }
+        (WebXrSessionState::Requested, WebXrSessionState::Idle) => {
+            bevy_log::warn!("The WebXR session request has been rejected");
+        }
+        _ => (),
+    }
+
+    context.session_state = session_state;
+}
+
+fn poll_frame(
+    mut context: NonSendMut<WebXrContext>,
+    system: Res<XrSystem>,
+    mut action_set: ResMut<XrActionSet>,
+    mut profiles: ResMut<XrProfiles>,
+) {
+    if !system.is_session_running() {
+        return;
+    }
+
+    let requested_reference_space_type = context
+        .tracking_state
+        .write()
+        .requested_reference_space_type
+        .take();
+    if let Some(reference_space_type) = requested_reference_space_type {
+        context
+            .backend
+            .set_reference_space_type(reference_space_type);
+    }
+
+    if let Some(frame) = context.backend.poll_frame() {
+        action_set.set(interaction::action_states(
+            system.action_set(),
+            &frame.input_sources,
+        ));
+
+        let new_profiles = interaction::input_profiles(&frame.input_sources);
+        if *profiles != new_profiles {
+            *profiles = new_profiles;
+        }
+
+        context.tracking_state.write().frame = frame;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::Vec3;
+    use bevy_xr::{XrHandType, XrPlugin, XrPose, XrRigidTransform};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Default)]
+    struct MockState {
+        immersive_vr_supported: Option<bool>,
+        session_state: Option<WebXrSessionState>,
+        requested_mode: Option<WebXrSessionMode>,
+        reference_space_type: Option<XrReferenceSpaceType>,
+        frame: Option<WebXrFrame>,
+    }
+
+    struct MockBackend(Rc<RefCell<MockState>>);
+
+    impl WebXrBackend for MockBackend {
+        fn is_session_mode_supported(&self, mode: WebXrSessionMode) -> Option<bool> {
+            match mode {
+                WebXrSessionMode::ImmersiveVr => self.0.borrow().immersive_vr_supported,
+                _ => Some(false),
+            }
+        }
+
+        fn request_session(
+            &mut self,
+            mode: WebXrSessionMode,
+            reference_space_type: XrReferenceSpaceType,
+        ) {
+            let mut state = self.0.borrow_mut();
+            state.requested_mode = Some(mode);
+            state.reference_space_type = Some(reference_space_type);
+            state.session_state = Some(WebXrSessionState::Requested);
+        }
+
+        fn end_session(&mut self) {
+            self.0.borrow_mut().session_state = Some(WebXrSessionState::Idle);
+        }
+
+        fn session_state(&self) -> WebXrSessionState {
+            self.0
+                .borrow()
+                .session_state
+                .unwrap_or(WebXrSessionState::Idle)
+        }
+
+        fn set_reference_space_type(&mut self, reference_space_type: XrReferenceSpaceType) {
+            self.0.borrow_mut().reference_space_type = Some(reference_space_type);
+        }
+
+        fn poll_frame(&mut self) -> Option<WebXrFrame> {
+            self.0.borrow_mut().frame.take()
+        }
+    }
+
+    #[test]
+    fn session_lifecycle() {
+        let mock_state = Rc::new(RefCell::new(MockState::default()));
+        let mut app = App::new();
+        app.insert_non_send_resource(WebXrContext::new(Box::new(MockBackend(Rc::clone(
+            &mock_state,
+        )))))
+        .add_plugin(WebXrPlugin)
+        .add_plugin(XrPlugin);
+
+        app.update();
+        assert_eq!(
+            app.world.resource::<XrSystem>().available_session_modes(),
+            vec![XrSessionMode::InlineVR]
+        );
+
+        mock_state.borrow_mut().immersive_vr_supported = Some(true);
+        app.update();
+        assert_eq!(
+            app.world.resource::<XrSystem>().selected_session_mode(),
+            XrSessionMode::ImmersiveVR
+        );
+
+        app.world.send_event(XrSessionRequest::Start);
+        app.update();
+        assert_eq!(
+            mock_state.borrow().requested_mode,
+            Some(WebXrSessionMode::ImmersiveVr)
+        );
+        assert!(!app.world.resource::<XrSystem>().is_session_running());
+
+        mock_state.borrow_mut().session_state =
+            Some(WebXrSessionState::Running(WebXrSessionMode::ImmersiveVr));
+        mock_state.borrow_mut().frame = Some(WebXrFrame {
+            viewer: Some(XrPose {
+                transform: XrRigidTransform {
+                    position: Vec3::new(0.0, 1.6, 0.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            input_sources: vec![WebXrInputSource {
+                handedness: Some(XrHandType::Right),
+                profiles: vec!["generic-trigger".into()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        app.update();
+        assert!(app.world.resource::<XrSystem>().is_session_running());
+        let events = app
+            .world
+            .resource::<bevy_ecs::event::Events<XrSessionEvent>>();
+        assert_eq!(
+            events.iter_current_update_events().last(),
+            Some(&XrSessionEvent::Started(XrSessionMode::ImmersiveVR))
+        );
+        assert_eq!(
+            app.world.resource::<XrProfiles>().right_hand.as_deref(),
+            Some("generic-trigger")
+        );
+
+        let mut tracking_source = app.world.resource_mut::<XrTrackingSource>();
+        assert_eq!(
+            tracking_source.viewer_target_ray().position,
+            Vec3::new(0.0, 1.6, 0.0)
+        );
+        assert!(tracking_source.set_reference_space_type(XrReferenceSpaceType::Stage));
+        app.update();
+        assert_eq!(
+            mock_state.borrow().reference_space_type,
+            Some(XrReferenceSpaceType::Stage)
+        );
+
+        app.world.send_event(XrSessionRequest::End);
+        app.update();
+        assert!(!app.world.resource::<XrSystem>().is_session_running());
+        assert!(!app.world.contains_resource::<XrTrackingSource>());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{WebXrBackend, WebXrSessionMode};
+use bevy_xr::XrSessionMode;
+
+const WEBXR_SESSION_MODES: [WebXrSessionMode; 3] = [
+    WebXrSessionMode::ImmersiveVr,
+    WebXrSessionMode::ImmersiveAr,
+    WebXrSessionMode::Inline,
+];
+
+/// Returns the session modes supported by the browser, or `None` while some of the queries are
+/// still pending. Inline sessions are always supported.
+pub fn supported_session_modes(backend: &dyn WebXrBackend) -> Option<Vec<WebXrSessionMode>> {
+    let mut supported_modes = vec![];
+    for mode in WEBXR_SESSION_MODES.iter().copied() {
+        if mode == WebXrSessionMode::Inline || backend.is_session_mode_supported(mode)? {
+            supported_modes.push(mode);
+        }
+    }
+
+    Some(supported_modes)
+}
+
+/// Converts the browser supported modes into the modes exposed by [`bevy_xr::XrSystem`], in order
+/// of preference. WebXR inline sessions are exposed as [`XrSessionMode::InlineVR`].
+pub fn to_xr_session_modes(supported_modes: &[WebXrSessionMode]) -> Vec<XrSessionMode> {
+    let mut modes = supported_modes
+        .iter()
+        .map(|mode| match mode {
+            WebXrSessionMode::ImmersiveVr => XrSessionMode::ImmersiveVR,
+            WebXrSessionMode::ImmersiveAr => XrSessionMode::ImmersiveAR,
+            WebXrSessionMode::Inline => XrSessionMode::InlineVR,
+        })
+        .collect::<Vec<_>>();
+    modes.sort_by_key(|mode| *mode as u8);
+    modes.dedup();
+
+    modes
+}
+
+/// Chooses the WebXR session mode to request for `requested`. When the requested immersive mode
+/// is not supported, this falls back to an inline session. Returns the mode to request and the
+/// mode that will be reported to the app.
+pub fn negotiate_session_mode(
+    requested: XrSessionMode,
+    supported_modes: &[WebXrSessionMode],
+) -> (WebXrSessionMode, XrSessionMode) {
+    match requested {
+        XrSessionMode::ImmersiveVR if supported_modes.contains(&WebXrSessionMode::ImmersiveVr) => {
+            (WebXrSessionMode::ImmersiveVr, requested)
+        }
+        XrSessionMode::ImmersiveAR if supported_modes.contains(&WebXrSessionMode::ImmersiveAr) => {
+            (WebXrSessionMode::ImmersiveAr, requested)
+        }
+        XrSessionMode::InlineVR | XrSessionMode::InlineAR => (WebXrSessionMode::Inline, requested),
+        _ => (WebXrSessionMode::Inline, XrSessionMode::InlineVR),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_mode_negotiation() {
+        let vr_only = [WebXrSessionMode::ImmersiveVr, WebXrSessionMode::Inline];
+
+        assert_eq!(
+            negotiate_session_mode(XrSessionMode::ImmersiveVR, &vr_only),
+            (WebXrSessionMode::ImmersiveVr, XrSessionMode::ImmersiveVR)
+        );
+        assert_eq!(
+            negotiate_session_mode(XrSessionMode::ImmersiveAR, &vr_only),
+            (WebXrSessionMode::Inline, XrSessionMode::InlineVR)
+        );
+        assert_eq!(
+            negotiate_session_mode(XrSessionMode::InlineAR, &vr_only),
+            (WebXrSessionMode::Inline, XrSessionMode::InlineAR)
+        );
+        assert_eq!(
+            to_xr_session_modes(&[
+                WebXrSessionMode::Inline,
+                WebXrSessionMode::ImmersiveAr,
+                WebXrSessionMode::ImmersiveVr
+            ]),
+            vec![
+                XrSessionMode::ImmersiveVR,
+                XrSessionMode::ImmersiveAR,
+                XrSessionMode::InlineVR
+            ]
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! [`WebXrBackend`] implementation on top of `web-sys`. The WebXR bindings are unstable, so this
+//! requires building with `RUSTFLAGS=--cfg=web_sys_unstable_apis`.
+
+use crate::{
+    conversion, WebXrBackend, WebXrButton, WebXrFrame, WebXrGamepad, WebXrInputSource,
+    WebXrSessionMode, WebXrSessionState,
+};
+use bevy_xr::{XrHandType, XrPose, XrReferenceSpaceType};
+use js_sys::Array;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+fn to_web_session_mode(mode: WebXrSessionMode) -> web_sys::XrSessionMode {
+    match mode {
+        WebXrSessionMode::Inline => web_sys::XrSessionMode::Inline,
+        WebXrSessionMode::ImmersiveVr => web_sys::XrSessionMode::ImmersiveVr,
+        WebXrSessionMode::ImmersiveAr => web_sys::XrSessionMode::ImmersiveAr,
+    }
+}
+
+fn to_web_reference_space_type(
+    reference_space_type: XrReferenceSpaceType,
+) -> web_sys::XrReferenceSpaceType {
+    match reference_space_type {
+        XrReferenceSpaceType::Viewer => web_sys::XrReferenceSpaceType::Viewer,
+        XrReferenceSpaceType::Local => web_sys::XrReferenceSpaceType::Local,
+        XrReferenceSpaceType::Stage => web_sys::XrReferenceSpaceType::BoundedFloor,
+    }
+}
+
+fn to_array(point: &web_sys::DomPointReadOnly) -> [f64; 4] {
+    [point.x(), point.y(), point.z(), point.w()]
+}
+
+fn read_pose(pose: &web_sys::XrPose) -> XrPose {
+    let transform = pose.transform();
+
+    conversion::to_pose(
+        to_array(&transform.position()),
+        to_array(&transform.orientation()),
+        pose.emulated_position(),
+    )
+}
+
+fn read_gamepad(gamepad: &web_sys::Gamepad) -> WebXrGamepad {
+    WebXrGamepad {
+        buttons: gamepad
+            .buttons()
+            .iter()
+            .map(|button| {
+                let button = button.unchecked_into::<web_sys::GamepadButton>();
+
+                WebXrButton {
+                    pressed: button.pressed(),
+                    touched: button.touched(),
+                    value: button.value() as f32,
+                }
+            })
+            .collect(),
+        axes: gamepad
+            .axes()
+            .iter()
+            .map(|axis| axis.as_f64().unwrap_or(0.0) as f32)
+            .collect(),
+    }
+}
+
+fn read_input_source(
+    frame: &web_sys::XrFrame,
+    space: &web_sys::XrReferenceSpace,
+    source: &web_sys::XrInputSource,
+) -> WebXrInputSource {
+    WebXrInputSource {
+        handedness: match source.handedness() {
+            web_sys::XrHandedness::Left => Some(XrHandType::Left),
+            web_sys::XrHandedness::Right => Some(XrHandType::Right),
+            _ => None,
+        },
+        profiles: source
+            .profiles()
+            .iter()
+            .filter_map(|profile| profile.as_string())
+            .collect(),
+        target_ray: frame
+            .get_pose(&source.target_ray_space(), space)
+            .map(|pose| read_pose(&pose)),
+        grip: source
+            .grip_space()
+            .and_then(|grip_space| frame.get_pose(&grip_space, space))
+            .map(|pose| read_pose(&pose)),
+        gamepad: source.gamepad().map(|gamepad| read_gamepad(&gamepad)),
+    }
+}
+
+fn read_frame(
+    frame: &web_sys::XrFrame,
+    session: &web_sys::XrSession,
+    space: &web_sys::XrReferenceSpace,
+    reference_space_type: XrReferenceSpaceType,
+) -> WebXrFrame {
+    let viewer_pose = frame.get_viewer_pose(space);
+    let views = viewer_pose
+        .as_ref()
+        .map(|viewer_pose| {
+            viewer_pose
+                .views()
+                .iter()
+                .map(|view| {
+                    let transform = view.unchecked_into::<web_sys::XrView>().transform();
+
+                    conversion::to_pose(
+                        to_array(&transform.position()),
+                        to_array(&transform.orientation()),
+                        viewer_pose.emulated_position(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let input_sources = session.input_sources();
+    let input_sources = (0..input_sources.length())
+        .filter_map(|index| input_sources.get(index))
+        .map(|source| read_input_source(frame, space, &source))
+        .collect();
+
+    let bounds_geometry = space
+        .dyn_ref::<web_sys::XrBoundedReferenceSpace>()
+        .map(|space| {
+            space
+                .bounds_geometry()
+                .iter()
+                .map(|point| conversion::to_vec3(to_array(&point.unchecked_into())))
+                .collect()
+        });
+
+    WebXrFrame {
+        reference_space_type,
+        viewer: viewer_pose.as_ref().map(|pose| read_pose(pose)),
+        views,
+        input_sources,
+        bounds_geometry,
+    }
+}
+
+async fn request_reference_space(
+    session: &web_sys::XrSession,
+    reference_space_type: XrReferenceSpaceType,
+) -> Option<web_sys::XrReferenceSpace> {
+    let promise =
+        session.request_reference_space(to_web_reference_space_type(reference_space_type));
+
+    JsFuture::from(promise)
+        .await
+        .ok()
+        .map(|space| space.unchecked_into())
+}
+
+type AnimationFrameCallback = Closure<dyn FnMut(f64, web_sys::XrFrame)>;
+
+struct SessionState {
+    session: web_sys::XrSession,
+    reference_space: (web_sys::XrReferenceSpace, XrReferenceSpaceType),
+    // Kept alive until the session is replaced: the browser may still call them.
+    _animation_frame_callback: Rc<RefCell<Option<AnimationFrameCallback>>>,
+    _end_callback: Closure<dyn FnMut()>,
+}
+
+struct SharedState {
+    supported_modes: HashMap<WebXrSessionMode, bool>,
+    session_state: WebXrSessionState,
+    session: Option<SessionState>,
+    frame: Option<WebXrFrame>,
+}
+
+/// Backend using the browser `navigator.xr` object.
+pub struct WebSysBackend {
+    system: web_sys::XrSystem,
+    state: Rc<RefCell<SharedState>>,
+}
+
+impl WebSysBackend {
+    pub fn new() -> Self {
+        let system = web_sys::window()
+            .expect("WebXR requires a browser window")
+            .navigator()
+            .xr();
+        let state = Rc::new(RefCell::new(SharedState {
+            supported_modes: HashMap::new(),
+            session_state: WebXrSessionState::Idle,
+            session: None,
+            frame: None,
+        }));
+
+        for mode in [WebXrSessionMode::ImmersiveVr, WebXrSessionMode::ImmersiveAr]
+            .iter()
+            .copied()
+        {
+            let promise = system.is_session_supported(to_web_session_mode(mode));
+            let state = Rc::clone(&state);
+            spawn_local(async move {
+                let supported = JsFuture::from(promise)
+                    .await
+                    .ok()
+                    .and_then(|supported| supported.as_bool())
+                    .unwrap_or(false);
+                state.borrow_mut().supported_modes.insert(mode, supported);
+            });
+        }
+
+        Self { system, state }
+    }
+}
+
+impl Default for WebSysBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn start_session(
+    state: &Rc<RefCell<SharedState>>,
+    session: web_sys::XrSession,
+    reference_space: web_sys::XrReferenceSpace,
+    reference_space_type: XrReferenceSpaceType,
+    mode: WebXrSessionMode,
+) {
+    let end_callback = Closure::wrap(Box::new({
+        let state = Rc::clone(state);
+        move || {
+            let mut state = state.borrow_mut();
+            state.session_state = WebXrSessionState::Idle;
+            state.frame = None;
+        }
+    }) as Box<dyn FnMut()>);
+    session.set_onend(Some(end_callback.as_ref().unchecked_ref()));
+
+    // The callback requests the next animation frame itself, so it needs a handle to itself.
+    let animation_frame_callback: Rc<RefCell<Option<AnimationFrameCallback>>> =
+        Rc::new(RefCell::new(None));
+    *animation_frame_callback.borrow_mut() = Some(Closure::wrap(Box::new({
+        let state = Rc::clone(state);
+        let animation_frame_callback = Rc::downgrade(&animation_frame_callback);
+        move |_time: f64, frame: web_sys::XrFrame| {
+            let state = &mut *state.borrow_mut();
+            let session_state = match &state.session {
+                Some(session_state) => session_state,
+                None => return,
+            };
+
+            if let Some(callback) = animation_frame_callback.upgrade() {
+                if let Some(callback) = &*callback.borrow() {
+                    session_state
+                        .session
+                        .request_animation_frame(callback.as_ref().unchecked_ref());
+                }
+            }
+
+            let (space, reference_space_type) = &session_state.reference_space;
+            state.frame = Some(read_frame(
+                &frame,
+                &session_state.session,
+                space,
+                *reference_space_type,
+            ));
+        }
+    })
+        as Box<dyn FnMut(f64, web_sys::XrFrame)>));
+    if let Some(callback) = &*animation_frame_callback.borrow() {
+        session.request_animation_frame(callback.as_ref().unchecked_ref());
+    }
+
+    let mut state = state.borrow_mut();
+    state.session = Some(SessionState {
+        session,
+        reference_space: (reference_space, reference_space_type),
+        _animation_frame_callback: animation_frame_callback,
+        _end_callback: end_callback,
+    });
+    state.session_state = WebXrSessionState::Running(mode);
+}
+
+impl WebXrBackend for WebSysBackend {
+    fn is_session_mode_supported(&self, mode: WebXrSessionMode) -> Option<bool> {
+        if mode == WebXrSessionMode::Inline {
+            Some(true)
+        } else {
+            self.state.borrow().supported_modes.get(&mode).copied()
+        }
+    }
+
+    fn request_session(
+        &mut self,
+        mode: WebXrSessionMode,
+        reference_space_type: XrReferenceSpaceType,
+    ) {
+        let mut session_init = web_sys::XrSessionInit::new();
+        let optional_features = [XrReferenceSpaceType::Local, XrReferenceSpaceType::Stage]
+            .iter()
+            .map(|reference_space_type| {
+                JsValue::from_str(conversion::reference_space_name(*reference_space_type))
+            })
+            .collect::<Array>();
+        session_init.optional_features(&optional_features);
+
+        let promise = self
+            .system
+            .request_session_with_options(to_web_session_mode(mode), &session_init);
+        self.state.borrow_mut().session_state = WebXrSessionState::Requested;
+
+        let state = Rc::clone(&self.state);
+        spawn_local(async move {
+            let session = match JsFuture::from(promise).await {
+                Ok(session) => session.unchecked_into::<web_sys::XrSession>(),
+                Err(error) => {
+                    bevy_log::error!("Failed to request the WebXR session: {:?}", error);
+                    state.borrow_mut().session_state = WebXrSessionState::Idle;
+                    return;
+                }
+            };
+
+            match request_reference_space(&session, reference_space_type).await {
+                Some(reference_space) => {
+                    start_session(&state, session, reference_space, reference_space_type, mode)
+                }
+                None => {
+                    bevy_log::error!("{:?} reference space not supported", reference_space_type);
+                    let _ = session.end();
+                    state.borrow_mut().session_state = WebXrSessionState::Idle;
+                }
+            }
+        });
+    }
+
+    fn end_session(&mut self) {
+        if let Some(session_state) = &self.state.borrow().session {
+            let _ = session_state.session.end();
+        }
+    }
+
+    fn session_state(&self) -> WebXrSessionState {
+        self.state.borrow().session_state
+    }
+
+    fn set_reference_space_type(&mut self, reference_space_type: XrReferenceSpaceType) {
+        let session = match &self.state.borrow().session {
+            Some(session_state) => session_state.session.clone(),
+            None => return,
+        };
+
+        let state = Rc::clone(&self.state);
+        spawn_local(async move {
+            match request_reference_space(&session, reference_space_type).await {
+                Some(reference_space) => {
+                    if let Some(session_state) = &mut state.borrow_mut().session {
+                        session_state.reference_space = (reference_space, reference_space_type);
+                    }
+                }
+                None => {
+                    bevy_log::warn!("{:?} reference space not supported", reference_space_type);
+                }
+            }
+        });
+    }
+
+    fn poll_frame(&mut self) -> Option<WebXrFrame> {
+        self.state.borrow_mut().frame.take()
+    }
+}
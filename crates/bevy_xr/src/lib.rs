@@ -1,9 +1,10 @@
 pub mod interaction;
+pub mod locomotion;
 pub mod presentation;
 
 use bevy_ecs::system::Resource;
 pub use interaction::*;
-pub use presentation::XrVisibilityState;
+pub use presentation::{XrFrameTiming, XrResolutionScale, XrVisibilityState};
 
 use bevy_app::{App, Plugin};
 
@@ -15,22 +16,52 @@ pub enum XrSessionMode {
     InlineAR,
 }
 
+/// Sent by the app to ask the backend to start or end the XR session. The session is started with
+/// the mode selected with [`XrSystem::request_session_mode`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum XrSessionRequest {
+    Start,
+    End,
+}
+
+/// Sent by the backend when the XR session starts rendering or stops.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum XrSessionEvent {
+    Started(XrSessionMode),
+    Ended,
+}
+
 #[derive(Resource)]
 pub struct XrSystem {
     available_session_modes: Vec<XrSessionMode>,
     session_mode: XrSessionMode,
+    /// Whether `session_mode` was selected with [`XrSystem::request_session_mode`].
+    session_mode_requested: bool,
     action_set_desc: Vec<XrProfileDescriptor>,
+    session_running: bool,
 }
 
 impl XrSystem {
     pub fn new(available_session_modes: Vec<XrSessionMode>) -> Self {
         Self {
             session_mode: available_session_modes[0],
+            session_mode_requested: false,
             available_session_modes,
             action_set_desc: vec![],
+            session_running: false,
         }
     }
 
+    /// Returns true while the session is running, that is while XR cameras are being rendered.
+    pub fn is_session_running(&self) -> bool {
+        self.session_running
+    }
+
+    /// Used by backends to report the session state.
+    pub fn set_session_running(&mut self, running: bool) {
+        self.session_running = running;
+    }
+
     pub fn selected_session_mode(&self) -> XrSessionMode {
         self.session_mode
     }
@@ -39,6 +70,19 @@ impl XrSystem {
         self.available_session_modes.clone()
     }
 
+    /// Used by backends that discover the supported session modes asynchronously, with the
+    /// preferred mode first. The mode selected with [`XrSystem::request_session_mode`] is kept if
+    /// it is still available, otherwise the selected mode is reset to the first available mode.
+    /// It is left unchanged if no mode is available.
+    pub fn set_available_session_modes(&mut self, available_session_modes: Vec<XrSessionMode>) {
+        if !(self.session_mode_requested && available_session_modes.contains(&self.session_mode)) {
+            if let Some(&mode) = available_session_modes.first() {
+                self.session_mode = mode;
+            }
+        }
+        self.available_session_modes = available_session_modes;
+    }
+
     /// In case this method returns false, it may be either because the mode is not supported or
     /// currently not available.
     pub fn is_session_mode_supported(&self, mode: XrSessionMode) -> bool {
@@ -49,6 +93,7 @@ impl XrSystem {
     pub fn request_session_mode(&mut self, mode: XrSessionMode) -> bool {
         if self.is_session_mode_supported(mode) {
             self.session_mode = mode;
+            self.session_mode_requested = true;
 
             true
         } else {
@@ -71,6 +116,41 @@ pub struct XrPlugin;
 impl Plugin for XrPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<XrVibrationEvent>()
-            .init_resource::<XrProfiles>();
+            .add_event::<XrSessionRequest>()
+            .add_event::<XrSessionEvent>()
+            .init_resource::<XrProfiles>()
+            .init_resource::<XrResolutionScale>()
+            .init_resource::<XrFrameTiming>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_available_session_modes() {
+        let mut system = XrSystem::new(vec![XrSessionMode::InlineVR]);
+        system.set_available_session_modes(vec![]);
+        assert_eq!(system.selected_session_mode(), XrSessionMode::InlineVR);
+
+        // The preferred mode is selected until a mode is requested.
+        system
+            .set_available_session_modes(vec![XrSessionMode::ImmersiveVR, XrSessionMode::InlineVR]);
+        assert_eq!(system.selected_session_mode(), XrSessionMode::ImmersiveVR);
+        assert!(system.request_session_mode(XrSessionMode::InlineVR));
+        system
+            .set_available_session_modes(vec![XrSessionMode::ImmersiveVR, XrSessionMode::InlineVR]);
+        assert_eq!(system.selected_session_mode(), XrSessionMode::InlineVR);
+        assert!(system.request_session_mode(XrSessionMode::ImmersiveVR));
+
+        system.set_available_session_modes(vec![
+            XrSessionMode::ImmersiveAR,
+            XrSessionMode::ImmersiveVR,
+        ]);
+        assert_eq!(system.selected_session_mode(), XrSessionMode::ImmersiveVR);
+
+        system.set_available_session_modes(vec![XrSessionMode::InlineAR]);
+        assert_eq!(system.selected_session_mode(), XrSessionMode::InlineAR);
     }
 }
@@ -0,0 +1,309 @@
+//! Locomotion helpers operating on the pawn, the developer-owned root of the XR camera rig.
+//!
+//! Tracking data returned by [`XrTrackingSource`] is relative to the pawn, so moving or rotating
+//! the pawn [`Transform`] moves the whole rig (head, eyes and hands) through the world. The pawn is
+//! expected to stay upright: only its yaw is ever changed by these helpers.
+
+use crate::{XrRigidTransform, XrTrackingSource};
+use bevy_math::{Quat, Vec2, Vec3};
+use bevy_transform::components::Transform;
+
+/// Converts a pose reported by the tracking source into world space, given the pawn transform.
+pub fn pose_in_world(pawn: &Transform, pose: &XrRigidTransform) -> XrRigidTransform {
+    XrRigidTransform {
+        position: pawn.transform_point(pose.position),
+        orientation: pawn.rotation * pose.orientation,
+    }
+}
+
+/// Returns the head pose in world space.
+pub fn head_in_world(pawn: &Transform, tracking_source: &XrTrackingSource) -> XrRigidTransform {
+    pose_in_world(pawn, &tracking_source.viewer_target_ray().transform)
+}
+
+/// Returns the horizontal direction the head is facing in world space. Falls back to the pawn
+/// forward direction when looking straight up or down.
+pub fn head_forward(pawn: &Transform, tracking_source: &XrTrackingSource) -> Vec3 {
+    let forward = head_in_world(pawn, tracking_source).orientation * Vec3::NEG_Z;
+    let forward = Vec3::new(forward.x, 0.0, forward.z);
+    if forward.length_squared() > f32::EPSILON {
+        forward.normalize()
+    } else {
+        let forward = pawn.forward();
+        Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero()
+    }
+}
+
+/// Rotates the pawn by `angle` radians around the vertical axis passing through the head, so the
+/// user turns in place. Positive angles turn left.
+pub fn snap_turn(pawn: &mut Transform, tracking_source: &XrTrackingSource, angle: f32) {
+    let head_position = head_in_world(pawn, tracking_source).position;
+    pawn.rotate_around(head_position, Quat::from_rotation_y(angle));
+}
+
+/// Continuously rotates the pawn around the head. `axis` is usually the horizontal thumbstick
+/// value, where positive values turn right, and `angular_speed` is expressed in radians per second.
+pub fn smooth_turn(
+    pawn: &mut Transform,
+    tracking_source: &XrTrackingSource,
+    axis: f32,
+    angular_speed: f32,
+    delta_seconds: f32,
+) {
+    snap_turn(pawn, tracking_source, -axis * angular_speed * delta_seconds);
+}
+
+/// Moves the pawn on the horizontal plane relative to the head yaw: +Y on the thumbstick moves
+/// towards where the user is looking and +X strafes to the right. `speed` is expressed in meters
+/// per second.
+pub fn thumbstick_move(
+    pawn: &mut Transform,
+    tracking_source: &XrTrackingSource,
+    thumbstick: Vec2,
+    speed: f32,
+    delta_seconds: f32,
+) {
+    let forward = head_forward(pawn, tracking_source);
+    let right = forward.cross(Vec3::Y);
+    let thumbstick = thumbstick.clamp_length_max(1.0);
+
+    pawn.translation += (forward * thumbstick.y + right * thumbstick.x) * speed * delta_seconds;
+}
+
+/// Moves the pawn so that the head ends up vertically above `target` and the pawn origin (the
+/// tracking space floor) at `target` height.
+pub fn teleport(pawn: &mut Transform, tracking_source: &XrTrackingSource, target: Vec3) {
+    let head_position = head_in_world(pawn, tracking_source).position;
+
+    pawn.translation.x += target.x - head_position.x;
+    pawn.translation.z += target.z - head_position.z;
+    pawn.translation.y = target.y;
+}
+
+/// Parameters of the parabolic arc used to pick a teleport destination.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XrTeleportArc {
+    /// Initial speed along the target ray, in meters per second.
+    pub speed: f32,
+    /// Downward acceleration, in meters per second squared.
+    pub gravity: f32,
+    /// Simulated time between two arc points, in seconds.
+    pub time_step: f32,
+    /// Maximum number of arc segments tested before giving up.
+    pub max_segments: usize,
+}
+
+impl Default for XrTeleportArc {
+    fn default() -> Self {
+        Self {
+            speed: 7.0,
+            gravity: 9.81,
+            time_step: 0.05,
+            max_segments: 64,
+        }
+    }
+}
+
+/// Result of a successful teleport arc raycast.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XrTeleportHit {
+    /// Point where the arc hit the world.
+    pub point: Vec3,
+    /// Points of the arc, from the ray origin to `point` included. Useful to draw the arc.
+    pub arc: Vec<Vec3>,
+}
+
+impl XrTeleportArc {
+    /// Returns the arc point at time `t` for a ray starting at `origin`, along -Z.
+    pub fn point_at(&self, origin: &XrRigidTransform, t: f32) -> Vec3 {
+        let direction = origin.orientation * Vec3::NEG_Z;
+        origin.position + direction * self.speed * t + Vec3::NEG_Y * (0.5 * self.gravity * t * t)
+    }
+
+    /// Traces the arc starting from `origin` (usually a hand target ray in world space) and tests
+    /// each segment with `raycast`. `raycast` receives the start and end points of a segment and
+    /// returns the first hit point on that segment, if any.
+    pub fn cast(
+        &self,
+        origin: &XrRigidTransform,
+        mut raycast: impl FnMut(Vec3, Vec3) -> Option<Vec3>,
+    ) -> Option<XrTeleportHit> {
+        let mut arc = vec![origin.position];
+        for segment in 1..=self.max_segments {
+            let start = *arc.last().unwrap();
+            let end = self.point_at(origin, segment as f32 * self.time_step);
+            if let Some(point) = raycast(start, end) {
+                arc.push(point);
+                return Some(XrTeleportHit { point, arc });
+            }
+            arc.push(end);
+        }
+
+        None
+    }
+}
+
+/// Raycast function to use with [`XrTeleportArc::cast`] that hits a horizontal plane at `height`
+/// from above.
+pub fn horizontal_plane_raycast(height: f32) -> impl FnMut(Vec3, Vec3) -> Option<Vec3> {
+    move |start, end| {
+        if start.y >= height && end.y < height {
+            let t = (start.y - height) / (start.y - end.y);
+            Some(start.lerp(end, t))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        implementation::XrTrackingSourceBackend, XrJointPose, XrPose, XrReferenceSpaceType,
+    };
+    use std::f32::consts::FRAC_PI_2;
+
+    const EPSILON: f32 = 1e-4;
+
+    struct MockTrackingSource {
+        head: XrRigidTransform,
+    }
+
+    impl XrTrackingSourceBackend for MockTrackingSource {
+        fn reference_space_type(&self) -> XrReferenceSpaceType {
+            XrReferenceSpaceType::Stage
+        }
+
+        fn set_reference_space_type(&self, reference_space_type: XrReferenceSpaceType) -> bool {
+            reference_space_type == XrReferenceSpaceType::Stage
+        }
+
+        fn bounds_geometry(&self) -> Option<Vec<Vec3>> {
+            None
+        }
+
+        fn views_poses(&self) -> Vec<XrPose> {
+            [-0.03, 0.03]
+                .iter()
+                .map(|x| XrPose {
+                    transform: self.head
+                        * XrRigidTransform {
+                            position: Vec3::new(*x, 0.0, 0.0),
+                            orientation: Quat::IDENTITY,
+                        },
+                    ..Default::default()
+                })
+                .collect()
+        }
+
+        fn hands_pose(&self) -> [Option<XrPose>; 2] {
+            [None, None]
+        }
+
+        fn hands_skeleton_pose(&self) -> [Option<Vec<XrJointPose>>; 2] {
+            [None, None]
+        }
+
+        fn hands_target_ray(&self) -> [Option<XrPose>; 2] {
+            [None, None]
+        }
+
+        fn viewer_target_ray(&self) -> XrPose {
+            XrPose {
+                transform: self.head,
+                ..Default::default()
+            }
+        }
+    }
+
+    fn tracking_source(position: Vec3, yaw: f32) -> XrTrackingSource {
+        XrTrackingSource::new(Box::new(MockTrackingSource {
+            head: XrRigidTransform {
+                position,
+                orientation: Quat::from_rotation_y(yaw),
+            },
+        }))
+    }
+
+    #[test]
+    fn snap_turn_pivots_around_head() {
+        let tracking_source = tracking_source(Vec3::new(1.0, 1.7, 0.0), 0.0);
+        let mut pawn = Transform::from_xyz(2.0, 0.0, 3.0);
+        let head_before = head_in_world(&pawn, &tracking_source).position;
+
+        snap_turn(&mut pawn, &tracking_source, FRAC_PI_2);
+
+        let head_after = head_in_world(&pawn, &tracking_source).position;
+        assert!(head_before.abs_diff_eq(head_after, EPSILON));
+        assert!(head_forward(&pawn, &tracking_source).abs_diff_eq(Vec3::NEG_X, EPSILON));
+    }
+
+    #[test]
+    fn smooth_turn_scales_with_time() {
+        let tracking_source = tracking_source(Vec3::ZERO, 0.0);
+        let mut pawn = Transform::default();
+
+        for _ in 0..10 {
+            smooth_turn(&mut pawn, &tracking_source, 1.0, FRAC_PI_2, 0.1);
+        }
+
+        assert!(head_forward(&pawn, &tracking_source).abs_diff_eq(Vec3::X, EPSILON));
+    }
+
+    #[test]
+    fn thumbstick_move_follows_head_yaw() {
+        // Head looking to the left of the pawn, pawn itself rotated to the left.
+        let tracking_source = tracking_source(Vec3::new(0.0, 1.7, 0.0), FRAC_PI_2);
+        let mut pawn = Transform::from_rotation(Quat::from_rotation_y(FRAC_PI_2));
+
+        thumbstick_move(&mut pawn, &tracking_source, Vec2::Y, 2.0, 0.5);
+        assert!(pawn.translation.abs_diff_eq(Vec3::Z, EPSILON));
+
+        thumbstick_move(&mut pawn, &tracking_source, Vec2::X, 2.0, 0.5);
+        assert!(pawn
+            .translation
+            .abs_diff_eq(Vec3::new(-1.0, 0.0, 1.0), EPSILON));
+    }
+
+    #[test]
+    fn teleport_places_head_above_target() {
+        let tracking_source = tracking_source(Vec3::new(0.5, 1.7, -0.25), 0.3);
+        let mut pawn = Transform::from_rotation(Quat::from_rotation_y(1.0));
+        let target = Vec3::new(4.0, 1.0, -2.0);
+
+        teleport(&mut pawn, &tracking_source, target);
+
+        let head = head_in_world(&pawn, &tracking_source).position;
+        assert!((head.x - target.x).abs() < EPSILON);
+        assert!((head.z - target.z).abs() < EPSILON);
+        assert!((pawn.translation.y - target.y).abs() < EPSILON);
+    }
+
+    #[test]
+    fn teleport_arc_hits_ground() {
+        let arc = XrTeleportArc::default();
+        let origin = XrRigidTransform {
+            position: Vec3::new(0.0, 1.0, 0.0),
+            orientation: Quat::from_rotation_x(0.5),
+        };
+
+        let hit = arc.cast(&origin, horizontal_plane_raycast(0.0)).unwrap();
+        assert!(hit.point.y.abs() < EPSILON);
+        assert!(hit.point.z < -1.0);
+        assert!(hit.point.x.abs() < EPSILON);
+        assert_eq!(*hit.arc.first().unwrap(), origin.position);
+        assert_eq!(*hit.arc.last().unwrap(), hit.point);
+
+        let upwards = XrRigidTransform {
+            position: Vec3::new(0.0, 1.0, 0.0),
+            orientation: Quat::IDENTITY,
+        };
+        let miss = XrTeleportArc {
+            max_segments: 2,
+            ..Default::default()
+        }
+        .cast(&upwards, horizontal_plane_raycast(0.0));
+        assert_eq!(miss, None);
+    }
+}
@@ -1,4 +1,6 @@
 use bevy_ecs::system::Resource;
+use bevy_math::UVec2;
+use bevy_utils::Duration;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use wgpu::AdapterInfo;
@@ -38,3 +40,129 @@ pub struct XrGraphicsContext {
 pub trait XrPresentationSession: Send + Sync + 'static {
     fn get_swapchains(&mut self) -> Vec<Vec<u64>>;
 }
+
+/// Timing of the frame being simulated, as predicted by the runtime. Updated every frame while the
+/// session is running.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Resource)]
+pub struct XrFrameTiming {
+    /// Time at which the frame will be displayed, relative to an arbitrary runtime-specific epoch.
+    pub predicted_display_time: Duration,
+    /// Predicted duration between two displayed frames.
+    pub predicted_display_period: Duration,
+}
+
+/// Scale applied to both dimensions of the recommended eye render target resolution. Rendering at
+/// a lower resolution trades sharpness for GPU time, which is used to keep a stable frame rate on
+/// mobile headsets.
+#[derive(Clone, Copy, PartialEq, Debug, Resource)]
+pub struct XrResolutionScale {
+    /// Current scale, kept in `min_scale..=max_scale` when adjusted dynamically.
+    pub scale: f32,
+    pub min_scale: f32,
+    pub max_scale: f32,
+    /// When set, the backend adjusts `scale` every frame to keep the frame time under this target.
+    /// Otherwise `scale` is only changed manually.
+    pub target_frame_time: Option<Duration>,
+}
+
+impl Default for XrResolutionScale {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            target_frame_time: None,
+        }
+    }
+}
+
+impl XrResolutionScale {
+    /// A scale that is never changed by the backend.
+    pub fn fixed(scale: f32) -> Self {
+        Self {
+            scale,
+            ..Default::default()
+        }
+    }
+
+    /// A scale adjusted by the backend to keep the frame time under `target_frame_time`.
+    pub fn dynamic(target_frame_time: Duration) -> Self {
+        Self {
+            target_frame_time: Some(target_frame_time),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the scaled size of a render target of size `extent`. The result is never empty.
+    pub fn apply(&self, extent: UVec2) -> UVec2 {
+        let scale = self.scale.clamp(0.0, 1.0);
+        (extent.as_vec2() * scale)
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE)
+    }
+
+    /// Adjusts `scale` according to the last measured frame time. Does nothing if there is no
+    /// `target_frame_time`.
+    pub fn update(&mut self, frame_time: Duration) {
+        let target_frame_time = match self.target_frame_time {
+            Some(target_frame_time) if !frame_time.is_zero() => target_frame_time,
+            _ => return,
+        };
+
+        // When the frame rate is locked to the display, the frame time never goes below the
+        // target, so the scale is slowly increased as long as the target is met.
+        let (desired_scale, rate) = if frame_time <= target_frame_time {
+            (self.max_scale, 0.05)
+        } else {
+            // The frame cost is roughly proportional to the pixel count, that is to the square of
+            // the scale. Decrease quickly to avoid dropping frames.
+            let ratio = target_frame_time.as_secs_f32() / frame_time.as_secs_f32();
+            (self.scale * ratio.sqrt(), 0.5)
+        };
+
+        self.scale += (desired_scale - self.scale) * rate;
+        self.scale = self.scale.clamp(self.min_scale, self.max_scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_scale_apply() {
+        let extent = UVec2::new(1832, 1920);
+        assert_eq!(XrResolutionScale::default().apply(extent), extent);
+        assert_eq!(
+            XrResolutionScale::fixed(0.5).apply(extent),
+            UVec2::new(916, 960)
+        );
+        assert_eq!(XrResolutionScale::fixed(0.0).apply(extent), UVec2::ONE);
+    }
+
+    #[test]
+    fn resolution_scale_follows_frame_time() {
+        let target = Duration::from_micros(13_889);
+        let mut scale = XrResolutionScale::dynamic(target);
+
+        for _ in 0..100 {
+            scale.update(target * 2);
+        }
+        assert_eq!(scale.scale, scale.min_scale);
+
+        scale.update(target);
+        let increased = scale.scale;
+        assert!(increased > scale.min_scale);
+
+        for _ in 0..200 {
+            scale.update(target);
+        }
+        assert!(scale.scale > increased);
+        assert!(scale.scale <= scale.max_scale);
+
+        let mut fixed = XrResolutionScale::fixed(0.8);
+        fixed.update(target * 2);
+        assert_eq!(fixed.scale, 0.8);
+    }
+}
@@ -17,5 +17,7 @@ fn main() {
         // .add_plugin(bevy::diagnostic::EntityCountDiagnosticsPlugin::default())
         // Uncomment this to add an asset count diagnostics:
         // .add_plugin(bevy::asset::diagnostic::AssetCountDiagnosticsPlugin::<Texture>::default())
+        // Uncomment this to add the time spent in each system and stage, and log the slowest ones:
+        // .add_plugin(bevy::diagnostic::SystemTimingDiagnosticsPlugin::default())
         .run();
 }
@@ -4,8 +4,8 @@ use bevy::{
     prelude::*,
     utils::Duration,
     xr::{
-        XrActionSet, XrHandType, XrReferenceSpaceType, XrSessionMode, XrSystem, XrTrackingSource,
-        XrVibrationEvent, XrVibrationEventType,
+        locomotion, XrActionSet, XrHandType, XrReferenceSpaceType, XrSessionMode, XrSystem,
+        XrTrackingSource, XrVibrationEvent, XrVibrationEventType,
     },
     DefaultPlugins,
 };
@@ -21,6 +21,7 @@ fn main() {
         .add_startup_system(startup)
         .add_startup_system(init_camera_position)
         .add_system(interaction)
+        .add_system(move_pawn)
         // .add_system(dummy)
         .run();
 }
@@ -47,6 +48,40 @@ fn init_camera_position(mut q: Query<(&mut Transform, &mut GlobalTransform, &XrP
 #[derive(Component)]
 struct CubeMarker;
 
+// Move with the left thumbstick, snap turn with the right thumbstick.
+fn move_pawn(
+    time: Res<Time>,
+    action_set: Option<Res<XrActionSet>>,
+    tracking_source: Res<XrTrackingSource>,
+    mut pawn: Query<&mut Transform, With<XrPawn>>,
+    mut turning: Local<bool>,
+) {
+    let (action_set, mut pawn) = match (action_set, pawn.get_single_mut()) {
+        (Some(action_set), Ok(pawn)) => (action_set, pawn),
+        _ => return,
+    };
+
+    locomotion::thumbstick_move(
+        &mut pawn,
+        &tracking_source,
+        action_set.vec_2d_value("left_thumbstick"),
+        1.5,
+        time.delta_seconds(),
+    );
+
+    let turn = action_set.vec_2d_value("right_thumbstick").x;
+    if turn.abs() > 0.7 && !*turning {
+        locomotion::snap_turn(
+            &mut pawn,
+            &tracking_source,
+            -turn.signum() * std::f32::consts::FRAC_PI_4,
+        );
+        *turning = true;
+    } else if turn.abs() < 0.3 {
+        *turning = false;
+    }
+}
+
 fn startup(
     mut c: Commands,
     mut xr_system: ResMut<XrSystem>,